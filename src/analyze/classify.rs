@@ -6,9 +6,26 @@ pub enum EntryCategory {
     Metadata,
     ClojureSource,
     JavaSource,
+    /// Dependency JAR nested in a Spring Boot or WAR archive
+    NestedJar,
+    /// Spring Boot launcher classes
+    BootLoader,
+}
+
+/// Path inside the application, without Spring Boot's `BOOT-INF/classes/` prefix.
+pub fn app_path(name: &str) -> &str {
+    name.strip_prefix(crate::boot::CLASSES_PREFIX)
+        .unwrap_or(name)
 }
 
 pub fn classify_entry(name: &str) -> EntryCategory {
+    if crate::boot::is_nested_jar(name) {
+        return EntryCategory::NestedJar;
+    }
+    if name.starts_with(crate::boot::LOADER_PREFIX) {
+        return EntryCategory::BootLoader;
+    }
+    let name = app_path(name);
     if name.ends_with(".class") {
         return EntryCategory::Class;
     }
@@ -32,6 +49,7 @@ pub fn classify_entry(name: &str) -> EntryCategory {
 }
 
 pub fn extract_package(name: &str) -> String {
+    let name = app_path(name);
     let parts: Vec<&str> = name.split('/').collect();
     let depth = parts.len().saturating_sub(1).min(3);
     if depth == 0 {
@@ -50,6 +68,30 @@ pub fn detect_clojure_ns(name: &str) -> Option<String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn classify_spring_boot_entries() {
+        assert_eq!(
+            classify_entry("BOOT-INF/lib/spring-core-6.1.2.jar"),
+            EntryCategory::NestedJar
+        );
+        assert_eq!(
+            classify_entry("org/springframework/boot/loader/launch/JarLauncher.class"),
+            EntryCategory::BootLoader
+        );
+        assert_eq!(
+            classify_entry("BOOT-INF/classes/com/example/App.class"),
+            EntryCategory::Class
+        );
+        assert_eq!(
+            classify_entry("BOOT-INF/classes/META-INF/spring.factories"),
+            EntryCategory::Metadata
+        );
+        assert_eq!(
+            extract_package("BOOT-INF/classes/com/example/web/Api.class"),
+            "com.example.web"
+        );
+    }
+
     #[test]
     fn classify_class_files() {
         assert_eq!(
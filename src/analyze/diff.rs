@@ -0,0 +1,558 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::io::Read;
+use std::path::Path;
+
+use indicatif::HumanBytes;
+use serde::Serialize;
+
+use super::{
+    analyze_jar_with_limit, format_number, resolve_input_jar, AnalysisReport, CategoryStats,
+    GroupStats, SCHEMA_VERSION, TOP_N,
+};
+use crate::error::PackError;
+
+/// Limit on how much the JAR may grow relative to the baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MaxGrowth {
+    Bytes(u64),
+    Percent(f64),
+}
+
+impl MaxGrowth {
+    /// Parses a size (`5MB`) or a percentage (`10%`).
+    pub fn from_str(s: &str) -> Result<Self, PackError> {
+        match s.trim().strip_suffix('%') {
+            Some(pct) => pct
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|p| *p >= 0.0)
+                .map(MaxGrowth::Percent)
+                .ok_or_else(|| PackError::InvalidMaxGrowth(s.to_string())),
+            None => crate::config::parse_size(s)
+                .map(MaxGrowth::Bytes)
+                .map_err(|_| PackError::InvalidMaxGrowth(s.to_string())),
+        }
+    }
+
+    fn exceeded_by(&self, old: u64, new: u64) -> bool {
+        let growth = new.saturating_sub(old);
+        match self {
+            MaxGrowth::Bytes(limit) => growth > *limit,
+            MaxGrowth::Percent(limit) => {
+                if old == 0 {
+                    growth > 0
+                } else {
+                    growth as f64 / old as f64 * 100.0 > *limit
+                }
+            }
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            MaxGrowth::Bytes(limit) => HumanBytes(*limit).to_string(),
+            MaxGrowth::Percent(limit) => format!("{limit}%"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SizeDelta {
+    pub old: u64,
+    pub new: u64,
+    pub delta: i64,
+}
+
+impl SizeDelta {
+    fn new(old: u64, new: u64) -> Self {
+        SizeDelta {
+            old,
+            new,
+            delta: new as i64 - old as i64,
+        }
+    }
+}
+
+/// Size change of a category, package or Clojure namespace
+#[derive(Debug, Serialize)]
+pub struct GroupDelta {
+    pub name: String,
+    pub old_size: u64,
+    pub new_size: u64,
+    pub delta: i64,
+    pub old_files: usize,
+    pub new_files: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EntryChange {
+    pub name: String,
+    pub size: u64,
+}
+
+/// Entries present in only one of the JARs
+#[derive(Debug, Serialize)]
+pub struct EntryChanges {
+    pub count: usize,
+    pub size: u64,
+    /// The largest entries, up to 20
+    pub largest: Vec<EntryChange>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnalysisDiff {
+    pub baseline: String,
+    pub current: String,
+    pub disk_size: SizeDelta,
+    pub total_uncompressed: SizeDelta,
+    pub entry_count: SizeDelta,
+    pub categories: Vec<GroupDelta>,
+    pub packages: Vec<GroupDelta>,
+    pub clojure_namespaces: Vec<GroupDelta>,
+    pub added_entries: EntryChanges,
+    pub removed_entries: EntryChanges,
+    pub duplicate_classes_added: Vec<String>,
+    pub duplicate_classes_removed: Vec<String>,
+}
+
+impl AnalysisDiff {
+    /// Fails when the JAR grew past `limit`.
+    pub fn check_growth(&self, limit: MaxGrowth) -> Result<(), PackError> {
+        if limit.exceeded_by(self.disk_size.old, self.disk_size.new) {
+            return Err(PackError::GrowthExceeded {
+                growth: signed_bytes(self.disk_size.delta),
+                limit: limit.describe(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Analyzes a JAR, project directory or jbundle binary (its app layer) with
+/// every package and namespace kept, for comparison.
+pub fn analyze_for_diff(input: &Path) -> Result<AnalysisReport, PackError> {
+    if input.is_dir() {
+        let jar_path = resolve_input_jar(input)?;
+        return analyze_jar_with_limit(&jar_path, None);
+    }
+    let mut magic = [0u8; 4];
+    let is_zip = std::fs::File::open(input)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .is_ok()
+        && magic == *b"PK\x03\x04";
+    if is_zip {
+        return analyze_jar_with_limit(input, None);
+    }
+
+    let jar = crate::pack::layout::read_app_jar(input)?;
+    let temp = tempfile::NamedTempFile::new()?;
+    std::fs::write(temp.path(), &jar)?;
+
+    let mut report = analyze_jar_with_limit(temp.path(), None)?;
+    report.jar_path = format!("{} (app layer)", input.display());
+    Ok(report)
+}
+
+pub fn diff_reports(old: &AnalysisReport, new: &AnalysisReport) -> AnalysisDiff {
+    let categories = group_deltas(
+        &to_groups(&old.categories),
+        &to_groups(&new.categories),
+        None,
+    );
+    let packages = group_deltas(&old.top_packages, &new.top_packages, Some(TOP_N));
+    let clojure_namespaces = group_deltas(
+        &old.clojure_namespaces,
+        &new.clojure_namespaces,
+        Some(TOP_N),
+    );
+
+    let old_dups: BTreeSet<&String> = old.duplicate_classes.iter().collect();
+    let new_dups: BTreeSet<&String> = new.duplicate_classes.iter().collect();
+
+    AnalysisDiff {
+        baseline: old.jar_path.clone(),
+        current: new.jar_path.clone(),
+        disk_size: SizeDelta::new(old.disk_size, new.disk_size),
+        total_uncompressed: SizeDelta::new(old.total_uncompressed, new.total_uncompressed),
+        entry_count: SizeDelta::new(old.entry_count as u64, new.entry_count as u64),
+        categories,
+        packages,
+        clojure_namespaces,
+        added_entries: entry_changes(&new.entries, &old.entries),
+        removed_entries: entry_changes(&old.entries, &new.entries),
+        duplicate_classes_added: new_dups
+            .difference(&old_dups)
+            .map(|s| s.to_string())
+            .collect(),
+        duplicate_classes_removed: old_dups
+            .difference(&new_dups)
+            .map(|s| s.to_string())
+            .collect(),
+    }
+}
+
+fn to_groups(categories: &[CategoryStats]) -> Vec<GroupStats> {
+    categories
+        .iter()
+        .map(|c| GroupStats {
+            name: c.name.clone(),
+            size: c.size,
+            file_count: c.file_count,
+        })
+        .collect()
+}
+
+type SizeAndFiles = (u64, usize);
+
+/// Groups that changed, largest change first.
+fn group_deltas(old: &[GroupStats], new: &[GroupStats], limit: Option<usize>) -> Vec<GroupDelta> {
+    // name -> ((old size, old files), (new size, new files))
+    let mut merged: BTreeMap<&str, (SizeAndFiles, SizeAndFiles)> = BTreeMap::new();
+    for g in old {
+        merged.entry(&g.name).or_default().0 = (g.size, g.file_count);
+    }
+    for g in new {
+        merged.entry(&g.name).or_default().1 = (g.size, g.file_count);
+    }
+
+    let mut deltas: Vec<GroupDelta> = merged
+        .into_iter()
+        .filter(|(_, (o, n))| o != n)
+        .map(
+            |(name, ((old_size, old_files), (new_size, new_files)))| GroupDelta {
+                name: name.to_string(),
+                old_size,
+                new_size,
+                delta: new_size as i64 - old_size as i64,
+                old_files,
+                new_files,
+            },
+        )
+        .collect();
+    deltas.sort_by(|a, b| {
+        b.delta
+            .unsigned_abs()
+            .cmp(&a.delta.unsigned_abs())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    if let Some(limit) = limit {
+        deltas.truncate(limit);
+    }
+    deltas
+}
+
+/// Entries of `a` missing from `b`.
+fn entry_changes(a: &BTreeMap<String, u64>, b: &BTreeMap<String, u64>) -> EntryChanges {
+    let mut only: Vec<EntryChange> = a
+        .iter()
+        .filter(|(name, _)| !b.contains_key(*name))
+        .map(|(name, size)| EntryChange {
+            name: name.clone(),
+            size: *size,
+        })
+        .collect();
+    let count = only.len();
+    let size = only.iter().map(|e| e.size).sum();
+    only.sort_by(|x, y| y.size.cmp(&x.size).then_with(|| x.name.cmp(&y.name)));
+    only.truncate(TOP_N);
+    EntryChanges {
+        count,
+        size,
+        largest: only,
+    }
+}
+
+fn signed_bytes(delta: i64) -> String {
+    match delta {
+        0 => "0 B".to_string(),
+        d if d > 0 => format!("+{}", HumanBytes(d as u64)),
+        d => format!("-{}", HumanBytes(d.unsigned_abs())),
+    }
+}
+
+fn signed_percent(delta: &SizeDelta) -> String {
+    if delta.old == 0 {
+        return String::new();
+    }
+    format!(" ({:+.1}%)", delta.delta as f64 / delta.old as f64 * 100.0)
+}
+
+pub fn render_diff(diff: &AnalysisDiff) {
+    eprintln!();
+    eprintln!(
+        "Baseline: {} ({})",
+        diff.baseline,
+        HumanBytes(diff.disk_size.old)
+    );
+    eprintln!(
+        "Current:  {} ({})",
+        diff.current,
+        HumanBytes(diff.disk_size.new)
+    );
+    eprintln!(
+        "Change:   {}{} on disk, {}{} uncompressed, {:+} entries",
+        signed_bytes(diff.disk_size.delta),
+        signed_percent(&diff.disk_size),
+        signed_bytes(diff.total_uncompressed.delta),
+        signed_percent(&diff.total_uncompressed),
+        diff.entry_count.delta
+    );
+    eprintln!();
+
+    for (title, groups) in [
+        ("Categories", &diff.categories),
+        ("Packages with the largest changes", &diff.packages),
+        (
+            "Clojure namespaces with the largest changes",
+            &diff.clojure_namespaces,
+        ),
+    ] {
+        if groups.is_empty() {
+            continue;
+        }
+        eprintln!("{title}:");
+        for g in groups {
+            eprintln!(
+                "  {:<35} {:>12}  {} \u{2192} {} files",
+                g.name,
+                signed_bytes(g.delta),
+                format_number(g.old_files),
+                format_number(g.new_files),
+            );
+        }
+        eprintln!();
+    }
+
+    for (title, changes) in [
+        ("Added entries", &diff.added_entries),
+        ("Removed entries", &diff.removed_entries),
+    ] {
+        if changes.count == 0 {
+            continue;
+        }
+        eprintln!(
+            "{title}: {} ({})",
+            format_number(changes.count),
+            HumanBytes(changes.size)
+        );
+        for e in &changes.largest {
+            eprintln!("  {:<50} {:>10}", e.name, HumanBytes(e.size));
+        }
+        if changes.count > changes.largest.len() {
+            eprintln!("  ... and {} more", changes.count - changes.largest.len());
+        }
+        eprintln!();
+    }
+
+    for (title, classes) in [
+        ("New duplicate classes", &diff.duplicate_classes_added),
+        (
+            "Resolved duplicate classes",
+            &diff.duplicate_classes_removed,
+        ),
+    ] {
+        if classes.is_empty() {
+            continue;
+        }
+        eprintln!("{title}:");
+        for class in classes {
+            eprintln!("  {class}");
+        }
+        eprintln!();
+    }
+}
+
+#[derive(Serialize)]
+struct JsonDiff<'a> {
+    schema_version: u32,
+    #[serde(flatten)]
+    diff: &'a AnalysisDiff,
+}
+
+pub fn render_diff_json(diff: &AnalysisDiff) -> String {
+    let json = JsonDiff {
+        schema_version: SCHEMA_VERSION,
+        diff,
+    };
+    serde_json::to_string_pretty(&json).expect("analysis diff serializes")
+}
+
+pub fn render_diff_markdown(diff: &AnalysisDiff) -> String {
+    let mut md = String::new();
+    let _ = writeln!(
+        md,
+        "### JAR size change: {}",
+        signed_bytes(diff.disk_size.delta)
+    );
+    let _ = writeln!(md);
+    let _ = writeln!(
+        md,
+        "{} \u{2192} {}{} on disk, {} \u{2192} {} entries",
+        HumanBytes(diff.disk_size.old),
+        HumanBytes(diff.disk_size.new),
+        signed_percent(&diff.disk_size),
+        format_number(diff.entry_count.old as usize),
+        format_number(diff.entry_count.new as usize),
+    );
+    let _ = writeln!(md);
+
+    for (title, groups) in [
+        ("Categories", &diff.categories),
+        ("Packages", &diff.packages),
+        ("Clojure namespaces", &diff.clojure_namespaces),
+    ] {
+        if groups.is_empty() {
+            continue;
+        }
+        let _ = writeln!(md, "| {title} | Baseline | Current | Change |");
+        let _ = writeln!(md, "|---|---:|---:|---:|");
+        for g in groups {
+            let _ = writeln!(
+                md,
+                "| `{}` | {} | {} | {} |",
+                g.name,
+                HumanBytes(g.old_size),
+                HumanBytes(g.new_size),
+                signed_bytes(g.delta)
+            );
+        }
+        let _ = writeln!(md);
+    }
+
+    for (title, changes) in [
+        ("Added entries", &diff.added_entries),
+        ("Removed entries", &diff.removed_entries),
+    ] {
+        if changes.count == 0 {
+            continue;
+        }
+        let _ = writeln!(
+            md,
+            "<details><summary>{title}: {} ({})</summary>",
+            format_number(changes.count),
+            HumanBytes(changes.size)
+        );
+        let _ = writeln!(md);
+        for e in &changes.largest {
+            let _ = writeln!(md, "- `{}` ({})", e.name, HumanBytes(e.size));
+        }
+        let _ = writeln!(md);
+        let _ = writeln!(md, "</details>");
+        let _ = writeln!(md);
+    }
+
+    if !diff.duplicate_classes_added.is_empty() {
+        let _ = writeln!(md, "**New duplicate classes**");
+        let _ = writeln!(md);
+        for class in &diff.duplicate_classes_added {
+            let _ = writeln!(md, "- `{class}`");
+        }
+        let _ = writeln!(md);
+    }
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write as IoWrite;
+    use tempfile::NamedTempFile;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn create_test_jar(entries: &[(&str, &[u8])]) -> NamedTempFile {
+        let file = NamedTempFile::new().unwrap();
+        let mut zip = ZipWriter::new(file.reopen().unwrap());
+        let options = SimpleFileOptions::default();
+        for (name, content) in entries {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap();
+        file
+    }
+
+    #[test]
+    fn diff_reports_changes() {
+        let old = create_test_jar(&[
+            ("com/example/Main.class", b"main"),
+            ("org/legacy/util/Old.class", b"old class"),
+        ]);
+        let big = vec![7u8; 4096];
+        let new = create_test_jar(&[
+            ("com/example/Main.class", b"main, but longer"),
+            ("org/apache/poi/Workbook.class", &big),
+            ("myapp/core__init.class", b"init"),
+        ]);
+        let old = analyze_for_diff(old.path()).unwrap();
+        let new = analyze_for_diff(new.path()).unwrap();
+        let diff = diff_reports(&old, &new);
+
+        assert_eq!(diff.entry_count.delta, 1);
+        assert_eq!(diff.packages[0].name, "org.apache.poi");
+        assert_eq!(diff.packages[0].delta, 4096);
+        assert!(diff
+            .packages
+            .iter()
+            .any(|p| p.name == "org.legacy.util" && p.new_files == 0));
+        assert_eq!(diff.clojure_namespaces[0].name, "myapp.core");
+
+        assert_eq!(diff.added_entries.count, 2);
+        assert_eq!(
+            diff.added_entries.largest[0].name,
+            "org/apache/poi/Workbook.class"
+        );
+        assert_eq!(diff.removed_entries.count, 1);
+        assert_eq!(diff.removed_entries.size, 9);
+
+        let classes = diff
+            .categories
+            .iter()
+            .find(|c| c.name == "Classes")
+            .unwrap();
+        assert_eq!((classes.old_files, classes.new_files), (2, 3));
+
+        render_diff(&diff);
+        let json: serde_json::Value = serde_json::from_str(&render_diff_json(&diff)).unwrap();
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+        assert_eq!(json["added_entries"]["count"], 2);
+        assert!(render_diff_markdown(&diff).contains("| `org.apache.poi` |"));
+    }
+
+    #[test]
+    fn diff_duplicate_classes() {
+        let dup = |n: usize| {
+            let mut report = analyze_jar_with_limit(create_test_jar(&[]).path(), None).unwrap();
+            report.duplicate_classes = (0..n).map(|i| format!("a/C{i}.class")).collect();
+            report
+        };
+        let diff = diff_reports(&dup(1), &dup(3));
+        assert_eq!(
+            diff.duplicate_classes_added,
+            vec!["a/C1.class", "a/C2.class"]
+        );
+        assert!(diff.duplicate_classes_removed.is_empty());
+    }
+
+    #[test]
+    fn max_growth_limits() {
+        assert_eq!(
+            MaxGrowth::from_str("5MB").unwrap(),
+            MaxGrowth::Bytes(5 * 1024 * 1024)
+        );
+        assert_eq!(
+            MaxGrowth::from_str("10%").unwrap(),
+            MaxGrowth::Percent(10.0)
+        );
+        assert!(MaxGrowth::from_str("-5%").is_err());
+        assert!(MaxGrowth::from_str("lots").is_err());
+
+        assert!(MaxGrowth::Percent(10.0).exceeded_by(100, 111));
+        assert!(!MaxGrowth::Percent(10.0).exceeded_by(100, 110));
+        assert!(!MaxGrowth::Percent(10.0).exceeded_by(100, 50));
+        assert!(MaxGrowth::Bytes(1024).exceeded_by(0, 1025));
+        assert!(!MaxGrowth::Bytes(1024).exceeded_by(4096, 5120));
+    }
+}
@@ -0,0 +1,202 @@
+use std::io::{Read, Seek};
+use std::path::Path;
+
+use zip::ZipArchive;
+
+use crate::error::PackError;
+
+/// A dependency identified by the `pom.properties` Maven writes into every
+/// JAR it builds (`META-INF/maven/<group>/<artifact>/pom.properties`).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MavenCoordinate {
+    pub group_id: String,
+    pub artifact_id: String,
+    pub version: String,
+}
+
+impl MavenCoordinate {
+    /// Package URL, e.g. `pkg:maven/org.clojure/clojure@1.12.0`.
+    pub fn purl(&self) -> String {
+        format!(
+            "pkg:maven/{}/{}@{}",
+            self.group_id, self.artifact_id, self.version
+        )
+    }
+}
+
+impl std::fmt::Display for MavenCoordinate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.group_id, self.artifact_id, self.version)
+    }
+}
+
+/// Collects the Maven coordinates of every artifact merged into a JAR,
+/// sorted and deduplicated. Spring Boot JARs keep their dependencies as
+/// nested JARs, which are read as well. Shrinking removes these entries,
+/// so read them from the original uberjar.
+pub fn read_coordinates(jar_path: &Path) -> Result<Vec<MavenCoordinate>, PackError> {
+    let file = std::fs::File::open(jar_path)
+        .map_err(|e| PackError::AnalyzeFailed(format!("cannot open JAR: {e}")))?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| PackError::AnalyzeFailed(format!("cannot read JAR: {e}")))?;
+
+    let mut coordinates = archive_coordinates(&mut archive)?;
+    for name in crate::boot::nested_jar_names(&archive) {
+        if let Some(mut nested) = crate::boot::open_nested(&mut archive, &name)? {
+            coordinates.extend(archive_coordinates(&mut nested)?);
+        }
+    }
+    coordinates.sort();
+    coordinates.dedup();
+    Ok(coordinates)
+}
+
+/// Coordinates from the `pom.properties` entries of one archive, without
+/// looking into nested JARs.
+pub fn archive_coordinates<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+) -> Result<Vec<MavenCoordinate>, PackError> {
+    let names: Vec<String> = archive
+        .file_names()
+        .filter(|n| n.starts_with("META-INF/maven/") && n.ends_with("/pom.properties"))
+        .map(str::to_string)
+        .collect();
+
+    let mut coordinates = Vec::new();
+    for name in names {
+        let mut content = String::new();
+        archive
+            .by_name(&name)
+            .map_err(|e| PackError::AnalyzeFailed(format!("zip entry error: {e}")))?
+            .read_to_string(&mut content)?;
+        if let Some(coordinate) = parse_pom_properties(&content) {
+            coordinates.push(coordinate);
+        }
+    }
+    Ok(coordinates)
+}
+
+fn parse_pom_properties(content: &str) -> Option<MavenCoordinate> {
+    let value = |key: &str| {
+        content.lines().find_map(|line| {
+            let (k, v) = line.split_once('=')?;
+            (k.trim() == key).then(|| v.trim().to_string())
+        })
+    };
+    Some(MavenCoordinate {
+        group_id: value("groupId")?,
+        artifact_id: value("artifactId")?,
+        version: value("version")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    #[test]
+    fn parse_pom_properties_ignores_comments() {
+        let content = "#Generated by Maven\n#Tue Jan 02 10:00:00 UTC 2024\n\
+                       artifactId=guava\ngroupId=com.google.guava\nversion=33.0.0-jre\n";
+        assert_eq!(
+            parse_pom_properties(content),
+            Some(MavenCoordinate {
+                group_id: "com.google.guava".to_string(),
+                artifact_id: "guava".to_string(),
+                version: "33.0.0-jre".to_string(),
+            })
+        );
+        assert_eq!(parse_pom_properties("groupId=a\nartifactId=b\n"), None);
+    }
+
+    #[test]
+    fn read_coordinates_from_uberjar() {
+        let file = NamedTempFile::new().unwrap();
+        let mut zip = ZipWriter::new(file.reopen().unwrap());
+        for (name, content) in [
+            (
+                "META-INF/maven/org.clojure/clojure/pom.properties",
+                "groupId=org.clojure\nartifactId=clojure\nversion=1.12.0\n",
+            ),
+            (
+                "META-INF/maven/com.google.guava/guava/pom.properties",
+                "groupId=com.google.guava\nartifactId=guava\nversion=33.0.0-jre\n",
+            ),
+            ("META-INF/maven/org.clojure/clojure/pom.xml", "<project/>"),
+            ("clojure/core.clj", "(ns clojure.core)"),
+        ] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let coordinates = read_coordinates(file.path()).unwrap();
+        let ids: Vec<String> = coordinates.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "com.google.guava:guava:33.0.0-jre",
+                "org.clojure:clojure:1.12.0"
+            ]
+        );
+        assert_eq!(
+            coordinates[1].purl(),
+            "pkg:maven/org.clojure/clojure@1.12.0"
+        );
+    }
+
+    #[test]
+    fn read_coordinates_from_boot_nested_jars() {
+        let nested = |group: &str, artifact: &str| {
+            let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+            zip.start_file(
+                format!("META-INF/maven/{group}/{artifact}/pom.properties"),
+                SimpleFileOptions::default(),
+            )
+            .unwrap();
+            write!(zip, "groupId={group}\nartifactId={artifact}\nversion=1.0\n").unwrap();
+            zip.finish().unwrap().into_inner()
+        };
+
+        let file = NamedTempFile::new().unwrap();
+        let mut zip = ZipWriter::new(file.reopen().unwrap());
+        let stored =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        zip.start_file(
+            "META-INF/maven/com.example/app/pom.properties",
+            SimpleFileOptions::default(),
+        )
+        .unwrap();
+        zip.write_all(b"groupId=com.example\nartifactId=app\nversion=0.1.0\n")
+            .unwrap();
+        zip.start_file("BOOT-INF/classes/com/example/App.class", stored)
+            .unwrap();
+        zip.start_file("BOOT-INF/lib/spring-core-1.0.jar", stored)
+            .unwrap();
+        zip.write_all(&nested("org.springframework", "spring-core"))
+            .unwrap();
+        zip.start_file("BOOT-INF/lib/postgresql-1.0.jar", stored)
+            .unwrap();
+        zip.write_all(&nested("org.postgresql", "postgresql"))
+            .unwrap();
+        zip.finish().unwrap();
+
+        let ids: Vec<String> = read_coordinates(file.path())
+            .unwrap()
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                "com.example:app:0.1.0",
+                "org.postgresql:postgresql:1.0",
+                "org.springframework:spring-core:1.0"
+            ]
+        );
+    }
+}
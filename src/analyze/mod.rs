@@ -1,41 +1,84 @@
 mod classify;
+mod diff;
+pub mod maven;
+mod output;
 
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
 use indicatif::HumanBytes;
+use serde::Serialize;
 use zip::ZipArchive;
 
+use crate::config::BuildSystem;
 use crate::error::PackError;
 use classify::{classify_entry, detect_clojure_ns, extract_package, EntryCategory};
+pub use diff::MaxGrowth;
+pub use output::{render_json, render_markdown};
 
-#[derive(Debug)]
+/// Version of the `--format json` schema; bumped on incompatible changes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Table,
+    Json,
+    Markdown,
+}
+
+impl ReportFormat {
+    pub fn from_str(s: &str) -> Result<Self, PackError> {
+        match s {
+            "table" => Ok(ReportFormat::Table),
+            "json" => Ok(ReportFormat::Json),
+            "markdown" => Ok(ReportFormat::Markdown),
+            other => Err(PackError::InvalidReportFormat(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
 pub struct AnalysisReport {
     pub jar_path: String,
     pub disk_size: u64,
     pub total_uncompressed: u64,
     pub entry_count: usize,
     pub categories: Vec<CategoryStats>,
-    pub top_packages: Vec<(String, u64, usize)>,
-    pub clojure_namespaces: Vec<(String, u64, usize)>,
+    pub top_packages: Vec<GroupStats>,
+    pub clojure_namespaces: Vec<GroupStats>,
+    /// Dependency JARs nested in a Spring Boot archive (file count = entries inside)
+    pub nested_jars: Vec<GroupStats>,
     pub shrink_estimate: ShrinkEstimate,
     pub issues: Vec<AnalysisIssue>,
+    /// Uncompressed size of every file entry, for `--baseline` comparisons
+    #[serde(skip)]
+    pub entries: BTreeMap<String, u64>,
+    #[serde(skip)]
+    pub duplicate_classes: Vec<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct CategoryStats {
     pub name: String,
     pub size: u64,
     pub file_count: usize,
 }
 
-#[derive(Debug)]
+/// Size of a package or Clojure namespace
+#[derive(Debug, Serialize)]
+pub struct GroupStats {
+    pub name: String,
+    pub size: u64,
+    pub file_count: usize,
+}
+
+#[derive(Debug, Serialize)]
 pub struct ShrinkEstimate {
     pub removable_size: u64,
     pub removable_files: usize,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct AnalysisIssue {
     pub message: String,
 }
@@ -44,6 +87,14 @@ const LARGE_RESOURCE_THRESHOLD: u64 = 1_048_576; // 1 MB
 const TOP_N: usize = 20;
 
 pub fn analyze_jar(jar_path: &Path) -> Result<AnalysisReport, PackError> {
+    analyze_jar_with_limit(jar_path, Some(TOP_N))
+}
+
+/// Like `analyze_jar`, keeping `limit` packages and namespaces (all if `None`).
+fn analyze_jar_with_limit(
+    jar_path: &Path,
+    limit: Option<usize>,
+) -> Result<AnalysisReport, PackError> {
     let file = std::fs::File::open(jar_path)
         .map_err(|e| PackError::AnalyzeFailed(format!("cannot open JAR: {e}")))?;
     let disk_size = file
@@ -57,15 +108,17 @@ pub fn analyze_jar(jar_path: &Path) -> Result<AnalysisReport, PackError> {
     let mut cat_counters: HashMap<&str, (u64, usize)> = HashMap::new();
     let mut packages: HashMap<String, (u64, usize)> = HashMap::new();
     let mut clj_ns_map: HashMap<String, (u64, usize)> = HashMap::new();
+    let mut nested_jars: HashMap<String, (u64, usize)> = HashMap::new();
     let mut class_occurrences: HashMap<String, usize> = HashMap::new();
     let mut large_resources: Vec<(String, u64)> = Vec::new();
+    let mut entries: BTreeMap<String, u64> = BTreeMap::new();
     let mut shrink_size: u64 = 0;
     let mut shrink_count: usize = 0;
 
     let entry_count = archive.len();
 
     for i in 0..entry_count {
-        let entry = archive
+        let mut entry = archive
             .by_index(i)
             .map_err(|e| PackError::AnalyzeFailed(format!("zip entry error: {e}")))?;
 
@@ -76,6 +129,7 @@ pub fn analyze_jar(jar_path: &Path) -> Result<AnalysisReport, PackError> {
 
         let size = entry.size();
         total_uncompressed += size;
+        *entries.entry(name.clone()).or_insert(0) += size;
         let category = classify_entry(&name);
 
         let cat_key = match category {
@@ -85,6 +139,8 @@ pub fn analyze_jar(jar_path: &Path) -> Result<AnalysisReport, PackError> {
             EntryCategory::Metadata => "Metadata",
             EntryCategory::ClojureSource => "Clojure sources",
             EntryCategory::JavaSource => "Java sources",
+            EntryCategory::NestedJar => "Nested JARs",
+            EntryCategory::BootLoader => "Boot loader",
         };
         let counter = cat_counters.entry(cat_key).or_insert((0, 0));
         counter.0 += size;
@@ -99,6 +155,15 @@ pub fn analyze_jar(jar_path: &Path) -> Result<AnalysisReport, PackError> {
             }
         }
 
+        if category == EntryCategory::NestedJar {
+            let mut buf = Vec::with_capacity(size as usize);
+            std::io::Read::read_to_end(&mut entry, &mut buf)
+                .map_err(|e| PackError::AnalyzeFailed(format!("cannot read {name}: {e}")))?;
+            let inner_count = ZipArchive::new(std::io::Cursor::new(buf)).map_or(0, |a| a.len());
+            let file_name = name.rsplit('/').next().unwrap_or(&name).to_string();
+            nested_jars.insert(file_name, (size, inner_count));
+        }
+
         if category == EntryCategory::Resource && size >= LARGE_RESOURCE_THRESHOLD {
             large_resources.push((name.clone(), size));
         }
@@ -122,29 +187,29 @@ pub fn analyze_jar(jar_path: &Path) -> Result<AnalysisReport, PackError> {
             file_count: count,
         })
         .collect();
-    categories.sort_by(|a, b| b.size.cmp(&a.size));
-
-    let mut top_packages: Vec<(String, u64, usize)> =
-        packages.into_iter().map(|(k, (s, c))| (k, s, c)).collect();
-    top_packages.sort_by(|a, b| b.1.cmp(&a.1));
-    top_packages.truncate(TOP_N);
-
-    let mut clojure_namespaces: Vec<(String, u64, usize)> = clj_ns_map
-        .into_iter()
-        .map(|(k, (s, c))| (k, s, c))
-        .collect();
-    clojure_namespaces.sort_by(|a, b| b.1.cmp(&a.1));
-    clojure_namespaces.truncate(TOP_N);
+    categories.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+    let mut top_packages = group_stats(packages);
+    let mut clojure_namespaces = group_stats(clj_ns_map);
+    let mut nested_jars = group_stats(nested_jars);
+    if let Some(limit) = limit {
+        top_packages.truncate(limit);
+        clojure_namespaces.truncate(limit);
+        nested_jars.truncate(limit);
+    }
 
     let mut issues = Vec::new();
+    let mut duplicate_classes = Vec::new();
     for (name, count) in &class_occurrences {
         if *count > 1 {
             issues.push(AnalysisIssue {
                 message: format!("Duplicate class: {} ({} occurrences)", name, count),
             });
+            duplicate_classes.push(name.clone());
         }
     }
     issues.sort_by(|a, b| a.message.cmp(&b.message));
+    duplicate_classes.sort();
     for (name, size) in &large_resources {
         issues.push(AnalysisIssue {
             message: format!("Large resource: {} ({})", name, HumanBytes(*size)),
@@ -159,14 +224,31 @@ pub fn analyze_jar(jar_path: &Path) -> Result<AnalysisReport, PackError> {
         categories,
         top_packages,
         clojure_namespaces,
+        nested_jars,
         shrink_estimate: ShrinkEstimate {
             removable_size: shrink_size,
             removable_files: shrink_count,
         },
         issues,
+        entries,
+        duplicate_classes,
     })
 }
 
+/// Largest first; ties by name so output is stable across runs.
+fn group_stats(groups: HashMap<String, (u64, usize)>) -> Vec<GroupStats> {
+    let mut stats: Vec<GroupStats> = groups
+        .into_iter()
+        .map(|(name, (size, file_count))| GroupStats {
+            name,
+            size,
+            file_count,
+        })
+        .collect();
+    stats.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    stats
+}
+
 pub fn render_report(report: &AnalysisReport) {
     eprintln!();
     eprintln!(
@@ -202,12 +284,12 @@ pub fn render_report(report: &AnalysisReport) {
 
     if !report.top_packages.is_empty() {
         eprintln!("Top packages by size:");
-        for (pkg, size, count) in &report.top_packages {
+        for group in &report.top_packages {
             eprintln!(
                 "  {:<35} {:>10}  {} files",
-                pkg,
-                HumanBytes(*size),
-                format_number(*count),
+                group.name,
+                HumanBytes(group.size),
+                format_number(group.file_count),
             );
         }
         eprintln!();
@@ -215,12 +297,25 @@ pub fn render_report(report: &AnalysisReport) {
 
     if !report.clojure_namespaces.is_empty() {
         eprintln!("Clojure namespaces:");
-        for (ns, size, count) in &report.clojure_namespaces {
+        for group in &report.clojure_namespaces {
             eprintln!(
                 "  {:<35} {:>10}  {} files",
-                ns,
-                HumanBytes(*size),
-                format_number(*count),
+                group.name,
+                HumanBytes(group.size),
+                format_number(group.file_count),
+            );
+        }
+        eprintln!();
+    }
+
+    if !report.nested_jars.is_empty() {
+        eprintln!("Nested JARs (Spring Boot):");
+        for group in &report.nested_jars {
+            eprintln!(
+                "  {:<35} {:>10}  {} entries",
+                group.name,
+                HumanBytes(group.size),
+                format_number(group.file_count),
             );
         }
         eprintln!();
@@ -251,7 +346,39 @@ pub fn render_report(report: &AnalysisReport) {
     }
 }
 
-pub fn run_analyze(input: &Path) -> Result<(), PackError> {
+pub fn run_analyze(
+    input: &Path,
+    format: ReportFormat,
+    baseline: Option<&Path>,
+    max_growth: Option<MaxGrowth>,
+) -> Result<(), PackError> {
+    if let Some(baseline) = baseline {
+        let old = diff::analyze_for_diff(baseline)?;
+        let new = diff::analyze_for_diff(input)?;
+        let report = diff::diff_reports(&old, &new);
+        match format {
+            ReportFormat::Table => diff::render_diff(&report),
+            ReportFormat::Json => println!("{}", diff::render_diff_json(&report)),
+            ReportFormat::Markdown => print!("{}", diff::render_diff_markdown(&report)),
+        }
+        if let Some(limit) = max_growth {
+            report.check_growth(limit)?;
+        }
+        return Ok(());
+    }
+
+    let jar_path = resolve_input_jar(input)?;
+    let report = analyze_jar(&jar_path)?;
+    match format {
+        ReportFormat::Table => render_report(&report),
+        ReportFormat::Json => println!("{}", render_json(&report)),
+        ReportFormat::Markdown => print!("{}", render_markdown(&report)),
+    }
+    Ok(())
+}
+
+/// Returns `input` when it is a JAR, otherwise builds the project's uberjar.
+pub fn resolve_input_jar(input: &Path) -> Result<PathBuf, PackError> {
     let jar_path = if input.extension().is_some_and(|e| e == "jar") {
         input.to_path_buf()
     } else if input.is_dir() {
@@ -260,7 +387,17 @@ pub fn run_analyze(input: &Path) -> Result<(), PackError> {
             crate::detect::DetectedBuild::Simple(system) => {
                 eprintln!("Detected build system: {:?}", system);
                 eprintln!("Building uberjar...");
-                crate::build::build_uberjar(input, system)?
+                let project_config = crate::project_config::load_project_config(input)
+                    .map_err(|e| PackError::AnalyzeFailed(e.to_string()))?
+                    .unwrap_or_default();
+                let target = match system {
+                    BuildSystem::Bazel => project_config.bazel_target,
+                    BuildSystem::Buck2 => project_config.buck_target,
+                    BuildSystem::Mill => project_config.mill_module,
+                    BuildSystem::Sbt => project_config.sbt_project,
+                    _ => None,
+                };
+                crate::build::build_uberjar(input, system, target.as_deref())?
             }
             crate::detect::DetectedBuild::GradleMultiProject {
                 app_subprojects, ..
@@ -275,6 +412,17 @@ pub fn run_analyze(input: &Path) -> Result<(), PackError> {
                 eprintln!("Building uberjar...");
                 crate::build::build_gradle_subproject(input, &sub.name)?
             }
+            crate::detect::DetectedBuild::MavenMultiModule { app_modules, .. } => {
+                let project_config = crate::project_config::load_project_config(input)
+                    .map_err(|e| PackError::AnalyzeFailed(e.to_string()))?
+                    .unwrap_or_default();
+                let module = project_config
+                    .maven_module
+                    .unwrap_or_else(|| app_modules[0].name.clone());
+                eprintln!("Detected Maven multi-module, using: {}", module);
+                eprintln!("Building uberjar...");
+                crate::build::build_maven_module(input, &module)?
+            }
         }
     } else {
         return Err(PackError::AnalyzeFailed(format!(
@@ -282,13 +430,10 @@ pub fn run_analyze(input: &Path) -> Result<(), PackError> {
             input.display()
         )));
     };
-
-    let report = analyze_jar(&jar_path)?;
-    render_report(&report);
-    Ok(())
+    Ok(jar_path)
 }
 
-fn format_number(n: usize) -> String {
+pub(crate) fn format_number(n: usize) -> String {
     let s = n.to_string();
     let mut result = String::new();
     for (i, c) in s.chars().rev().enumerate() {
@@ -320,6 +465,38 @@ mod tests {
         file
     }
 
+    #[test]
+    fn analyze_spring_boot_jar() {
+        let mut nested = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for name in ["org/lib/A.class", "org/lib/B.class"] {
+            nested
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            nested.write_all(b"x").unwrap();
+        }
+        let nested = nested.finish().unwrap().into_inner();
+
+        let jar = create_test_jar(&[
+            (
+                "org/springframework/boot/loader/launch/JarLauncher.class",
+                b"loader",
+            ),
+            ("BOOT-INF/classes/com/example/App.class", b"app"),
+            ("BOOT-INF/lib/lib-1.0.jar", &nested),
+        ]);
+        let report = analyze_jar(jar.path()).unwrap();
+
+        let names: Vec<_> = report.categories.iter().map(|c| c.name.as_str()).collect();
+        assert!(names.contains(&"Nested JARs"));
+        assert!(names.contains(&"Boot loader"));
+        assert!(names.contains(&"Classes"));
+        assert!(!names.contains(&"Resources"));
+        assert!(report.top_packages.iter().any(|p| p.name == "com.example"));
+        assert_eq!(report.nested_jars.len(), 1);
+        assert_eq!(report.nested_jars[0].name, "lib-1.0.jar");
+        assert_eq!(report.nested_jars[0].file_count, 2);
+    }
+
     #[test]
     fn analyze_empty_jar() {
         let jar = create_test_jar(&[]);
@@ -397,7 +574,7 @@ mod tests {
         let ns_names: Vec<&str> = report
             .clojure_namespaces
             .iter()
-            .map(|n| n.0.as_str())
+            .map(|n| n.name.as_str())
             .collect();
         assert!(ns_names.contains(&"myapp.core"));
         assert!(ns_names.contains(&"clojure.core"));
@@ -413,7 +590,11 @@ mod tests {
         let report = analyze_jar(jar.path()).unwrap();
         assert!(!report.top_packages.is_empty());
 
-        let pkg_names: Vec<&str> = report.top_packages.iter().map(|p| p.0.as_str()).collect();
+        let pkg_names: Vec<&str> = report
+            .top_packages
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
         assert!(pkg_names.contains(&"org.apache.commons"));
         assert!(pkg_names.contains(&"com.google.guava"));
     }
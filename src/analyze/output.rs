@@ -0,0 +1,173 @@
+use std::fmt::Write;
+
+use indicatif::HumanBytes;
+use serde::Serialize;
+
+use super::{format_number, AnalysisReport, SCHEMA_VERSION};
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    schema_version: u32,
+    #[serde(flatten)]
+    report: &'a AnalysisReport,
+}
+
+/// The report as pretty-printed JSON. Sizes are in bytes.
+pub fn render_json(report: &AnalysisReport) -> String {
+    let json = JsonReport {
+        schema_version: SCHEMA_VERSION,
+        report,
+    };
+    serde_json::to_string_pretty(&json).expect("analysis report serializes")
+}
+
+/// The report as GitHub-flavored Markdown, suitable for a PR comment.
+pub fn render_markdown(report: &AnalysisReport) -> String {
+    let mut md = String::new();
+    let file_name = std::path::Path::new(&report.jar_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| report.jar_path.clone());
+    let _ = writeln!(md, "### JAR analysis: `{file_name}`");
+    let _ = writeln!(md);
+    let _ = writeln!(
+        md,
+        "**{}** on disk, {} uncompressed, {} entries",
+        HumanBytes(report.disk_size),
+        HumanBytes(report.total_uncompressed),
+        format_number(report.entry_count)
+    );
+    let _ = writeln!(md);
+
+    if !report.categories.is_empty() {
+        let _ = writeln!(md, "| Category | Size | % | Files |");
+        let _ = writeln!(md, "|----------|-----:|--:|------:|");
+        for cat in &report.categories {
+            let _ = writeln!(
+                md,
+                "| {} | {} | {:.0}% | {} |",
+                cat.name,
+                HumanBytes(cat.size),
+                percent(cat.size, report.total_uncompressed),
+                format_number(cat.file_count)
+            );
+        }
+        let _ = writeln!(md);
+    }
+
+    for (title, groups) in [
+        ("Top packages", &report.top_packages),
+        ("Clojure namespaces", &report.clojure_namespaces),
+        ("Nested JARs", &report.nested_jars),
+    ] {
+        if groups.is_empty() {
+            continue;
+        }
+        let _ = writeln!(md, "<details><summary>{title}</summary>");
+        let _ = writeln!(md);
+        let _ = writeln!(md, "| Name | Size | Files |");
+        let _ = writeln!(md, "|------|-----:|------:|");
+        for group in groups {
+            let _ = writeln!(
+                md,
+                "| `{}` | {} | {} |",
+                group.name,
+                HumanBytes(group.size),
+                format_number(group.file_count)
+            );
+        }
+        let _ = writeln!(md);
+        let _ = writeln!(md, "</details>");
+        let _ = writeln!(md);
+    }
+
+    let est = &report.shrink_estimate;
+    if est.removable_files > 0 {
+        let _ = writeln!(
+            md,
+            "Estimated `--shrink` savings: {} ({:.0}%), {} removable files",
+            HumanBytes(est.removable_size),
+            percent(est.removable_size, report.total_uncompressed),
+            format_number(est.removable_files)
+        );
+        let _ = writeln!(md);
+    }
+
+    if !report.issues.is_empty() {
+        let _ = writeln!(md, "**Potential issues**");
+        let _ = writeln!(md);
+        for issue in &report.issues {
+            let _ = writeln!(md, "- {}", issue.message);
+        }
+        let _ = writeln!(md);
+    }
+    md
+}
+
+fn percent(part: u64, total: u64) -> f64 {
+    if total > 0 {
+        part as f64 / total as f64 * 100.0
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{AnalysisIssue, CategoryStats, GroupStats, ShrinkEstimate};
+    use super::*;
+
+    fn sample_report() -> AnalysisReport {
+        AnalysisReport {
+            jar_path: "target/app-standalone.jar".to_string(),
+            disk_size: 2_000,
+            total_uncompressed: 4_000,
+            entry_count: 3,
+            categories: vec![CategoryStats {
+                name: "Classes".to_string(),
+                size: 3_000,
+                file_count: 2,
+            }],
+            top_packages: vec![GroupStats {
+                name: "org.apache.commons".to_string(),
+                size: 3_000,
+                file_count: 2,
+            }],
+            clojure_namespaces: Vec::new(),
+            nested_jars: Vec::new(),
+            shrink_estimate: ShrinkEstimate {
+                removable_size: 1_000,
+                removable_files: 1,
+            },
+            issues: vec![AnalysisIssue {
+                message: "Duplicate class: a/B.class (2 occurrences)".to_string(),
+            }],
+            entries: Default::default(),
+            duplicate_classes: vec!["a/B.class".to_string()],
+        }
+    }
+
+    #[test]
+    fn json_has_schema_version_and_byte_sizes() {
+        let json: serde_json::Value = serde_json::from_str(&render_json(&sample_report())).unwrap();
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+        assert_eq!(json["disk_size"], 2_000);
+        assert_eq!(json["categories"][0]["name"], "Classes");
+        assert_eq!(json["top_packages"][0]["file_count"], 2);
+        assert_eq!(json["shrink_estimate"]["removable_size"], 1_000);
+        assert_eq!(
+            json["issues"][0]["message"],
+            "Duplicate class: a/B.class (2 occurrences)"
+        );
+    }
+
+    #[test]
+    fn markdown_tables() {
+        let md = render_markdown(&sample_report());
+        assert!(md.starts_with("### JAR analysis: `app-standalone.jar`"));
+        assert!(md.contains("| Classes | 2.93 KiB | 75% | 2 |"));
+        assert!(md.contains("<details><summary>Top packages</summary>"));
+        assert!(!md.contains("Clojure namespaces"));
+        assert!(md.contains("- Duplicate class: a/B.class (2 occurrences)"));
+    }
+}
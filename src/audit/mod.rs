@@ -0,0 +1,590 @@
+mod cvss;
+mod version;
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::analyze::maven::MavenCoordinate;
+use crate::error::PackError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Unknown,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    /// Parses a `--fail-on` threshold or an advisory's severity label.
+    pub fn from_str(s: &str) -> Result<Self, PackError> {
+        match s.to_lowercase().as_str() {
+            "low" => Ok(Severity::Low),
+            "medium" | "moderate" => Ok(Severity::Medium),
+            "high" => Ok(Severity::High),
+            "critical" => Ok(Severity::Critical),
+            other => Err(PackError::InvalidSeverity(other.to_string())),
+        }
+    }
+
+    fn from_score(score: f64) -> Self {
+        match score {
+            s if s >= 9.0 => Severity::Critical,
+            s if s >= 7.0 => Severity::High,
+            s if s >= 4.0 => Severity::Medium,
+            s if s > 0.0 => Severity::Low,
+            _ => Severity::Unknown,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Unknown => "unknown",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+/// An OSV advisory (https://ossf.github.io/osv-schema/). Only the fields
+/// used for matching are read.
+#[derive(Debug, Deserialize)]
+struct OsvRecord {
+    id: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    severity: Vec<OsvSeverity>,
+    #[serde(default)]
+    affected: Vec<OsvAffected>,
+    #[serde(default)]
+    database_specific: Option<serde_json::Value>,
+    #[serde(default)]
+    withdrawn: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvSeverity {
+    #[serde(rename = "type")]
+    kind: String,
+    score: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvAffected {
+    #[serde(default)]
+    package: Option<OsvPackage>,
+    #[serde(default)]
+    ranges: Vec<OsvRange>,
+    #[serde(default)]
+    versions: Vec<String>,
+    #[serde(default)]
+    severity: Vec<OsvSeverity>,
+    #[serde(default)]
+    database_specific: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvPackage {
+    ecosystem: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvRange {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    events: Vec<OsvEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvEvent {
+    introduced: Option<String>,
+    fixed: Option<String>,
+    last_affected: Option<String>,
+    limit: Option<String>,
+}
+
+/// Maven advisories indexed by `groupId:artifactId`.
+pub struct AdvisoryDb {
+    records: Vec<OsvRecord>,
+    by_package: HashMap<String, Vec<(usize, usize)>>,
+}
+
+impl AdvisoryDb {
+    /// Loads an OSV dump: a directory of `.json` files, a `.zip` of them (as
+    /// published per ecosystem, e.g. `Maven/all.zip`), or a single JSON file
+    /// holding one advisory or an array of them.
+    pub fn load(path: &Path) -> Result<Self, PackError> {
+        let mut records = Vec::new();
+        if path.is_dir() {
+            load_dir(path, &mut records)?;
+        } else if path.extension().is_some_and(|e| e == "zip") {
+            let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?)?;
+            for i in 0..archive.len() {
+                let mut entry = archive.by_index(i)?;
+                if !entry.name().ends_with(".json") {
+                    continue;
+                }
+                let name = entry.name().to_string();
+                let mut bytes = Vec::new();
+                entry.read_to_end(&mut bytes)?;
+                parse_records(&bytes, &name, &mut records)?;
+            }
+        } else {
+            let bytes = std::fs::read(path)?;
+            parse_records(&bytes, &path.display().to_string(), &mut records)?;
+        }
+        records.retain(|r| r.withdrawn.is_none());
+
+        let mut by_package: HashMap<String, Vec<(usize, usize)>> = HashMap::new();
+        for (r, record) in records.iter().enumerate() {
+            for (a, affected) in record.affected.iter().enumerate() {
+                if let Some(ref package) = affected.package {
+                    if package.ecosystem == "Maven" {
+                        by_package
+                            .entry(package.name.clone())
+                            .or_default()
+                            .push((r, a));
+                    }
+                }
+            }
+        }
+        Ok(AdvisoryDb {
+            records,
+            by_package,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Advisories affecting the given dependencies.
+    pub fn check(&self, coordinates: &[MavenCoordinate]) -> Vec<Vulnerability> {
+        let mut found = Vec::new();
+        for coordinate in coordinates {
+            let key = format!("{}:{}", coordinate.group_id, coordinate.artifact_id);
+            let Some(entries) = self.by_package.get(&key) else {
+                continue;
+            };
+            for &(r, a) in entries {
+                let record = &self.records[r];
+                let affected = &record.affected[a];
+                if !is_affected(&coordinate.version, affected) {
+                    continue;
+                }
+                if found.iter().any(|v: &Vulnerability| {
+                    v.id == record.id && v.package == coordinate.to_string()
+                }) {
+                    continue;
+                }
+                let (severity, score) = severity_of(record, affected);
+                found.push(Vulnerability {
+                    id: record.id.clone(),
+                    aliases: record.aliases.clone(),
+                    summary: record.summary.clone().unwrap_or_default(),
+                    severity,
+                    score,
+                    package: coordinate.to_string(),
+                    fixed: fixed_version(&coordinate.version, affected),
+                });
+            }
+        }
+        found.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.id.cmp(&b.id)));
+        found
+    }
+}
+
+fn load_dir(dir: &Path, records: &mut Vec<OsvRecord>) -> Result<(), PackError> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            load_dir(&path, records)?;
+        } else if path.extension().is_some_and(|e| e == "json") {
+            let bytes = std::fs::read(&path)?;
+            parse_records(&bytes, &path.display().to_string(), records)?;
+        }
+    }
+    Ok(())
+}
+
+fn parse_records(bytes: &[u8], source: &str, out: &mut Vec<OsvRecord>) -> Result<(), PackError> {
+    let invalid = |e: serde_json::Error| PackError::AuditFailed(format!("{source}: {e}"));
+    if bytes.trim_ascii_start().starts_with(b"[") {
+        out.extend(serde_json::from_slice::<Vec<OsvRecord>>(bytes).map_err(invalid)?);
+    } else {
+        out.push(serde_json::from_slice(bytes).map_err(invalid)?);
+    }
+    Ok(())
+}
+
+/// Evaluates OSV ranges the way the schema describes: walk the events in
+/// version order, entering the range at `introduced` and leaving it at
+/// `fixed`/`limit`, or after `last_affected`.
+fn is_affected(version: &str, affected: &OsvAffected) -> bool {
+    if affected.versions.iter().any(|v| v == version) {
+        return true;
+    }
+    affected
+        .ranges
+        .iter()
+        .filter(|r| r.kind == "ECOSYSTEM" || r.kind == "SEMVER")
+        .any(|range| {
+            let mut events: Vec<(&str, &OsvEvent)> = range
+                .events
+                .iter()
+                .filter_map(|e| {
+                    let v = e
+                        .introduced
+                        .as_deref()
+                        .or(e.fixed.as_deref())
+                        .or(e.last_affected.as_deref())
+                        .or(e.limit.as_deref())?;
+                    Some((v, e))
+                })
+                .collect();
+            events.sort_by(|(a, _), (b, _)| compare_event_versions(a, b));
+
+            let mut inside = false;
+            for (event_version, event) in events {
+                let ord = if event_version == "0" {
+                    Ordering::Greater
+                } else {
+                    version::compare(version, event_version)
+                };
+                if ord == Ordering::Less {
+                    break;
+                }
+                if event.introduced.is_some() {
+                    inside = true;
+                } else if event.last_affected.is_none() || ord == Ordering::Greater {
+                    // fixed/limit at or below the version, or past last_affected
+                    inside = false;
+                }
+            }
+            inside
+        })
+}
+
+fn compare_event_versions(a: &str, b: &str) -> Ordering {
+    match (a, b) {
+        ("0", "0") => Ordering::Equal,
+        ("0", _) => Ordering::Less,
+        (_, "0") => Ordering::Greater,
+        _ => version::compare(a, b),
+    }
+}
+
+/// Lowest `fixed` version above the installed one.
+fn fixed_version(version: &str, affected: &OsvAffected) -> Option<String> {
+    affected
+        .ranges
+        .iter()
+        .flat_map(|r| &r.events)
+        .filter_map(|e| e.fixed.as_deref())
+        .filter(|f| version::compare(f, version) == Ordering::Greater)
+        .min_by(|a, b| version::compare(a, b))
+        .map(str::to_string)
+}
+
+/// Severity from the database's own label (GHSA uses LOW/MODERATE/HIGH/
+/// CRITICAL), falling back to the highest CVSS v3 base score.
+fn severity_of(record: &OsvRecord, affected: &OsvAffected) -> (Severity, Option<f64>) {
+    let score = record
+        .severity
+        .iter()
+        .chain(&affected.severity)
+        .filter(|s| s.kind == "CVSS_V3")
+        .filter_map(|s| cvss::base_score(&s.score))
+        .fold(None, |max: Option<f64>, s| {
+            Some(max.map_or(s, |m| m.max(s)))
+        });
+
+    let label = [&affected.database_specific, &record.database_specific]
+        .into_iter()
+        .flatten()
+        .find_map(|d| d.get("severity")?.as_str())
+        .and_then(|s| Severity::from_str(s).ok());
+
+    let severity = label
+        .or_else(|| score.map(Severity::from_score))
+        .unwrap_or(Severity::Unknown);
+    (severity, score)
+}
+
+#[derive(Debug)]
+pub struct Vulnerability {
+    pub id: String,
+    pub aliases: Vec<String>,
+    pub summary: String,
+    pub severity: Severity,
+    pub score: Option<f64>,
+    /// Affected dependency (`group:artifact:version`) or `java-runtime`
+    pub package: String,
+    pub fixed: Option<String>,
+}
+
+/// A known-vulnerable JDK release family, from the `--jdk-advisories` file.
+#[derive(Debug, Deserialize)]
+pub struct JdkAdvisory {
+    pub id: String,
+    pub severity: String,
+    #[serde(default)]
+    pub summary: Option<String>,
+    /// First fixed release of each affected feature version, e.g. `21.0.2`
+    pub fixed: Vec<String>,
+}
+
+pub fn load_jdk_advisories(path: &Path) -> Result<Vec<JdkAdvisory>, PackError> {
+    let bytes = std::fs::read(path)?;
+    serde_json::from_slice(&bytes)
+        .map_err(|e| PackError::AuditFailed(format!("{}: {e}", path.display())))
+}
+
+/// Reads the Java version (`JAVA_VERSION`) of a runtime directory or binary.
+pub fn runtime_java_version(runtime: &Path) -> Result<String, PackError> {
+    let release = crate::pack::layout::read_runtime_files(runtime, "release")?;
+    let content = release
+        .first()
+        .map(|(_, bytes)| String::from_utf8_lossy(bytes).to_string())
+        .unwrap_or_default();
+    crate::sbom::parse_release(&content)
+        .remove("JAVA_VERSION")
+        .ok_or_else(|| {
+            PackError::AuditFailed(format!(
+                "no JAVA_VERSION in the release file of {}",
+                runtime.display()
+            ))
+        })
+}
+
+/// Matches a JDK version against advisories listing fixed releases per
+/// feature version. Feature versions an advisory doesn't list are skipped.
+pub fn check_jdk(java_version: &str, advisories: &[JdkAdvisory]) -> Vec<Vulnerability> {
+    let feature = |v: &str| v.split(['.', '+', '-']).next().unwrap_or("").to_string();
+    let mut found = Vec::new();
+    for advisory in advisories {
+        let fixed = advisory
+            .fixed
+            .iter()
+            .find(|f| feature(f) == feature(java_version));
+        if let Some(fixed) = fixed {
+            if version::compare(java_version, fixed) == Ordering::Less {
+                found.push(Vulnerability {
+                    id: advisory.id.clone(),
+                    aliases: Vec::new(),
+                    summary: advisory.summary.clone().unwrap_or_default(),
+                    severity: Severity::from_str(&advisory.severity).unwrap_or(Severity::Unknown),
+                    score: None,
+                    package: format!("java-runtime:{java_version}"),
+                    fixed: Some(fixed.clone()),
+                });
+            }
+        }
+    }
+    found.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.id.cmp(&b.id)));
+    found
+}
+
+pub fn render_report(vulnerabilities: &[Vulnerability], checked: usize, advisories: usize) {
+    eprintln!();
+    eprintln!("Checked {checked} dependencies against {advisories} advisories");
+    eprintln!();
+    if vulnerabilities.is_empty() {
+        eprintln!("No known vulnerabilities found.");
+        eprintln!();
+        return;
+    }
+
+    eprintln!(
+        "{:<22} {:<10} {:<50} Fixed in",
+        "Advisory", "Severity", "Package"
+    );
+    eprintln!("{}", "\u{2500}".repeat(92));
+    for v in vulnerabilities {
+        let severity = match v.score {
+            Some(score) => format!("{} {score:.1}", v.severity.name()),
+            None => v.severity.name().to_string(),
+        };
+        eprintln!(
+            "{:<22} {:<10} {:<50} {}",
+            v.id,
+            severity,
+            v.package,
+            v.fixed.as_deref().unwrap_or("-")
+        );
+        let cves: Vec<&str> = v
+            .aliases
+            .iter()
+            .filter(|a| a.starts_with("CVE-"))
+            .map(String::as_str)
+            .collect();
+        let detail = match (cves.is_empty(), v.summary.is_empty()) {
+            (false, false) => format!("{}: {}", cves.join(", "), v.summary),
+            (false, true) => cves.join(", "),
+            (true, false) => v.summary.clone(),
+            (true, true) => continue,
+        };
+        eprintln!("    {detail}");
+    }
+    eprintln!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG4SHELL: &str = r#"{
+        "id": "GHSA-jfh8-c2jp-5v3q",
+        "aliases": ["CVE-2021-44228"],
+        "summary": "Remote code injection in Log4j",
+        "severity": [{"type": "CVSS_V3", "score": "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:C/C:H/I:H/A:H"}],
+        "affected": [{
+            "package": {"ecosystem": "Maven", "name": "org.apache.logging.log4j:log4j-core"},
+            "ranges": [{"type": "ECOSYSTEM", "events": [
+                {"introduced": "2.13.0"}, {"fixed": "2.15.0"},
+                {"introduced": "2.0-beta9"}, {"fixed": "2.12.2"}
+            ]}]
+        }],
+        "database_specific": {"severity": "CRITICAL"}
+    }"#;
+
+    fn coordinate(group: &str, artifact: &str, version: &str) -> MavenCoordinate {
+        MavenCoordinate {
+            group_id: group.to_string(),
+            artifact_id: artifact.to_string(),
+            version: version.to_string(),
+        }
+    }
+
+    fn affected(events: &str) -> OsvAffected {
+        serde_json::from_str(&format!(
+            r#"{{"ranges": [{{"type": "ECOSYSTEM", "events": {events}}}]}}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn range_events() {
+        let a = affected(r#"[{"introduced": "0"}, {"fixed": "1.5"}]"#);
+        assert!(is_affected("1.0", &a));
+        assert!(is_affected("1.5-rc1", &a));
+        assert!(!is_affected("1.5", &a));
+
+        let a = affected(r#"[{"introduced": "2.0"}, {"last_affected": "2.3"}]"#);
+        assert!(!is_affected("1.9", &a));
+        assert!(is_affected("2.3", &a));
+        assert!(!is_affected("2.3.1", &a));
+
+        let a = affected(
+            r#"[{"introduced": "2.13.0"}, {"fixed": "2.15.0"}, {"introduced": "2.0-beta9"}, {"fixed": "2.12.2"}]"#,
+        );
+        assert!(is_affected("2.14.1", &a));
+        assert!(is_affected("2.11.0", &a));
+        assert!(!is_affected("2.12.2", &a));
+        assert!(!is_affected("2.17.1", &a));
+    }
+
+    #[test]
+    fn check_matches_dependencies() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("maven")).unwrap();
+        std::fs::write(dir.path().join("maven").join("GHSA-jfh8.json"), LOG4SHELL).unwrap();
+        std::fs::write(dir.path().join("README.md"), "not an advisory").unwrap();
+
+        let db = AdvisoryDb::load(dir.path()).unwrap();
+        assert_eq!(db.len(), 1);
+        let found = db.check(&[
+            coordinate("org.apache.logging.log4j", "log4j-core", "2.14.1"),
+            coordinate("org.apache.logging.log4j", "log4j-api", "2.14.1"),
+            coordinate("org.clojure", "clojure", "1.12.0"),
+        ]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "GHSA-jfh8-c2jp-5v3q");
+        assert_eq!(found[0].severity, Severity::Critical);
+        assert_eq!(found[0].score, Some(10.0));
+        assert_eq!(found[0].fixed.as_deref(), Some("2.15.0"));
+        assert_eq!(
+            found[0].package,
+            "org.apache.logging.log4j:log4j-core:2.14.1"
+        );
+
+        let patched = db.check(&[coordinate(
+            "org.apache.logging.log4j",
+            "log4j-core",
+            "2.17.1",
+        )]);
+        assert!(patched.is_empty());
+    }
+
+    #[test]
+    fn load_array_and_zip_dumps() {
+        let dir = tempfile::tempdir().unwrap();
+        let array = dir.path().join("advisories.json");
+        std::fs::write(&array, format!("[{LOG4SHELL}]")).unwrap();
+        assert_eq!(AdvisoryDb::load(&array).unwrap().len(), 1);
+
+        let zip_path = dir.path().join("all.zip");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        zip.start_file("GHSA-jfh8.json", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        std::io::Write::write_all(&mut zip, LOG4SHELL.as_bytes()).unwrap();
+        zip.finish().unwrap();
+        assert_eq!(AdvisoryDb::load(&zip_path).unwrap().len(), 1);
+
+        std::fs::write(&array, "{not json").unwrap();
+        assert!(matches!(
+            AdvisoryDb::load(&array),
+            Err(PackError::AuditFailed(_))
+        ));
+    }
+
+    #[test]
+    fn severity_falls_back_to_cvss() {
+        let record: OsvRecord = serde_json::from_str(
+            r#"{"id": "OSV-1", "severity": [{"type": "CVSS_V3",
+                "score": "CVSS:3.1/AV:N/AC:H/PR:N/UI:N/S:U/C:L/I:N/A:N"}]}"#,
+        )
+        .unwrap();
+        let none = affected("[]");
+        assert_eq!(severity_of(&record, &none), (Severity::Low, Some(3.7)));
+    }
+
+    #[test]
+    fn jdk_versions_below_fixed_release() {
+        let advisories: Vec<JdkAdvisory> = serde_json::from_str(
+            r#"[{"id": "CVE-2024-20918", "severity": "high", "fixed": ["17.0.10", "21.0.2"]},
+                {"id": "CVE-2023-22081", "severity": "medium", "fixed": ["21.0.1"]}]"#,
+        )
+        .unwrap();
+        let found = check_jdk("21.0.1", &advisories);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "CVE-2024-20918");
+        assert_eq!(found[0].fixed.as_deref(), Some("21.0.2"));
+
+        assert_eq!(check_jdk("21", &advisories).len(), 2);
+        assert!(check_jdk("21.0.2", &advisories).is_empty());
+        // Feature versions an advisory doesn't list are not matched
+        assert!(check_jdk("11.0.20", &advisories).is_empty());
+    }
+
+    #[test]
+    fn severity_thresholds() {
+        assert_eq!(Severity::from_str("HIGH").unwrap(), Severity::High);
+        assert_eq!(Severity::from_str("moderate").unwrap(), Severity::Medium);
+        assert!(Severity::Critical > Severity::High);
+        assert!(Severity::Unknown < Severity::Low);
+        assert!(Severity::from_str("severe").is_err());
+    }
+}
@@ -0,0 +1,135 @@
+use std::cmp::Ordering;
+
+/// One component of a Maven version: a number or a qualifier.
+#[derive(Debug, Clone, PartialEq)]
+enum Item {
+    Number(u64),
+    Qualifier(String),
+}
+
+/// Qualifier order from Maven's `ComparableVersion`. Unknown qualifiers sort
+/// after all of these, alphabetically.
+const QUALIFIERS: &[&str] = &["alpha", "beta", "milestone", "rc", "snapshot", "", "sp"];
+
+fn qualifier_rank(q: &str) -> (usize, &str) {
+    match QUALIFIERS.iter().position(|k| *k == q) {
+        Some(rank) => (rank, ""),
+        None => (QUALIFIERS.len(), q),
+    }
+}
+
+fn normalize_qualifier(q: &str, followed_by_digit: bool) -> String {
+    match q {
+        "a" if followed_by_digit => "alpha",
+        "b" if followed_by_digit => "beta",
+        "m" if followed_by_digit => "milestone",
+        "cr" => "rc",
+        "ga" | "final" | "release" => "",
+        other => other,
+    }
+    .to_string()
+}
+
+fn parse(version: &str) -> Vec<Item> {
+    let lower = version.trim().to_lowercase();
+    let mut tokens: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for c in lower.chars() {
+        if c == '.' || c == '-' || c == '_' || c == '+' {
+            tokens.push(std::mem::take(&mut current));
+            continue;
+        }
+        // Digit/letter transitions split items too: "1rc2" -> 1, rc, 2
+        if let Some(last) = current.chars().last() {
+            if last.is_ascii_digit() != c.is_ascii_digit() {
+                tokens.push(std::mem::take(&mut current));
+            }
+        }
+        current.push(c);
+    }
+    tokens.push(current);
+
+    let mut items = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if token.is_empty() {
+            continue;
+        }
+        match token.parse::<u64>() {
+            Ok(n) => items.push(Item::Number(n)),
+            Err(_) => {
+                let next_is_digit = tokens
+                    .get(i + 1)
+                    .is_some_and(|t| t.starts_with(|c: char| c.is_ascii_digit()));
+                items.push(Item::Qualifier(normalize_qualifier(token, next_is_digit)));
+            }
+        }
+    }
+
+    // "1.0.0" == "1" and "1.0-ga" == "1"
+    while matches!(items.last(), Some(Item::Number(0)))
+        || items.last() == Some(&Item::Qualifier(String::new()))
+    {
+        items.pop();
+    }
+    items
+}
+
+fn compare_items(a: Option<&Item>, b: Option<&Item>) -> Ordering {
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (Some(Item::Number(x)), Some(Item::Number(y))) => x.cmp(y),
+        (Some(Item::Number(x)), None) => x.cmp(&0),
+        (None, Some(Item::Number(y))) => 0.cmp(y),
+        (Some(Item::Number(_)), Some(Item::Qualifier(_))) => Ordering::Greater,
+        (Some(Item::Qualifier(_)), Some(Item::Number(_))) => Ordering::Less,
+        (Some(Item::Qualifier(x)), Some(Item::Qualifier(y))) => {
+            qualifier_rank(x).cmp(&qualifier_rank(y))
+        }
+        (Some(Item::Qualifier(x)), None) => qualifier_rank(x).cmp(&qualifier_rank("")),
+        (None, Some(Item::Qualifier(y))) => qualifier_rank("").cmp(&qualifier_rank(y)),
+    }
+}
+
+/// Compares two Maven versions, e.g. `2.15.0` > `2.15.0-rc2` > `2.14.1`.
+pub fn compare(a: &str, b: &str) -> Ordering {
+    let (a, b) = (parse(a), parse(b));
+    for i in 0..a.len().max(b.len()) {
+        let ord = compare_items(a.get(i), b.get(i));
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+    Ordering::Equal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numeric_components() {
+        assert_eq!(compare("2.15.0", "2.14.1"), Ordering::Greater);
+        assert_eq!(compare("2.9", "2.10"), Ordering::Less);
+        assert_eq!(compare("1.0.0", "1"), Ordering::Equal);
+        assert_eq!(compare("1.0.1", "1"), Ordering::Greater);
+    }
+
+    #[test]
+    fn qualifiers_sort_before_release() {
+        assert_eq!(compare("2.15.0-rc2", "2.15.0"), Ordering::Less);
+        assert_eq!(compare("2.15.0-rc1", "2.15.0-rc2"), Ordering::Less);
+        assert_eq!(compare("1.0-alpha1", "1.0-beta1"), Ordering::Less);
+        assert_eq!(compare("1.0-SNAPSHOT", "1.0"), Ordering::Less);
+        assert_eq!(compare("1.0.Final", "1.0"), Ordering::Equal);
+        assert_eq!(compare("1.0-CR1", "1.0-rc1"), Ordering::Equal);
+        assert_eq!(compare("1.0-sp1", "1.0"), Ordering::Greater);
+    }
+
+    #[test]
+    fn unknown_qualifiers_and_mixed_items() {
+        assert_eq!(compare("33.0.0-jre", "32.1.3-jre"), Ordering::Greater);
+        assert_eq!(compare("1.0-jre", "1.0"), Ordering::Greater);
+        assert_eq!(compare("1.0.1", "1.0-jre"), Ordering::Greater);
+        assert_eq!(compare("1rc2", "1-rc-2"), Ordering::Equal);
+    }
+}
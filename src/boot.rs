@@ -0,0 +1,239 @@
+//! Spring Boot executable JAR support.
+//!
+//! Boot JARs keep the application under `BOOT-INF/classes/` and each dependency
+//! as a stored JAR under `BOOT-INF/lib/`, which tools such as jdeps can't see
+//! through. This module unpacks that layout.
+
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use zip::ZipArchive;
+
+use crate::error::PackError;
+use crate::jvm::cache::jdk_bin;
+use crate::thin::ThinJar;
+
+pub const CLASSES_PREFIX: &str = "BOOT-INF/classes/";
+pub const LIB_PREFIX: &str = "BOOT-INF/lib/";
+/// Loader classes that launch the app from the nested JARs
+pub const LOADER_PREFIX: &str = "org/springframework/boot/loader/";
+
+/// A Boot JAR unpacked to plain class directories and JARs.
+pub struct BootLayout {
+    pub classes: PathBuf,
+    pub libs: Vec<PathBuf>,
+}
+
+/// Whether the archive uses the Spring Boot nested-JAR layout.
+pub fn is_boot_archive<R: Read + Seek>(archive: &ZipArchive<R>) -> bool {
+    archive.file_names().any(|n| n.starts_with("BOOT-INF/"))
+}
+
+pub fn is_boot_jar(jar_path: &Path) -> Result<bool, PackError> {
+    let archive = ZipArchive::new(std::fs::File::open(jar_path)?)?;
+    Ok(is_boot_archive(&archive))
+}
+
+/// Whether an entry is a dependency JAR nested in a Boot (or WAR) archive.
+pub fn is_nested_jar(name: &str) -> bool {
+    (name.starts_with(LIB_PREFIX) || name.starts_with("WEB-INF/lib/")) && name.ends_with(".jar")
+}
+
+/// Names of the dependency JARs nested in a Boot archive; empty for other JARs.
+pub fn nested_jar_names<R: Read + Seek>(archive: &ZipArchive<R>) -> Vec<String> {
+    if !is_boot_archive(archive) {
+        return Vec::new();
+    }
+    archive
+        .file_names()
+        .filter(|n| is_nested_jar(n))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Opens a nested JAR in memory. `None` when the entry isn't a valid archive.
+pub fn open_nested<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+) -> Result<Option<ZipArchive<Cursor<Vec<u8>>>>, PackError> {
+    let mut buf = Vec::new();
+    archive.by_name(name)?.read_to_end(&mut buf)?;
+    Ok(ZipArchive::new(Cursor::new(buf)).ok())
+}
+
+/// Extracts `BOOT-INF/classes/` to `dest/classes` and the nested JARs to `dest/lib`.
+pub fn extract_layout(jar_path: &Path, dest: &Path) -> Result<BootLayout, PackError> {
+    let mut archive = ZipArchive::new(std::fs::File::open(jar_path)?)?;
+    let classes = dest.join("classes");
+    let lib_dir = dest.join("lib");
+    std::fs::create_dir_all(&classes)?;
+    std::fs::create_dir_all(&lib_dir)?;
+
+    let mut libs = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        let target = if let Some(rel) = name.strip_prefix(CLASSES_PREFIX) {
+            classes.join(rel)
+        } else if is_nested_jar(&name) {
+            let file_name = name.rsplit('/').next().unwrap_or(&name);
+            let path = lib_dir.join(file_name);
+            libs.push(path.clone());
+            path
+        } else {
+            continue;
+        };
+        // Reject entries escaping the destination (e.g. "../")
+        if entry.enclosed_name().is_none() {
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut out = std::fs::File::create(&target)?;
+        std::io::copy(&mut entry, &mut out)?;
+    }
+
+    libs.sort();
+    Ok(BootLayout { classes, libs })
+}
+
+/// Splits a Boot JAR with Spring Boot's own `jarmode=tools extract` (Boot 3.3+),
+/// giving an application JAR whose `Main-Class` is the app's `Start-Class`
+/// plus its dependencies in `lib/`. Launching that with `-cp` skips Boot's
+/// nested-JAR class loading and starts faster.
+pub fn jarmode_extract(
+    jdk_path: &Path,
+    jar_path: &Path,
+    work_dir: &Path,
+) -> Result<ThinJar, PackError> {
+    let java = jdk_bin(jdk_path, "java");
+    let dest = work_dir.join("boot-extract");
+    tracing::info!(
+        "running: java -Djarmode=tools -jar {} extract --destination {}",
+        jar_path.display(),
+        dest.display()
+    );
+
+    let output = Command::new(&java)
+        .arg("-Djarmode=tools")
+        .arg("-jar")
+        .arg(jar_path)
+        .args(["extract", "--destination"])
+        .arg(&dest)
+        .output()
+        .map_err(|e| PackError::ThinFailed(format!("failed to run java: {e}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(PackError::ThinFailed(format!(
+            "java -Djarmode=tools extract failed (needs Spring Boot 3.3+):\n{}",
+            stderr.trim()
+        )));
+    }
+
+    let app_jar = dest.join(jar_path.file_name().unwrap_or_default());
+    let main_class = crate::thin::jar_main_class(&app_jar)?.ok_or_else(|| {
+        PackError::ThinFailed(format!(
+            "{} has no Main-Class in its manifest",
+            app_jar.display()
+        ))
+    })?;
+
+    let mut deps: Vec<PathBuf> = std::fs::read_dir(dest.join("lib"))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == "jar"))
+        .collect();
+    deps.sort();
+
+    Ok(ThinJar {
+        app_jar,
+        main_class,
+        deps,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+    use zip::write::SimpleFileOptions;
+    use zip::{CompressionMethod, ZipWriter};
+
+    fn create_boot_jar(path: &Path) {
+        let mut nested = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        nested
+            .start_file("com/lib/Util.class", SimpleFileOptions::default())
+            .unwrap();
+        nested.write_all(b"lib").unwrap();
+        let nested = nested.finish().unwrap().into_inner();
+
+        let mut zip = ZipWriter::new(std::fs::File::create(path).unwrap());
+        let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        for (name, content) in [
+            (
+                "META-INF/MANIFEST.MF",
+                b"Start-Class: com.example.App\n".as_slice(),
+            ),
+            (
+                "org/springframework/boot/loader/launch/JarLauncher.class",
+                b"loader",
+            ),
+            ("BOOT-INF/classes/com/example/App.class", b"app"),
+            ("BOOT-INF/classes/application.yml", b"server: {}"),
+        ] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.start_file("BOOT-INF/lib/util-1.0.jar", stored).unwrap();
+        zip.write_all(&nested).unwrap();
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn detects_boot_layout() {
+        let dir = tempdir().unwrap();
+        let jar = dir.path().join("app.jar");
+        create_boot_jar(&jar);
+        assert!(is_boot_jar(&jar).unwrap());
+
+        let plain = dir.path().join("plain.jar");
+        let mut zip = ZipWriter::new(std::fs::File::create(&plain).unwrap());
+        zip.start_file("com/example/App.class", SimpleFileOptions::default())
+            .unwrap();
+        zip.finish().unwrap();
+        assert!(!is_boot_jar(&plain).unwrap());
+    }
+
+    #[test]
+    fn extracts_classes_and_nested_jars() {
+        let dir = tempdir().unwrap();
+        let jar = dir.path().join("app.jar");
+        create_boot_jar(&jar);
+
+        let layout = extract_layout(&jar, &dir.path().join("out")).unwrap();
+        assert!(layout.classes.join("com/example/App.class").is_file());
+        assert!(layout.classes.join("application.yml").is_file());
+        assert!(!layout
+            .classes
+            .join("org/springframework/boot/loader")
+            .exists());
+        assert_eq!(layout.libs.len(), 1);
+        assert!(layout.libs[0].ends_with("lib/util-1.0.jar"));
+        let nested = ZipArchive::new(std::fs::File::open(&layout.libs[0]).unwrap()).unwrap();
+        assert_eq!(nested.len(), 1);
+    }
+
+    #[test]
+    fn nested_jar_paths() {
+        assert!(is_nested_jar("BOOT-INF/lib/spring-core-6.1.jar"));
+        assert!(is_nested_jar("WEB-INF/lib/guava.jar"));
+        assert!(!is_nested_jar("BOOT-INF/classes/lib/x.jar.txt"));
+        assert!(!is_nested_jar("lib/x.jar"));
+    }
+}
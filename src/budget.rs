@@ -0,0 +1,112 @@
+use std::fmt::Write;
+
+use indicatif::HumanBytes;
+
+use crate::error::PackError;
+use crate::pack::PackedSizes;
+
+/// Size limits from `[budget]` in jbundle.toml, in bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SizeBudget {
+    pub max_binary: Option<u64>,
+    pub max_runtime: Option<u64>,
+    /// Limit for the app layer, plus the deps layer in thin mode
+    pub max_app: Option<u64>,
+}
+
+impl SizeBudget {
+    /// Fails with a per-layer breakdown when any limit is exceeded.
+    pub fn check(&self, binary_size: u64, sizes: &PackedSizes) -> Result<(), PackError> {
+        let app_size = sizes.app + sizes.deps;
+        let rows = [
+            ("binary", binary_size, self.max_binary),
+            ("runtime", sizes.runtime, self.max_runtime),
+            ("app", app_size, self.max_app),
+            ("crac", sizes.crac, None),
+            ("resources", sizes.resources, None),
+            ("sbom", sizes.sbom, None),
+        ];
+        if !rows
+            .iter()
+            .any(|(_, size, limit)| limit.is_some_and(|l| *size > l))
+        {
+            return Ok(());
+        }
+
+        let mut breakdown = String::new();
+        for (name, size, limit) in rows {
+            if size == 0 && limit.is_none() {
+                continue;
+            }
+            let _ = write!(
+                breakdown,
+                "  {:<10} {:>12}",
+                name,
+                HumanBytes(size).to_string()
+            );
+            match limit {
+                Some(l) if size > l => {
+                    let _ = write!(
+                        breakdown,
+                        "  limit {}, over by {}",
+                        HumanBytes(l),
+                        HumanBytes(size - l)
+                    );
+                }
+                Some(l) => {
+                    let _ = write!(breakdown, "  limit {}", HumanBytes(l));
+                }
+                None => {}
+            }
+            breakdown.push('\n');
+        }
+        breakdown.push_str(
+            "Run `jbundle analyze` to see which dependencies and resources take up the app layer",
+        );
+        Err(PackError::BudgetExceeded(breakdown))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+
+    fn sizes() -> PackedSizes {
+        PackedSizes {
+            runtime: 40 * MB,
+            app: 30 * MB,
+            deps: 10 * MB,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn within_budget() {
+        let budget = SizeBudget {
+            max_binary: Some(90 * MB),
+            max_runtime: Some(40 * MB),
+            max_app: Some(40 * MB),
+        };
+        assert!(budget.check(81 * MB, &sizes()).is_ok());
+        assert!(SizeBudget::default().check(u64::MAX, &sizes()).is_ok());
+    }
+
+    #[test]
+    fn exceeded_budget_lists_layers() {
+        let budget = SizeBudget {
+            max_binary: Some(80 * MB),
+            max_app: Some(35 * MB),
+            ..Default::default()
+        };
+        let err = budget.check(81 * MB, &sizes()).unwrap_err().to_string();
+        assert!(err.contains("binary"));
+        assert!(err.contains("limit 80.00 MiB, over by 1.00 MiB"));
+        // Thin mode: deps count towards the app budget
+        assert!(err.contains("limit 35.00 MiB, over by 5.00 MiB"));
+        assert!(err.contains("runtime"));
+        assert!(!err.contains("crac"));
+        assert!(err.contains("jbundle analyze"));
+    }
+}
@@ -13,12 +13,22 @@ fn ensure_command_exists(cmd: &str) -> Result<(), PackError> {
     Ok(())
 }
 
-pub fn build_uberjar(project_dir: &Path, system: BuildSystem) -> Result<PathBuf, PackError> {
+/// `target` is the Bazel/Buck2 target label, Mill module or sbt subproject;
+/// other build systems ignore it.
+pub fn build_uberjar(
+    project_dir: &Path,
+    system: BuildSystem,
+    target: Option<&str>,
+) -> Result<PathBuf, PackError> {
     match system {
         BuildSystem::DepsEdn => build_deps_edn(project_dir),
         BuildSystem::Leiningen => build_leiningen(project_dir),
         BuildSystem::Maven => build_maven(project_dir),
         BuildSystem::Gradle => build_gradle(project_dir, None),
+        BuildSystem::Sbt => build_sbt(project_dir, target),
+        BuildSystem::Mill => build_mill(project_dir, target),
+        BuildSystem::Bazel => build_bazel(project_dir, target),
+        BuildSystem::Buck2 => build_buck2(project_dir, target),
     }
 }
 
@@ -32,12 +42,49 @@ pub fn gradle_subproject_command_description(subproject: &str) -> String {
     format!("gradle :{subproject}:shadowJar (or build)")
 }
 
+/// Build uberjar for a Maven reactor module (and the modules it depends on).
+pub fn build_maven_module(project_dir: &Path, module: &str) -> Result<PathBuf, PackError> {
+    ensure_command_exists("mvn")?;
+    tracing::info!("running: mvn -pl {module} -am package -DskipTests");
+
+    let output = Command::new("mvn")
+        .args(["-pl", module, "-am", "package", "-DskipTests"])
+        .current_dir(project_dir)
+        .output()
+        .map_err(|e| PackError::BuildFailed(format!("failed to run mvn: {e}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let formatted = crate::diagnostic::format_build_error(
+            &stderr,
+            &stdout,
+            BuildSystem::Maven,
+            project_dir,
+        );
+        return Err(PackError::BuildFailed(format!(
+            "mvn -pl {module} -am package failed:\n{formatted}"
+        )));
+    }
+
+    find_uberjar(&project_dir.join(module))
+}
+
+/// Returns the build command description for a Maven module.
+pub fn maven_module_command_description(module: &str) -> String {
+    format!("mvn -pl {module} -am package")
+}
+
 pub fn build_command_description(system: BuildSystem) -> &'static str {
     match system {
         BuildSystem::DepsEdn => "clojure -T:build uber",
         BuildSystem::Leiningen => "lein uberjar",
         BuildSystem::Maven => "mvn package -DskipTests",
         BuildSystem::Gradle => "gradle build -x test",
+        BuildSystem::Sbt => "sbt assembly",
+        BuildSystem::Mill => "mill <module>.assembly",
+        BuildSystem::Bazel => "bazel build <target>_deploy.jar",
+        BuildSystem::Buck2 => "buck2 build <target>",
     }
 }
 
@@ -357,12 +404,8 @@ fn extract_balanced_block(content: &str, start_pos: usize) -> Option<(usize, usi
     while i < bytes.len() {
         match bytes[i] {
             b'"' if !in_string => in_string = true,
-            b'"' if in_string => {
-                // Check for escaped quote
-                if i > 0 && bytes[i - 1] != b'\\' {
-                    in_string = false;
-                }
-            }
+            // Closing quote, unless escaped
+            b'"' if in_string && i > 0 && bytes[i - 1] != b'\\' => in_string = false,
             b'{' if !in_string => depth += 1,
             b'}' if !in_string => {
                 depth -= 1;
@@ -608,6 +651,291 @@ fn build_gradle(project_dir: &Path, subproject: Option<&str>) -> Result<PathBuf,
     )
 }
 
+fn build_sbt(project_dir: &Path, project: Option<&str>) -> Result<PathBuf, PackError> {
+    let cmd = if project_dir.join("sbt").is_file() {
+        "./sbt"
+    } else {
+        ensure_command_exists("sbt")?;
+        "sbt"
+    };
+    let task = match project {
+        Some(p) => format!("{p}/assembly"),
+        None => "assembly".to_string(),
+    };
+    tracing::info!("running: {cmd} {task}");
+
+    let output = Command::new(cmd)
+        .args(["--batch", &task])
+        .current_dir(project_dir)
+        .output()
+        .map_err(|e| PackError::BuildFailed(format!("failed to run sbt: {e}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let formatted =
+            crate::diagnostic::format_build_error(&stderr, &stdout, BuildSystem::Sbt, project_dir);
+        return Err(PackError::BuildFailed(format!(
+            "sbt {task} failed:\n{formatted}"
+        )));
+    }
+
+    find_sbt_assembly(project_dir)
+}
+
+/// sbt-assembly writes `target/scala-<version>/<name>-assembly-<version>.jar`
+/// under the root project or, in multi-project builds, under the subproject's
+/// directory (`server/target/...`, `modules/server/target/...`). The newest
+/// one is the one just built.
+fn find_sbt_assembly(project_dir: &Path) -> Result<PathBuf, PackError> {
+    let mut jars = Vec::new();
+    collect_sbt_assemblies(project_dir, 0, &mut jars);
+    newest(jars).ok_or_else(|| PackError::UberjarNotFound(project_dir.join("target")))
+}
+
+/// Subproject directories are searched two levels deep, skipping sbt's own
+/// `project/` build and hidden directories.
+fn collect_sbt_assemblies(dir: &Path, depth: usize, out: &mut Vec<PathBuf>) {
+    if let Ok(entries) = std::fs::read_dir(dir.join("target")) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let is_scala_dir = entry.file_name().to_string_lossy().starts_with("scala-");
+            if is_scala_dir && entry.path().is_dir() {
+                let mut jars = Vec::new();
+                collect_jars(&entry.path(), &mut jars);
+                out.extend(jars.into_iter().filter(|p| {
+                    p.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.contains("-assembly"))
+                }));
+            }
+        }
+    }
+    if depth == 2 {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let skip = name.starts_with('.') || matches!(name.as_str(), "target" | "project");
+        if !skip && entry.path().is_dir() {
+            collect_sbt_assemblies(&entry.path(), depth + 1, out);
+        }
+    }
+}
+
+/// Mill projects name their module in the build file; the root module
+/// (`extends RootModule`) has no prefix. Without a configured `module`, the
+/// first module object in the build file is assembled.
+fn build_mill(project_dir: &Path, module: Option<&str>) -> Result<PathBuf, PackError> {
+    let cmd = if project_dir.join("mill").is_file() {
+        "./mill"
+    } else {
+        ensure_command_exists("mill")?;
+        "mill"
+    };
+    let module = module
+        .map(str::to_string)
+        .or_else(|| detect_mill_module(project_dir));
+    let task = match module.as_deref() {
+        Some("") | None => "assembly".to_string(),
+        Some(m) => format!("{m}.assembly"),
+    };
+    tracing::info!("running: {cmd} {task}");
+
+    let output = Command::new(cmd)
+        .arg(&task)
+        .current_dir(project_dir)
+        .output()
+        .map_err(|e| PackError::BuildFailed(format!("failed to run mill: {e}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let formatted =
+            crate::diagnostic::format_build_error(&stderr, &stdout, BuildSystem::Mill, project_dir);
+        return Err(PackError::BuildFailed(format!(
+            "mill {task} failed:\n{formatted}"
+        )));
+    }
+
+    find_mill_assembly(project_dir, module.as_deref())
+}
+
+/// Returns the first module object defined in `build.mill` / `build.sc`
+/// (`""` for a root module), or `None` when the file can't be read.
+pub fn detect_mill_module(project_dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(project_dir.join("build.mill"))
+        .or_else(|_| std::fs::read_to_string(project_dir.join("build.sc")))
+        .ok()?;
+    parse_mill_module(&content)
+}
+
+fn parse_mill_module(content: &str) -> Option<String> {
+    let re = regex::Regex::new(r"(?m)^object\s+(`[^`]+`|\w+)\s+extends\s+([^{\n]*)").unwrap();
+    for caps in re.captures_iter(content) {
+        let name = caps[1].trim_matches('`');
+        let parents = &caps[2];
+        if parents.contains("RootModule") || name == "package" {
+            return Some(String::new());
+        }
+        if parents.contains("Module") {
+            return Some(name.to_string());
+        }
+    }
+    None
+}
+
+/// Mill writes `out/<module>/assembly.dest/out.jar`.
+fn find_mill_assembly(project_dir: &Path, module: Option<&str>) -> Result<PathBuf, PackError> {
+    let out_dir = project_dir.join("out");
+    let expected = match module {
+        Some("") | None => out_dir.join("assembly.dest").join("out.jar"),
+        Some(m) => out_dir
+            .join(m.replace('.', "/"))
+            .join("assembly.dest")
+            .join("out.jar"),
+    };
+    if expected.is_file() {
+        return Ok(expected);
+    }
+
+    let mut jars = Vec::new();
+    collect_jars(&out_dir, &mut jars);
+    jars.retain(|p| p.ends_with("assembly.dest/out.jar"));
+    newest(jars).ok_or(PackError::UberjarNotFound(out_dir))
+}
+
+fn collect_jars(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_jars(&path, out);
+        } else if path.extension().is_some_and(|ext| ext == "jar") {
+            out.push(path);
+        }
+    }
+}
+
+fn newest(mut paths: Vec<PathBuf>) -> Option<PathBuf> {
+    paths.sort_by_key(|p| std::fs::metadata(p).and_then(|m| m.modified()).ok());
+    paths.pop()
+}
+
+/// Builds the deploy JAR of a Bazel `java_binary` (its self-contained
+/// `<name>_deploy.jar` output) and asks Bazel where it was written.
+fn build_bazel(project_dir: &Path, target: Option<&str>) -> Result<PathBuf, PackError> {
+    let target = target.ok_or(PackError::MissingBuildTarget {
+        system: "Bazel",
+        option: "bazel_target",
+        flag: "bazel-target",
+    })?;
+    let deploy_jar = if target.ends_with("_deploy.jar") {
+        target.to_string()
+    } else {
+        format!("{target}_deploy.jar")
+    };
+    // bazelisk is the usual way to get a `bazel` matching .bazelversion
+    let cmd = if which::which("bazel").is_ok() {
+        "bazel"
+    } else {
+        ensure_command_exists("bazelisk")?;
+        "bazelisk"
+    };
+    tracing::info!("running: {cmd} build {deploy_jar}");
+
+    let output = Command::new(cmd)
+        .args(["build", &deploy_jar])
+        .current_dir(project_dir)
+        .output()
+        .map_err(|e| PackError::BuildFailed(format!("failed to run {cmd}: {e}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let formatted = crate::diagnostic::format_build_error(
+            &stderr,
+            &stdout,
+            BuildSystem::Bazel,
+            project_dir,
+        );
+        return Err(PackError::BuildFailed(format!(
+            "{cmd} build {deploy_jar} failed:\n{formatted}"
+        )));
+    }
+
+    tracing::info!("running: {cmd} cquery --output=files {deploy_jar}");
+    let output = Command::new(cmd)
+        .args(["cquery", "--output=files", &deploy_jar])
+        .current_dir(project_dir)
+        .output()
+        .map_err(|e| PackError::BuildFailed(format!("failed to run {cmd}: {e}")))?;
+    if !output.status.success() {
+        return Err(PackError::BuildFailed(format!(
+            "{cmd} cquery --output=files {deploy_jar} failed:\n{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    parse_output_jar(&stdout, project_dir)
+        .ok_or_else(|| PackError::UberjarNotFound(project_dir.join("bazel-bin")))
+}
+
+/// Builds a Buck2 `java_binary`, whose default output is already the fat JAR.
+fn build_buck2(project_dir: &Path, target: Option<&str>) -> Result<PathBuf, PackError> {
+    let target = target.ok_or(PackError::MissingBuildTarget {
+        system: "Buck2",
+        option: "buck_target",
+        flag: "buck-target",
+    })?;
+    ensure_command_exists("buck2")?;
+    tracing::info!("running: buck2 build {target} --show-full-output");
+
+    let output = Command::new("buck2")
+        .args(["build", target, "--show-full-output"])
+        .current_dir(project_dir)
+        .output()
+        .map_err(|e| PackError::BuildFailed(format!("failed to run buck2: {e}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let formatted = crate::diagnostic::format_build_error(
+            &stderr,
+            &stdout,
+            BuildSystem::Buck2,
+            project_dir,
+        );
+        return Err(PackError::BuildFailed(format!(
+            "buck2 build {target} failed:\n{formatted}"
+        )));
+    }
+
+    // Each line is "<target> <absolute output path>"
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let paths: String = stdout
+        .lines()
+        .filter_map(|l| l.split_whitespace().last())
+        .collect::<Vec<_>>()
+        .join("\n");
+    parse_output_jar(&paths, project_dir)
+        .ok_or_else(|| PackError::UberjarNotFound(project_dir.join("buck-out")))
+}
+
+/// First `.jar` among output paths, resolved against the workspace root.
+fn parse_output_jar(paths: &str, project_dir: &Path) -> Option<PathBuf> {
+    paths
+        .lines()
+        .map(str::trim)
+        .find(|l| l.ends_with(".jar"))
+        .map(|l| project_dir.join(l))
+}
+
 fn find_jar_in_dirs(project_dir: &Path, dirs: &[&str]) -> Result<PathBuf, PackError> {
     for dir in dirs {
         let target_dir = project_dir.join(dir);
@@ -759,6 +1087,108 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn find_sbt_assembly_in_scala_dir() {
+        let dir = tempdir().unwrap();
+        let scala = dir.path().join("target").join("scala-3.4.2");
+        std::fs::create_dir_all(&scala).unwrap();
+        std::fs::write(scala.join("app_3-0.1.0.jar"), "thin").unwrap();
+        std::fs::write(scala.join("app-assembly-0.1.0.jar"), "fat").unwrap();
+
+        let jar = find_sbt_assembly(dir.path()).unwrap();
+        assert!(jar.ends_with("app-assembly-0.1.0.jar"));
+    }
+
+    #[test]
+    fn find_sbt_assembly_error_without_assembly() {
+        let dir = tempdir().unwrap();
+        let scala = dir.path().join("target").join("scala-2.13");
+        std::fs::create_dir_all(&scala).unwrap();
+        std::fs::write(scala.join("app_2.13-0.1.0.jar"), "thin").unwrap();
+        assert!(find_sbt_assembly(dir.path()).is_err());
+    }
+
+    #[test]
+    fn find_sbt_assembly_in_subproject() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("target").join("scala-3.4.2")).unwrap();
+        let scala = dir
+            .path()
+            .join("modules")
+            .join("server")
+            .join("target")
+            .join("scala-3.4.2");
+        std::fs::create_dir_all(&scala).unwrap();
+        std::fs::write(scala.join("server-assembly-0.1.0.jar"), "fat").unwrap();
+        let plugins = dir.path().join("project").join("target").join("scala-2.12");
+        std::fs::create_dir_all(&plugins).unwrap();
+        std::fs::write(plugins.join("plugin-assembly-1.0.jar"), "build").unwrap();
+
+        let jar = find_sbt_assembly(dir.path()).unwrap();
+        assert!(jar.ends_with("modules/server/target/scala-3.4.2/server-assembly-0.1.0.jar"));
+    }
+
+    #[test]
+    fn parse_mill_module_names() {
+        let build = "import mill._, scalalib._\n\nobject app extends ScalaModule {\n  def scalaVersion = \"3.4.2\"\n}\n";
+        assert_eq!(parse_mill_module(build).as_deref(), Some("app"));
+
+        let root = "package build\nimport mill._\nobject `package` extends RootModule with JavaModule {\n}\n";
+        assert_eq!(parse_mill_module(root).as_deref(), Some(""));
+
+        let helpers = "object versions {\n}\nobject server extends JavaModule\n";
+        assert_eq!(parse_mill_module(helpers).as_deref(), Some("server"));
+
+        assert_eq!(parse_mill_module("// nothing here"), None);
+    }
+
+    #[test]
+    fn find_mill_assembly_for_module() {
+        let dir = tempdir().unwrap();
+        let dest = dir.path().join("out").join("app").join("assembly.dest");
+        std::fs::create_dir_all(&dest).unwrap();
+        std::fs::write(dest.join("out.jar"), "fat").unwrap();
+
+        let jar = find_mill_assembly(dir.path(), Some("app")).unwrap();
+        assert!(jar.ends_with("out/app/assembly.dest/out.jar"));
+        // Unknown module: search out/ for any assembly
+        let jar = find_mill_assembly(dir.path(), None).unwrap();
+        assert!(jar.ends_with("out/app/assembly.dest/out.jar"));
+    }
+
+    #[test]
+    fn parse_output_jar_from_cquery() {
+        let root = Path::new("/repo");
+        let stdout = "bazel-out/k8-fastbuild/bin/svc/app_deploy.jar\n";
+        assert_eq!(
+            parse_output_jar(stdout, root),
+            Some(PathBuf::from(
+                "/repo/bazel-out/k8-fastbuild/bin/svc/app_deploy.jar"
+            ))
+        );
+        // Absolute paths (buck2 --show-full-output) are kept as is
+        assert_eq!(
+            parse_output_jar("/repo/buck-out/v2/gen/root/svc/__app__/app.jar", root),
+            Some(PathBuf::from(
+                "/repo/buck-out/v2/gen/root/svc/__app__/app.jar"
+            ))
+        );
+        assert_eq!(parse_output_jar("", root), None);
+    }
+
+    #[test]
+    fn bazel_requires_a_target() {
+        let dir = tempdir().unwrap();
+        let err = build_uberjar(dir.path(), BuildSystem::Bazel, None).unwrap_err();
+        assert!(matches!(
+            err,
+            PackError::MissingBuildTarget {
+                option: "bazel_target",
+                ..
+            }
+        ));
+    }
+
     #[test]
     fn find_uberjar_error_when_no_target_dir() {
         let dir = tempdir().unwrap();
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 
 #[derive(Parser)]
 #[command(
@@ -16,73 +16,112 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum Command {
     /// Build a self-contained binary from a JVM project or JAR
-    Build {
+    Build(Box<BuildArgs>),
+
+    /// Analyze a JAR or project and report size breakdown
+    Analyze {
         /// Path to project directory or pre-built JAR file
         #[arg(short, long, default_value = ".")]
         input: PathBuf,
 
-        /// Output binary path
-        #[arg(short, long, default_value = "./dist/app")]
-        output: PathBuf,
+        /// JAR, project or binary to analyze (same as --input)
+        #[arg(conflicts_with = "input")]
+        path: Option<PathBuf>,
 
-        /// Java version (11, 17, 21). Auto-detected from JAR if not specified.
-        #[arg(long)]
-        java_version: Option<u8>,
+        /// Output format: table (stderr), json or markdown (stdout)
+        #[arg(long, default_value = "table")]
+        format: String,
 
-        /// Target platform (linux-x64, linux-aarch64, macos-x64, macos-aarch64)
+        /// Compare against an older JAR, project or jbundle binary
         #[arg(long)]
-        target: Option<String>,
+        baseline: Option<PathBuf>,
 
-        /// Extra JVM arguments passed to the application
-        #[arg(long)]
-        jvm_args: Vec<String>,
+        /// Fail when the JAR grew more than this vs. the baseline (e.g. 5MB or 10%)
+        #[arg(long, requires = "baseline")]
+        max_growth: Option<String>,
+    },
+
+    /// Report the licenses of bundled dependencies and check them against a policy
+    Licenses {
+        /// Path to project directory or pre-built JAR file
+        #[arg(short, long, default_value = ".")]
+        input: PathBuf,
 
-        /// Shrink the uberjar by removing non-essential files and recompressing
-        #[arg(long, default_value_t = false, num_args = 0..=1, default_missing_value = "true")]
-        shrink: bool,
+        /// Allowed licenses (SPDX ids, comma-separated); anything else fails
+        #[arg(long, value_delimiter = ',')]
+        allow: Vec<String>,
 
-        /// JVM startup profile (cli: fast startup, server: throughput optimized)
-        #[arg(long)]
-        profile: Option<String>,
+        /// Denied licenses (SPDX ids, comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        deny: Vec<String>,
 
-        /// Disable AppCDS archive generation
+        /// Write an aggregated THIRD_PARTY_NOTICES file
         #[arg(long)]
-        no_appcds: bool,
+        notices: Option<PathBuf>,
 
-        /// Enable CRaC checkpoint for instant restore (Linux only)
+        /// jlinked runtime directory or jbundle binary whose legal/ notices to include
         #[arg(long)]
-        crac: bool,
+        runtime: Option<PathBuf>,
+    },
 
-        /// Gradle subproject to build (for multi-project builds)
+    /// Check bundled dependencies and the JDK against an offline advisory database
+    Audit {
+        /// Path to project directory, pre-built JAR file or jbundle binary
+        #[arg(short, long, default_value = ".")]
+        input: PathBuf,
+
+        /// OSV advisory dump: a directory of JSON files, a .zip, or a single .json
         #[arg(long)]
-        gradle_project: Option<String>,
+        advisories: Option<PathBuf>,
 
-        /// Build all application subprojects (Gradle multi-project)
+        /// JSON list of JDK advisories with the first fixed release per feature version
         #[arg(long)]
-        all: bool,
+        jdk_advisories: Option<PathBuf>,
 
-        /// Manual module list (bypasses jdeps detection, comma-separated)
+        /// Runtime directory or jbundle binary whose JDK version to check
+        /// (default: the binary itself, or the project's cached JDK)
         #[arg(long)]
-        modules: Option<String>,
+        runtime: Option<PathBuf>,
 
-        /// Path to existing jlink runtime to reuse
+        /// Fail when a finding is at or above this severity (low, medium, high, critical)
         #[arg(long)]
-        jlink_runtime: Option<PathBuf>,
+        fail_on: Option<String>,
+    },
 
-        /// Enable verbose output (show build commands and details)
+    /// Create a patch holding only the layers that changed between two binaries
+    Diff {
+        /// Binary currently deployed
+        old: PathBuf,
+
+        /// New binary
+        new: PathBuf,
+
+        /// Output patch path
         #[arg(short, long)]
-        verbose: bool,
+        output: PathBuf,
+    },
 
-        /// Use a compact banner in the wrapper
-        #[arg(long)]
-        compact_banner: bool,
+    /// Apply a patch created by `jbundle diff` to rebuild the new binary
+    Patch {
+        /// Binary the patch was created against
+        base: PathBuf,
+
+        /// Patch file
+        patch: PathBuf,
+
+        /// Output binary path
+        #[arg(short, long)]
+        output: PathBuf,
     },
 
-    /// Analyze a JAR or project and report size breakdown
-    Analyze {
-        /// Path to project directory or pre-built JAR file
-        #[arg(short, long, default_value = ".")]
-        input: PathBuf,
+    /// Verify a binary's signature against a public key
+    Verify {
+        /// Binary to verify
+        binary: PathBuf,
+
+        /// Ed25519 public key (PEM)
+        #[arg(long)]
+        pubkey: PathBuf,
     },
 
     /// Clean the jbundle cache
@@ -91,3 +130,138 @@ pub enum Command {
     /// Show cache and configuration info
     Info,
 }
+
+#[derive(Args)]
+pub struct BuildArgs {
+    /// Path to project directory or pre-built JAR file
+    #[arg(short, long, default_value = ".")]
+    pub input: PathBuf,
+
+    /// Output binary path
+    #[arg(short, long, default_value = "./dist/app")]
+    pub output: PathBuf,
+
+    /// Java version (11, 17, 21). Auto-detected from JAR if not specified.
+    #[arg(long)]
+    pub java_version: Option<u8>,
+
+    /// Target platform (linux-x64, linux-aarch64, macos-x64, macos-aarch64)
+    #[arg(long)]
+    pub target: Option<String>,
+
+    /// Extra JVM arguments passed to the application
+    #[arg(long)]
+    pub jvm_args: Vec<String>,
+
+    /// Shrink the uberjar by removing non-essential files and recompressing
+    #[arg(long, default_value_t = false, num_args = 0..=1, default_missing_value = "true")]
+    pub shrink: bool,
+
+    /// JVM startup profile (cli: fast startup, server: throughput optimized)
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Disable AppCDS archive generation
+    #[arg(long)]
+    pub no_appcds: bool,
+
+    /// Enable CRaC checkpoint for instant restore (Linux only)
+    #[arg(long)]
+    pub crac: bool,
+
+    /// Ship dependency JARs as a separately cached layer and launch with -cp
+    #[arg(long)]
+    pub thin: bool,
+
+    /// Gradle subproject to build (for multi-project builds)
+    #[arg(long)]
+    pub gradle_project: Option<String>,
+
+    /// Maven module to build (for multi-module reactor builds)
+    #[arg(long)]
+    pub maven_module: Option<String>,
+
+    /// Bazel java_binary target to bundle (e.g. //svc:app)
+    #[arg(long)]
+    pub bazel_target: Option<String>,
+
+    /// Buck2 java_binary target to bundle (e.g. //svc:app)
+    #[arg(long)]
+    pub buck_target: Option<String>,
+
+    /// Mill module to assemble (e.g. app or services.api)
+    #[arg(long)]
+    pub mill_module: Option<String>,
+
+    /// sbt subproject to assemble (e.g. server)
+    #[arg(long)]
+    pub sbt_project: Option<String>,
+
+    /// Build all application subprojects (Gradle) or modules (Maven)
+    #[arg(long)]
+    pub all: bool,
+
+    /// Manual module list (bypasses jdeps detection, comma-separated)
+    #[arg(long)]
+    pub modules: Option<String>,
+
+    /// Don't add modules inferred from runtime lookups in the JAR (jdeps only)
+    #[arg(long)]
+    pub no_module_hints: bool,
+
+    /// Run the app once on the full JDK and record the modules it loads in jbundle.toml
+    #[arg(long, conflicts_with = "all")]
+    pub trace_modules: bool,
+
+    /// Arguments passed to the app while tracing (after --)
+    #[arg(last = true, requires = "trace_modules")]
+    pub trace_args: Vec<String>,
+
+    /// Path to existing jlink runtime to reuse
+    #[arg(long)]
+    pub jlink_runtime: Option<PathBuf>,
+
+    /// Enable verbose output (show build commands and details)
+    #[arg(short, long)]
+    pub verbose: bool,
+
+    /// Use a compact banner in the wrapper (same as --banner compact)
+    #[arg(long)]
+    pub compact_banner: bool,
+
+    /// Startup banner (full, compact, none, custom)
+    #[arg(long)]
+    pub banner: Option<String>,
+
+    /// Text file shown as the banner when --banner custom
+    #[arg(long)]
+    pub banner_file: Option<PathBuf>,
+
+    /// Only show the banner on first run (when layers are extracted)
+    #[arg(long)]
+    pub banner_first_run_only: bool,
+
+    /// Prefix for the binary's reserved launcher flags (default: jbundle)
+    #[arg(long)]
+    pub launcher_flag_prefix: Option<String>,
+
+    /// Update manifest URL enabling the binary's --self-update flag
+    #[arg(long)]
+    pub update_url: Option<String>,
+
+    /// Ed25519 private key (PKCS#8 PEM) used to sign the binary
+    #[arg(long)]
+    pub sign_key: Option<PathBuf>,
+
+    /// Make the launcher refuse to run when its signature doesn't verify
+    #[arg(long)]
+    pub require_signature: bool,
+
+    /// Write an SBOM next to the binary (cyclonedx, spdx)
+    #[arg(long)]
+    pub sbom: Option<String>,
+
+    /// Embed the SBOM in the binary (defaults to cyclonedx without --sbom)
+    #[arg(long)]
+    pub embed_sbom: bool,
+}
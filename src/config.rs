@@ -1,6 +1,10 @@
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+use crate::budget::SizeBudget;
 use crate::error::PackError;
+use crate::jlink::JlinkOptions;
+use crate::smoke::SmokeTest;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum JvmProfile {
@@ -8,6 +12,9 @@ pub enum JvmProfile {
     Server,
 }
 
+/// Default prefix for the launcher's reserved flags (`--jbundle-info`, ...)
+pub const DEFAULT_FLAG_PREFIX: &str = "jbundle";
+
 /// Known JVM garbage collector flags
 const GC_FLAGS: &[&str] = &[
     "-XX:+UseSerialGC",
@@ -55,6 +62,30 @@ impl JvmProfile {
     }
 }
 
+/// Startup banner printed by the launcher to stderr
+#[derive(Debug, Clone, PartialEq)]
+pub enum Banner {
+    Full,
+    Compact,
+    None,
+    Custom(String),
+}
+
+impl Banner {
+    /// Parses a banner mode. `custom_text` is required for `"custom"`.
+    pub fn parse(mode: &str, custom_text: Option<String>) -> Result<Self, PackError> {
+        match mode {
+            "full" => Ok(Banner::Full),
+            "compact" => Ok(Banner::Compact),
+            "none" => Ok(Banner::None),
+            "custom" => custom_text
+                .map(Banner::Custom)
+                .ok_or(PackError::MissingBannerFile),
+            other => Err(PackError::InvalidBanner(other.to_string())),
+        }
+    }
+}
+
 /// Result of GC conflict detection
 #[derive(Debug)]
 pub struct GcConflict {
@@ -83,12 +114,157 @@ pub fn detect_gc_conflict(profile: &JvmProfile, jvm_args: &[String]) -> Option<G
     None
 }
 
+/// Working directory the launcher switches to before starting the JVM
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WorkDir {
+    /// Keep the caller's working directory
+    Cwd,
+    /// Switch to the directory containing the binary
+    Binary,
+}
+
+impl WorkDir {
+    pub fn from_str(s: &str) -> Result<Self, PackError> {
+        match s {
+            "cwd" => Ok(WorkDir::Cwd),
+            "binary" => Ok(WorkDir::Binary),
+            other => Err(PackError::InvalidWorkDir(other.to_string())),
+        }
+    }
+}
+
+/// HotSpot VM kept in the jlinked runtime (`--vm`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JlinkVm {
+    Server,
+    Client,
+    Minimal,
+}
+
+impl JlinkVm {
+    pub fn from_str(s: &str) -> Result<Self, PackError> {
+        match s {
+            "server" => Ok(JlinkVm::Server),
+            "client" => Ok(JlinkVm::Client),
+            "minimal" => Ok(JlinkVm::Minimal),
+            other => Err(PackError::InvalidJlinkVm(other.to_string())),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            JlinkVm::Server => "server",
+            JlinkVm::Client => "client",
+            JlinkVm::Minimal => "minimal",
+        }
+    }
+}
+
+/// Software bill of materials format written next to the binary
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SbomFormat {
+    CycloneDx,
+    Spdx,
+}
+
+impl SbomFormat {
+    pub fn from_str(s: &str) -> Result<Self, PackError> {
+        match s {
+            "cyclonedx" => Ok(SbomFormat::CycloneDx),
+            "spdx" => Ok(SbomFormat::Spdx),
+            other => Err(PackError::InvalidSbomFormat(other.to_string())),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SbomFormat::CycloneDx => "cyclonedx",
+            SbomFormat::Spdx => "spdx",
+        }
+    }
+
+    /// Conventional file suffix for the format's JSON serialization
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SbomFormat::CycloneDx => "cdx.json",
+            SbomFormat::Spdx => "spdx.json",
+        }
+    }
+}
+
+/// Validates that all environment variable names are valid shell identifiers.
+pub fn validate_env(env: &BTreeMap<String, String>) -> Result<(), PackError> {
+    for name in env.keys() {
+        let mut chars = name.chars();
+        let valid = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(PackError::InvalidEnvVar(name.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// Validates a launcher flag prefix. It is embedded in the stub as a `case`
+/// pattern, so only ASCII letters, digits and `-` are allowed.
+pub fn validate_flag_prefix(prefix: &str) -> Result<(), PackError> {
+    let valid = !prefix.is_empty()
+        && !prefix.starts_with('-')
+        && prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(PackError::InvalidFlagPrefix(prefix.to_string()))
+    }
+}
+
+/// The launcher downloads with curl/wget, so only URL schemes they share.
+pub fn validate_update_url(url: &str) -> Result<(), PackError> {
+    let valid = ["https://", "http://", "file://"]
+        .iter()
+        .any(|scheme| url.len() > scheme.len() && url.starts_with(scheme));
+    if valid && !url.chars().any(char::is_whitespace) {
+        Ok(())
+    } else {
+        Err(PackError::InvalidUpdateUrl(url.to_string()))
+    }
+}
+
+/// Parses a size such as `80MB`, `1.5GB`, `512K` or `1048576`. Units are
+/// binary (1 MB = 1024 KB), matching how jbundle prints sizes.
+pub fn parse_size(s: &str) -> Result<u64, PackError> {
+    let trimmed = s.trim();
+    let split = trimmed
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        _ => return Err(PackError::InvalidSize(s.to_string())),
+    };
+    let value: f64 = number
+        .parse()
+        .map_err(|_| PackError::InvalidSize(s.to_string()))?;
+    Ok((value * multiplier as f64).round() as u64)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum BuildSystem {
     DepsEdn,
     Leiningen,
     Maven,
     Gradle,
+    Sbt,
+    Mill,
+    Bazel,
+    Buck2,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -146,6 +322,15 @@ impl Target {
         }
     }
 
+    /// Name as accepted by `from_str` (e.g. `linux-x64`)
+    pub fn name(&self) -> String {
+        let os = match self.os {
+            TargetOs::Linux => "linux",
+            TargetOs::MacOs => "macos",
+        };
+        format!("{os}-{}", self.adoptium_arch())
+    }
+
     pub fn adoptium_os(&self) -> &'static str {
         match self.os {
             TargetOs::Linux => "linux",
@@ -173,15 +358,57 @@ pub struct BuildConfig {
     pub profile: JvmProfile,
     pub appcds: bool,
     pub crac: bool,
-    pub compact_banner: bool,
+    /// Split dependency JARs into their own layer (launch with -cp)
+    pub thin: bool,
+    pub banner: Banner,
+    /// Only show the banner on first run (when layers are extracted)
+    pub banner_first_run_only: bool,
     /// Gradle subproject to build (for multi-project builds)
     pub gradle_project: Option<String>,
-    /// Build all application subprojects (Gradle multi-project)
+    /// Maven module to build (for multi-module reactor builds)
+    pub maven_module: Option<String>,
+    /// Build all application subprojects (Gradle) or modules (Maven)
     pub build_all: bool,
+    /// Bazel `java_binary` target (e.g. `//svc:app`)
+    pub bazel_target: Option<String>,
+    /// Buck2 `java_binary` target (e.g. `//svc:app`)
+    pub buck_target: Option<String>,
+    /// Mill module to assemble (default: first module in the build file)
+    pub mill_module: Option<String>,
+    /// sbt subproject to assemble (default: the root project)
+    pub sbt_project: Option<String>,
     /// Manual module override (bypasses jdeps detection)
     pub modules_override: Option<Vec<String>>,
+    /// Run the app with these arguments to trace the modules it loads
+    pub trace_modules: Option<Vec<String>>,
     /// Path to existing jlink runtime to reuse
     pub jlink_runtime: Option<PathBuf>,
+    /// jlink tuning; also part of the runtime cache key
+    pub jlink_options: JlinkOptions,
+    /// Add modules inferred from runtime lookups in the JAR to jdeps' set
+    pub module_hints: bool,
+    /// Prefix for the launcher's reserved flags
+    pub flag_prefix: String,
+    /// Environment variables exported by the launcher before starting the JVM
+    pub env: BTreeMap<String, String>,
+    /// Working directory for the JVM process
+    pub workdir: WorkDir,
+    /// Files and directories embedded as the resources layer
+    pub resources: Vec<PathBuf>,
+    /// Update manifest URL baked into the launcher for `--self-update`
+    pub update_url: Option<String>,
+    /// Ed25519 private key used to sign the binary
+    pub sign_key: Option<PathBuf>,
+    /// Bake the public key into the launcher and refuse unsigned payloads
+    pub require_signature: bool,
+    /// SBOM written next to the binary
+    pub sbom: Option<SbomFormat>,
+    /// Also embed the SBOM in the binary as a payload layer
+    pub embed_sbom: bool,
+    /// Size limits checked after packing
+    pub budget: SizeBudget,
+    /// Smoke test run against the packed binary
+    pub smoke_test: Option<SmokeTest>,
 }
 
 impl BuildConfig {
@@ -219,6 +446,20 @@ mod tests {
         assert_eq!(t.arch, TargetArch::Aarch64);
     }
 
+    #[test]
+    fn sbom_format_from_str() {
+        assert_eq!(
+            SbomFormat::from_str("cyclonedx").unwrap(),
+            SbomFormat::CycloneDx
+        );
+        assert_eq!(SbomFormat::from_str("spdx").unwrap(), SbomFormat::Spdx);
+        assert!(matches!(
+            SbomFormat::from_str("swid"),
+            Err(PackError::InvalidSbomFormat(_))
+        ));
+        assert_eq!(SbomFormat::Spdx.extension(), "spdx.json");
+    }
+
     #[test]
     fn target_from_str_invalid() {
         assert!(Target::from_str("windows-x64").is_none());
@@ -327,6 +568,86 @@ mod tests {
         assert!(conflict.is_none());
     }
 
+    #[test]
+    fn banner_parse_modes() {
+        assert_eq!(Banner::parse("full", None).unwrap(), Banner::Full);
+        assert_eq!(Banner::parse("compact", None).unwrap(), Banner::Compact);
+        assert_eq!(Banner::parse("none", None).unwrap(), Banner::None);
+        assert_eq!(
+            Banner::parse("custom", Some("hi".to_string())).unwrap(),
+            Banner::Custom("hi".to_string())
+        );
+    }
+
+    #[test]
+    fn banner_parse_errors() {
+        assert!(Banner::parse("fancy", None).is_err());
+        assert!(Banner::parse("custom", None).is_err());
+    }
+
+    #[test]
+    fn workdir_from_str() {
+        assert_eq!(WorkDir::from_str("cwd").unwrap(), WorkDir::Cwd);
+        assert_eq!(WorkDir::from_str("binary").unwrap(), WorkDir::Binary);
+        assert!(WorkDir::from_str("home").is_err());
+    }
+
+    #[test]
+    fn validate_env_names() {
+        let mut env = BTreeMap::new();
+        env.insert("LANG".to_string(), "C.UTF-8".to_string());
+        env.insert("_MY_VAR2".to_string(), "x".to_string());
+        assert!(validate_env(&env).is_ok());
+
+        env.insert("2BAD".to_string(), "x".to_string());
+        assert!(validate_env(&env).is_err());
+
+        let mut env = BTreeMap::new();
+        env.insert("BAD-NAME".to_string(), "x".to_string());
+        assert!(validate_env(&env).is_err());
+    }
+
+    #[test]
+    fn validate_flag_prefix_accepts_simple_names() {
+        assert!(validate_flag_prefix("jbundle").is_ok());
+        assert!(validate_flag_prefix("my-tool").is_ok());
+    }
+
+    #[test]
+    fn validate_flag_prefix_rejects_invalid() {
+        assert!(validate_flag_prefix("").is_err());
+        assert!(validate_flag_prefix("-x").is_err());
+        assert!(validate_flag_prefix("a b").is_err());
+        assert!(validate_flag_prefix("a)b").is_err());
+    }
+
+    #[test]
+    fn validate_update_url_schemes() {
+        assert!(validate_update_url("https://example.com/mytool/latest.json").is_ok());
+        assert!(validate_update_url("http://10.0.0.1:8080/update.json").is_ok());
+        assert!(validate_update_url("ftp://example.com/update.json").is_err());
+        assert!(validate_update_url("https://").is_err());
+        assert!(validate_update_url("https://example.com/a b.json").is_err());
+    }
+
+    #[test]
+    fn parse_size_units() {
+        assert_eq!(parse_size("1048576").unwrap(), 1_048_576);
+        assert_eq!(parse_size("80MB").unwrap(), 80 * 1024 * 1024);
+        assert_eq!(parse_size("512k").unwrap(), 512 * 1024);
+        assert_eq!(parse_size("1.5 GiB").unwrap(), 3 * 512 * 1024 * 1024);
+        assert_eq!(parse_size("100B").unwrap(), 100);
+        assert!(parse_size("80 parsecs").is_err());
+        assert!(parse_size("MB").is_err());
+    }
+
+    #[test]
+    fn target_name_roundtrip() {
+        for name in ["linux-x64", "linux-aarch64", "macos-x64", "macos-aarch64"] {
+            assert_eq!(Target::from_str(name).unwrap().name(), name);
+        }
+    }
+
     #[test]
     fn jvm_profile_gc_flag() {
         assert_eq!(JvmProfile::Cli.gc_flag(), Some("-XX:+UseSerialGC"));
@@ -3,20 +3,28 @@ use std::path::Path;
 use crate::config::BuildSystem;
 use crate::error::PackError;
 use crate::gradle::{GradleProject, Subproject};
+use crate::maven::{MavenProject, Module};
 
 /// Result of build system detection.
 #[derive(Debug)]
 pub enum DetectedBuild {
-    /// Simple build system (deps.edn, leiningen, maven, single-module gradle)
+    /// Simple build system (deps.edn, leiningen, maven, single-module gradle, sbt,
+    /// mill, bazel, buck2)
     Simple(BuildSystem),
     /// Gradle multi-project build with application subprojects
     GradleMultiProject {
         project: GradleProject,
         app_subprojects: Vec<Subproject>,
     },
+    /// Maven reactor build with fat-JAR application modules
+    MavenMultiModule {
+        project: MavenProject,
+        app_modules: Vec<Module>,
+    },
 }
 
-/// Enhanced detection that identifies Gradle multi-project builds.
+/// Enhanced detection that identifies Gradle multi-project and Maven
+/// multi-module builds.
 pub fn detect_build_system_enhanced(project_dir: &Path) -> Result<DetectedBuild, PackError> {
     // Clojure build systems
     if project_dir.join("deps.edn").exists() {
@@ -27,6 +35,19 @@ pub fn detect_build_system_enhanced(project_dir: &Path) -> Result<DetectedBuild,
     }
     // Java build systems
     if project_dir.join("pom.xml").exists() {
+        if let Some(maven_project) = MavenProject::parse(project_dir) {
+            let app_modules: Vec<_> = maven_project
+                .application_modules()
+                .into_iter()
+                .cloned()
+                .collect();
+            if maven_project.is_multi_module() && !app_modules.is_empty() {
+                return Ok(DetectedBuild::MavenMultiModule {
+                    project: maven_project,
+                    app_modules,
+                });
+            }
+        }
         return Ok(DetectedBuild::Simple(BuildSystem::Maven));
     }
 
@@ -52,6 +73,25 @@ pub fn detect_build_system_enhanced(project_dir: &Path) -> Result<DetectedBuild,
         return Ok(DetectedBuild::Simple(BuildSystem::Gradle));
     }
 
+    // Scala build systems
+    if project_dir.join("build.sbt").exists() {
+        return Ok(DetectedBuild::Simple(BuildSystem::Sbt));
+    }
+    if project_dir.join("build.mill").exists() || project_dir.join("build.sc").exists() {
+        return Ok(DetectedBuild::Simple(BuildSystem::Mill));
+    }
+
+    // Monorepo build systems (the target comes from bazel_target / buck_target)
+    if ["MODULE.bazel", "WORKSPACE", "WORKSPACE.bazel"]
+        .iter()
+        .any(|f| project_dir.join(f).exists())
+    {
+        return Ok(DetectedBuild::Simple(BuildSystem::Bazel));
+    }
+    if project_dir.join(".buckconfig").exists() {
+        return Ok(DetectedBuild::Simple(BuildSystem::Buck2));
+    }
+
     Err(PackError::NoBuildSystem(project_dir.to_path_buf()))
 }
 
@@ -118,6 +158,42 @@ mod tests {
         assert!(matches!(result, DetectedBuild::Simple(BuildSystem::Gradle)));
     }
 
+    #[test]
+    fn detects_sbt() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("build.sbt"), "scalaVersion := \"3.4.2\"").unwrap();
+        let result = detect_build_system_enhanced(dir.path()).unwrap();
+        assert!(matches!(result, DetectedBuild::Simple(BuildSystem::Sbt)));
+    }
+
+    #[test]
+    fn detects_mill() {
+        for file in ["build.mill", "build.sc"] {
+            let dir = tempdir().unwrap();
+            std::fs::write(dir.path().join(file), "object app extends ScalaModule").unwrap();
+            let result = detect_build_system_enhanced(dir.path()).unwrap();
+            assert!(matches!(result, DetectedBuild::Simple(BuildSystem::Mill)));
+        }
+    }
+
+    #[test]
+    fn detects_bazel() {
+        for file in ["MODULE.bazel", "WORKSPACE", "WORKSPACE.bazel"] {
+            let dir = tempdir().unwrap();
+            std::fs::write(dir.path().join(file), "").unwrap();
+            let result = detect_build_system_enhanced(dir.path()).unwrap();
+            assert!(matches!(result, DetectedBuild::Simple(BuildSystem::Bazel)));
+        }
+    }
+
+    #[test]
+    fn detects_buck2() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join(".buckconfig"), "[cells]\nroot = .\n").unwrap();
+        let result = detect_build_system_enhanced(dir.path()).unwrap();
+        assert!(matches!(result, DetectedBuild::Simple(BuildSystem::Buck2)));
+    }
+
     #[test]
     fn clojure_has_priority_over_java() {
         let dir = tempdir().unwrap();
@@ -181,4 +257,54 @@ application {
             _ => panic!("expected GradleMultiProject"),
         }
     }
+
+    #[test]
+    fn detects_maven_multi_module() {
+        let dir = tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("pom.xml"),
+            "<project><modules><module>lib</module><module>app</module></modules></project>",
+        )
+        .unwrap();
+        for module in ["lib", "app"] {
+            std::fs::create_dir_all(dir.path().join(module)).unwrap();
+        }
+        std::fs::write(dir.path().join("lib/pom.xml"), "<project/>").unwrap();
+        std::fs::write(
+            dir.path().join("app/pom.xml"),
+            r#"<project><build><plugins><plugin>
+  <artifactId>spring-boot-maven-plugin</artifactId>
+  <configuration><mainClass>com.example.App</mainClass></configuration>
+</plugin></plugins></build></project>"#,
+        )
+        .unwrap();
+
+        let result = detect_build_system_enhanced(dir.path()).unwrap();
+        match result {
+            DetectedBuild::MavenMultiModule { app_modules, .. } => {
+                assert_eq!(app_modules.len(), 1);
+                assert_eq!(app_modules[0].name, "app");
+                assert_eq!(
+                    app_modules[0].main_class.as_deref(),
+                    Some("com.example.App")
+                );
+            }
+            _ => panic!("expected MavenMultiModule"),
+        }
+    }
+
+    #[test]
+    fn maven_aggregator_without_app_modules_is_simple() {
+        let dir = tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("pom.xml"),
+            "<project><modules><module>lib</module></modules></project>",
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.path().join("lib")).unwrap();
+        std::fs::write(dir.path().join("lib/pom.xml"), "<project/>").unwrap();
+
+        let result = detect_build_system_enhanced(dir.path()).unwrap();
+        assert!(matches!(result, DetectedBuild::Simple(BuildSystem::Maven)));
+    }
 }
@@ -14,6 +14,7 @@ const SOURCE_PREFIXES: &[&str] = &[
     "src",
     "src/main/java",
     "src/main/kotlin",
+    "src/main/scala",
     "src/main/clj",
 ];
 
@@ -24,6 +24,8 @@ pub fn parse(stderr: &str, stdout: &str, system: BuildSystem) -> Vec<Diagnostic>
         BuildSystem::DepsEdn | BuildSystem::Leiningen => parse_clojure(stderr, stdout),
         BuildSystem::Maven => parse_maven(stderr, stdout),
         BuildSystem::Gradle => parse_gradle(stderr, stdout),
+        BuildSystem::Sbt | BuildSystem::Mill => parse_scalac(stderr, stdout),
+        BuildSystem::Bazel | BuildSystem::Buck2 => parse_bazel(stderr, stdout),
     }
 }
 
@@ -220,6 +222,83 @@ fn parse_gradle(stderr: &str, stdout: &str) -> Vec<Diagnostic> {
     diagnostics
 }
 
+/// Bazel and Buck2 relay javac/kotlinc output as is; Bazel adds its own
+/// `ERROR: <file>:<line>:<col>: <message>` lines for BUILD file problems.
+fn parse_bazel(stderr: &str, stdout: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = parse_gradle(stderr, stdout);
+    if !diagnostics.is_empty() {
+        return diagnostics;
+    }
+
+    let combined = format!("{stderr}\n{stdout}");
+    let bazel_re = Regex::new(r"^ERROR:\s+(\S+?):(\d+):(\d+):\s+(.+)").unwrap();
+    for line in combined.lines() {
+        if let Some(caps) = bazel_re.captures(line) {
+            diagnostics.push(Diagnostic {
+                severity: Severity::Error,
+                message: caps[4].trim().to_string(),
+                file: Some(PathBuf::from(&caps[1])),
+                line: caps[2].parse().ok(),
+                column: caps[3].parse().ok(),
+            });
+        }
+    }
+    diagnostics
+}
+
+fn parse_scalac(stderr: &str, stdout: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let combined = format!("{stderr}\n{stdout}");
+    let lines: Vec<&str> = combined.lines().collect();
+
+    // Scala 2 / zinc: [error] /path/App.scala:12:5: not found: value foo
+    let scala2_re =
+        Regex::new(r"^\[(error|warn)\]\s+(\S+\.(?:scala|java)):(\d+):(\d+):\s+(.+)").unwrap();
+    // Scala 3: [error] -- [E006] Not Found Error: /path/App.scala:12:5 ------
+    let scala3_re =
+        Regex::new(r"^\[(error|warn)\]\s+--\s+(?:\[E\d+\]\s+)?(.*?):\s+(\S+\.scala):(\d+):(\d+)")
+            .unwrap();
+    // Scala 3 message lines: [error]    |  Not found: foo
+    let detail_re = Regex::new(r"^\[(?:error|warn)\]\s+\|\s*(.*)$").unwrap();
+
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(caps) = scala2_re.captures(line) {
+            diagnostics.push(Diagnostic {
+                severity: scala_severity(&caps[1]),
+                message: caps[5].trim().to_string(),
+                file: Some(PathBuf::from(&caps[2])),
+                line: caps[3].parse().ok(),
+                column: caps[4].parse().ok(),
+            });
+        } else if let Some(caps) = scala3_re.captures(line) {
+            // The message follows the source excerpt and the ^^^ marker
+            let message = lines[i + 1..]
+                .iter()
+                .take(10)
+                .filter_map(|l| detail_re.captures(l))
+                .map(|c| c[1].trim().to_string())
+                .find(|m| !m.is_empty() && !m.chars().all(|c| c == '^' || c == ' '))
+                .unwrap_or_else(|| caps[2].trim().to_string());
+            diagnostics.push(Diagnostic {
+                severity: scala_severity(&caps[1]),
+                message,
+                file: Some(PathBuf::from(&caps[3])),
+                line: caps[4].parse().ok(),
+                column: caps[5].parse().ok(),
+            });
+        }
+    }
+
+    diagnostics
+}
+
+fn scala_severity(level: &str) -> Severity {
+    match level {
+        "error" => Severity::Error,
+        _ => Severity::Warning,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -294,6 +373,70 @@ Unable to resolve symbol: prntln in this context"#;
         assert_eq!(diags[1].severity, Severity::Warning);
     }
 
+    #[test]
+    fn parse_scala2_error() {
+        let stdout = "[info] compiling 3 Scala sources to /home/user/app/target/scala-2.13/classes ...\n\
+                      [error] /home/user/app/src/main/scala/App.scala:12:5: not found: value prntln\n\
+                      [warn] /home/user/app/src/main/scala/Util.scala:3:1: Unused import\n\
+                      [error] one error found";
+
+        let diags = parse("", stdout, BuildSystem::Sbt);
+        assert_eq!(diags.len(), 2);
+        assert_eq!(diags[0].severity, Severity::Error);
+        assert_eq!(diags[0].line, Some(12));
+        assert_eq!(diags[0].column, Some(5));
+        assert_eq!(diags[0].message, "not found: value prntln");
+        assert_eq!(diags[1].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn parse_scala3_error() {
+        let stdout =
+            "[error] -- [E006] Not Found Error: /home/user/app/src/App.scala:4:2 ------------\n\
+                      [error] 4 |  prntln(\"hi\")\n\
+                      [error]   |  ^^^^^^\n\
+                      [error]   |  Not found: prntln\n\
+                      [error]   |\n\
+                      [error]   | longer explanation available when compiling with `-explain`";
+
+        let diags = parse("", stdout, BuildSystem::Mill);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(
+            diags[0].file.as_ref().unwrap().to_str().unwrap(),
+            "/home/user/app/src/App.scala"
+        );
+        assert_eq!(diags[0].line, Some(4));
+        assert_eq!(diags[0].column, Some(2));
+        assert_eq!(diags[0].message, "Not found: prntln");
+    }
+
+    #[test]
+    fn parse_bazel_javac_error() {
+        let stderr = "INFO: Analyzed target //svc:app_deploy.jar (0 packages loaded).\n\
+                      ERROR: /repo/svc/BUILD.bazel:3:12: Building svc/libapp.jar (1 source file) failed: (Exit 1): java failed\n\
+                      svc/src/main/java/com/example/App.java:10: error: cannot find symbol\n\
+                      FAILED: Build did NOT complete successfully";
+
+        let diags = parse(stderr, "", BuildSystem::Bazel);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(
+            diags[0].file.as_ref().unwrap().to_str().unwrap(),
+            "svc/src/main/java/com/example/App.java"
+        );
+        assert_eq!(diags[0].line, Some(10));
+        assert!(diags[0].message.contains("cannot find symbol"));
+    }
+
+    #[test]
+    fn parse_bazel_build_file_error() {
+        let stderr = "ERROR: /repo/svc/BUILD.bazel:7:13: no such target '//lib:missing'";
+        let diags = parse(stderr, "", BuildSystem::Bazel);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].line, Some(7));
+        assert_eq!(diags[0].column, Some(13));
+        assert_eq!(diags[0].message, "no such target '//lib:missing'");
+    }
+
     #[test]
     fn parse_empty_stderr_returns_empty() {
         let diags = parse("", "", BuildSystem::DepsEdn);
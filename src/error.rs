@@ -46,6 +46,27 @@ pub enum PackError {
     #[error("analyze failed: {0}")]
     AnalyzeFailed(String),
 
+    #[error("not a jbundle binary: {0}")]
+    InvalidBinary(String),
+
+    #[error("patch failed: {0}")]
+    PatchFailed(String),
+
+    #[error("invalid key {0}")]
+    InvalidKey(String),
+
+    #[error("signature verification failed: {0}")]
+    SignatureInvalid(String),
+
+    #[error("require_signature needs a signing key (--sign-key or sign_key in jbundle.toml)")]
+    MissingSignKey,
+
+    #[error("thin mode failed: {0}")]
+    ThinFailed(String),
+
+    #[error("module tracing failed: {0}")]
+    TraceFailed(String),
+
     #[error("CRaC is not supported by this JDK")]
     CracNotSupported,
 
@@ -55,6 +76,84 @@ pub enum PackError {
     #[error("invalid JVM profile: {0} (expected: cli, server)")]
     InvalidProfile(String),
 
+    #[error("invalid jlink vm: {0} (expected: server, client, minimal)")]
+    InvalidJlinkVm(String),
+
+    #[error("invalid banner: {0} (expected: full, compact, none, custom)")]
+    InvalidBanner(String),
+
+    #[error(
+        "banner = \"custom\" requires a banner file (--banner-file or banner_file in jbundle.toml)"
+    )]
+    MissingBannerFile,
+
+    #[error("a banner file only applies to the custom banner, but the banner is set to {0}")]
+    BannerFileConflict(String),
+
+    #[error("resource path not found: {0}")]
+    ResourceNotFound(PathBuf),
+
+    #[error("resource path {0} has no name to store it under; list the files or directories inside it instead")]
+    UnnamedResource(PathBuf),
+
+    #[error("resources {0} and {1} would both be stored as {2}/ in the resources layer; rename one or list their contents instead")]
+    DuplicateResourceName(PathBuf, PathBuf, String),
+
+    #[error("invalid update_url: '{0}' (expected an http://, https:// or file:// URL)")]
+    InvalidUpdateUrl(String),
+
+    #[error("{0} dependencies violate the license policy")]
+    LicenseViolation(usize),
+
+    #[error("size budget exceeded:\n{0}")]
+    BudgetExceeded(String),
+
+    #[error("smoke test failed: {0}")]
+    SmokeTestFailed(String),
+
+    #[error("invalid expect_stdout_regex in [test]: {0}")]
+    InvalidTestRegex(String),
+
+    #[error("{system} builds need a target: set {option} in jbundle.toml or pass --{flag} (e.g. //svc:app)")]
+    MissingBuildTarget {
+        system: &'static str,
+        option: &'static str,
+        flag: &'static str,
+    },
+
+    #[error("invalid size: {0} (expected e.g. 80MB, 512KB, 1.5GB)")]
+    InvalidSize(String),
+
+    #[error("invalid growth limit: {0} (expected a size like 5MB or a percentage like 10%)")]
+    InvalidMaxGrowth(String),
+
+    #[error("JAR grew by {growth}, over the --max-growth limit of {limit}")]
+    GrowthExceeded { growth: String, limit: String },
+
+    #[error("invalid report format: {0} (expected: table, json, markdown)")]
+    InvalidReportFormat(String),
+
+    #[error("invalid severity: {0} (expected: low, medium, high, critical)")]
+    InvalidSeverity(String),
+
+    #[error("audit failed: {0}")]
+    AuditFailed(String),
+
+    #[error("{count} known vulnerabilities at or above {threshold} severity")]
+    VulnerabilitiesFound { count: usize, threshold: String },
+
+    #[error("invalid SBOM format: {0} (expected: cyclonedx, spdx)")]
+    InvalidSbomFormat(String),
+
+    #[error("invalid workdir: {0} (expected: binary, cwd)")]
+    InvalidWorkDir(String),
+
+    #[error("invalid environment variable name: '{0}'")]
+    InvalidEnvVar(String),
+
+    #[error("invalid launcher flag prefix: '{0}' (use letters, digits and '-')")]
+    InvalidFlagPrefix(String),
+
     #[error(
         "project requires Java {required}+ but --java-version is {configured}\n  \
          Detected: class file version {class_version} (Java {required}) in {class_file}\n  \
@@ -75,4 +174,10 @@ pub enum PackError {
 
     #[error("multiple application subprojects found, please specify one with --gradle-project")]
     MultipleApplicationSubprojects(Vec<String>),
+
+    #[error("Maven module '{0}' not found among the modules with a shade, assembly or spring-boot plugin")]
+    MavenModuleNotFound(String),
+
+    #[error("multiple application modules found, please specify one with --maven-module")]
+    MultipleApplicationModules(Vec<String>),
 }
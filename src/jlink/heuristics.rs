@@ -0,0 +1,465 @@
+//! Heuristics for modules that jdeps can't see.
+//!
+//! jdeps only follows static class references. Security providers, charsets
+//! and JNDI are looked up by name at runtime, so their modules have to be
+//! inferred from service files and from string constants next to the API
+//! that takes them.
+
+use std::collections::BTreeMap;
+use std::io::{Read, Seek};
+use std::path::Path;
+
+use zip::ZipArchive;
+
+use crate::error::PackError;
+
+/// A module added by a heuristic, with the evidence for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleHint {
+    pub module: &'static str,
+    pub reason: String,
+}
+
+enum Needle {
+    /// `META-INF/services/<name>` provider file
+    Service(&'static str),
+    /// Constant pool string equal to this
+    Exact(&'static str),
+    /// Constant pool string starting with this
+    Prefix(&'static str),
+    /// Reference to a class whose internal name starts with this, as a class
+    /// constant or inside a descriptor
+    Class(&'static str),
+}
+
+struct Rule {
+    module: &'static str,
+    what: &'static str,
+    needle: Needle,
+    /// API the same class must also use, so a bare string (a URL in a log
+    /// message, a charset name in a table) isn't enough. Empty when the
+    /// needle is itself the API.
+    api: &'static [Needle],
+}
+
+const SECURITY_API: &[Needle] = &[
+    Needle::Class("java/security/"),
+    Needle::Class("javax/crypto/"),
+];
+const NAMING_API: &[Needle] = &[Needle::Class("javax/naming/")];
+const CHARSET_API: &[Needle] = &[
+    Needle::Class("java/nio/charset/Charset"),
+    Needle::Class("java/io/InputStreamReader"),
+    Needle::Class("java/io/OutputStreamWriter"),
+    // String.getBytes(String) and new String(byte[], String)
+    Needle::Exact("(Ljava/lang/String;)[B"),
+    Needle::Exact("([BLjava/lang/String;)V"),
+];
+
+const RULES: &[Rule] = &[
+    // Elliptic-curve crypto, used by most TLS handshakes (separate module before JDK 22)
+    Rule {
+        module: "jdk.crypto.ec",
+        what: "TLS",
+        needle: Needle::Class("javax/net/ssl/"),
+        api: &[],
+    },
+    Rule {
+        module: "jdk.crypto.ec",
+        what: "TLS (HTTP client)",
+        needle: Needle::Class("java/net/http/"),
+        api: &[],
+    },
+    Rule {
+        module: "jdk.crypto.ec",
+        what: "TLS (HTTPS URL connection)",
+        needle: Needle::Class("sun/net/www/protocol/https/"),
+        api: &[],
+    },
+    Rule {
+        module: "jdk.crypto.ec",
+        what: "EC security provider",
+        needle: Needle::Exact("SunEC"),
+        api: SECURITY_API,
+    },
+    Rule {
+        module: "jdk.crypto.ec",
+        what: "EC algorithm",
+        needle: Needle::Exact("secp256r1"),
+        api: &[Needle::Class("java/security/spec/ECGenParameterSpec")],
+    },
+    // zip file system behind FileSystems.newFileSystem("jar:...")
+    Rule {
+        module: "jdk.zipfs",
+        what: "zip file system",
+        needle: Needle::Prefix("jar:"),
+        api: &[Needle::Class("java/nio/file/FileSystems")],
+    },
+    // JDBC drivers register through ServiceLoader
+    Rule {
+        module: "java.sql",
+        what: "JDBC driver",
+        needle: Needle::Service("java.sql.Driver"),
+        api: &[],
+    },
+    // JNDI / LDAP
+    Rule {
+        module: "java.naming",
+        what: "JNDI provider",
+        needle: Needle::Service("javax.naming.spi.InitialContextFactory"),
+        api: &[],
+    },
+    Rule {
+        module: "java.naming",
+        what: "LDAP",
+        needle: Needle::Prefix("ldap://"),
+        api: NAMING_API,
+    },
+    Rule {
+        module: "java.naming",
+        what: "JNDI LDAP provider",
+        needle: Needle::Exact("com.sun.jndi.ldap.LdapCtxFactory"),
+        api: NAMING_API,
+    },
+    Rule {
+        module: "jdk.naming.dns",
+        what: "JNDI DNS provider",
+        needle: Needle::Prefix("dns:"),
+        api: NAMING_API,
+    },
+    // Extended charsets (java.base only has the standard ones)
+    Rule {
+        module: "jdk.charsets",
+        what: "extended charset",
+        needle: Needle::Exact("Shift_JIS"),
+        api: CHARSET_API,
+    },
+    Rule {
+        module: "jdk.charsets",
+        what: "extended charset",
+        needle: Needle::Exact("EUC-JP"),
+        api: CHARSET_API,
+    },
+    Rule {
+        module: "jdk.charsets",
+        what: "extended charset",
+        needle: Needle::Exact("GBK"),
+        api: CHARSET_API,
+    },
+    Rule {
+        module: "jdk.charsets",
+        what: "extended charset",
+        needle: Needle::Exact("Big5"),
+        api: CHARSET_API,
+    },
+    Rule {
+        module: "jdk.charsets",
+        what: "extended charset",
+        needle: Needle::Exact("EUC-KR"),
+        api: CHARSET_API,
+    },
+    Rule {
+        module: "jdk.charsets",
+        what: "extended charset",
+        needle: Needle::Exact("GB18030"),
+        api: CHARSET_API,
+    },
+    // Remote JMX switched on from code with System.setProperty
+    Rule {
+        module: "jdk.management.agent",
+        what: "remote JMX",
+        needle: Needle::Prefix("com.sun.management.jmxremote"),
+        api: &[Needle::Exact("setProperty")],
+    },
+    // Reflective Unsafe access (Netty, serialization libraries)
+    Rule {
+        module: "jdk.unsupported",
+        what: "reflective sun.misc.Unsafe",
+        needle: Needle::Exact("sun.misc.Unsafe"),
+        api: &[Needle::Exact("forName"), Needle::Exact("loadClass")],
+    },
+    Rule {
+        module: "java.scripting",
+        what: "script engine",
+        needle: Needle::Service("javax.script.ScriptEngineFactory"),
+        api: &[],
+    },
+];
+
+/// Scans the JAR (and Spring Boot nested JARs) for runtime lookups jdeps misses.
+/// Returns one hint per module, sorted by module name.
+pub fn detect_hints(jar_path: &Path) -> Result<Vec<ModuleHint>, PackError> {
+    let mut archive = ZipArchive::new(std::fs::File::open(jar_path)?)?;
+    let mut hints = BTreeMap::new();
+    scan_archive(&mut archive, "", &mut hints)?;
+
+    for name in crate::boot::nested_jar_names(&archive) {
+        if let Some(mut inner) = crate::boot::open_nested(&mut archive, &name)? {
+            scan_archive(&mut inner, &format!("{name}!/"), &mut hints)?;
+        }
+    }
+
+    Ok(hints.into_values().collect())
+}
+
+fn scan_archive<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    prefix: &str,
+    hints: &mut BTreeMap<&'static str, ModuleHint>,
+) -> Result<(), PackError> {
+    let mut buf = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+
+        if let Some(service) = name.strip_prefix("META-INF/services/") {
+            for rule in RULES {
+                if matches!(rule.needle, Needle::Service(s) if s == service) {
+                    add_hint(hints, rule.module, || {
+                        format!("{} ({}{})", rule.what, prefix, name)
+                    });
+                }
+            }
+        } else if name.ends_with(".class") {
+            buf.clear();
+            entry.read_to_end(&mut buf)?;
+            let constants = utf8_constants(&buf);
+            for rule in RULES {
+                let Some(constant) = constants.iter().find(|c| matches_constant(&rule.needle, c))
+                else {
+                    continue;
+                };
+                if rule.api.is_empty() {
+                    add_hint(hints, rule.module, || {
+                        format!("{} (\"{}\" in {}{})", rule.what, constant, prefix, name)
+                    });
+                } else if let Some(api) = constants
+                    .iter()
+                    .find(|c| rule.api.iter().any(|n| matches_constant(n, c)))
+                {
+                    add_hint(hints, rule.module, || {
+                        format!(
+                            "{} (\"{}\" with {} in {}{})",
+                            rule.what, constant, api, prefix, name
+                        )
+                    });
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn add_hint(
+    hints: &mut BTreeMap<&'static str, ModuleHint>,
+    module: &'static str,
+    reason: impl FnOnce() -> String,
+) {
+    hints.entry(module).or_insert_with(|| ModuleHint {
+        module,
+        reason: reason(),
+    });
+}
+
+fn matches_constant(needle: &Needle, constant: &str) -> bool {
+    match needle {
+        Needle::Service(_) => false,
+        Needle::Exact(s) => constant == *s,
+        Needle::Prefix(p) => constant.starts_with(p),
+        // `javax/net/ssl/SSLContext`, or `Ljavax/net/ssl/SSLContext;` in a descriptor
+        Needle::Class(p) => {
+            constant.starts_with(p)
+                || constant
+                    .match_indices(p)
+                    .any(|(i, _)| i > 0 && constant.as_bytes()[i - 1] == b'L')
+        }
+    }
+}
+
+/// UTF-8 entries of a class file's constant pool: class names, member
+/// descriptors and string literals. Empty for malformed classes.
+fn utf8_constants(class: &[u8]) -> Vec<&str> {
+    let mut constants = Vec::new();
+    if class.len() < 10 || class[0..4] != [0xCA, 0xFE, 0xBA, 0xBE] {
+        return constants;
+    }
+    let count = u16::from_be_bytes([class[8], class[9]]) as usize;
+    let mut pos = 10;
+    let mut index = 1;
+    while index < count {
+        let Some(&tag) = class.get(pos) else {
+            break;
+        };
+        let size = match tag {
+            // Utf8: u2 length + bytes
+            1 => {
+                let Some(len) = class.get(pos + 1..pos + 3) else {
+                    break;
+                };
+                let len = u16::from_be_bytes([len[0], len[1]]) as usize;
+                let Some(bytes) = class.get(pos + 3..pos + 3 + len) else {
+                    break;
+                };
+                // Modified UTF-8; anything that isn't plain UTF-8 can't match a rule
+                if let Ok(s) = std::str::from_utf8(bytes) {
+                    constants.push(s);
+                }
+                3 + len
+            }
+            // Class, String, MethodType, Module, Package
+            7 | 8 | 16 | 19 | 20 => 3,
+            // MethodHandle
+            15 => 4,
+            // Integer, Float, Fieldref, Methodref, InterfaceMethodref, NameAndType, Dynamic, InvokeDynamic
+            3 | 4 | 9 | 10 | 11 | 12 | 17 | 18 => 5,
+            // Long and Double take two constant pool slots
+            5 | 6 => {
+                index += 1;
+                9
+            }
+            _ => break,
+        };
+        pos += size;
+        index += 1;
+    }
+    constants
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    /// Minimal class file whose constant pool holds the given UTF-8 strings.
+    fn class_with_constants(constants: &[&str]) -> Vec<u8> {
+        let mut class = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 61];
+        // A Long before the strings checks the two-slot handling
+        let count = constants.len() as u16 + 3;
+        class.extend_from_slice(&count.to_be_bytes());
+        class.push(5);
+        class.extend_from_slice(&42u64.to_be_bytes());
+        for s in constants {
+            class.push(1);
+            class.extend_from_slice(&(s.len() as u16).to_be_bytes());
+            class.extend_from_slice(s.as_bytes());
+        }
+        class
+    }
+
+    fn create_jar(entries: &[(&str, Vec<u8>)]) -> NamedTempFile {
+        let file = NamedTempFile::new().unwrap();
+        let mut zip = ZipWriter::new(file.reopen().unwrap());
+        for (name, content) in entries {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap();
+        file
+    }
+
+    #[test]
+    fn reads_constant_pool_strings() {
+        let class = class_with_constants(&["com/example/Main", "SunEC"]);
+        assert_eq!(utf8_constants(&class), vec!["com/example/Main", "SunEC"]);
+        assert!(utf8_constants(b"not a class").is_empty());
+        // Truncated pool stops cleanly
+        assert_eq!(
+            utf8_constants(&class[..class.len() - 2]),
+            vec!["com/example/Main"]
+        );
+    }
+
+    #[test]
+    fn detects_hints_from_api_use_and_services() {
+        let jar = create_jar(&[
+            (
+                "com/example/Http.class",
+                class_with_constants(&["javax/net/ssl/SSLContext"]),
+            ),
+            (
+                "com/example/Archive.class",
+                class_with_constants(&["jar:file:", "java/nio/file/FileSystems"]),
+            ),
+            (
+                "com/example/Text.class",
+                class_with_constants(&[
+                    "Shift_JIS",
+                    "(Ljava/lang/String;)Ljava/nio/charset/Charset;",
+                ]),
+            ),
+            (
+                "com/example/Reflect.class",
+                class_with_constants(&["sun.misc.Unsafe", "forName"]),
+            ),
+            (
+                "META-INF/services/java.sql.Driver",
+                b"org.postgresql.Driver\n".to_vec(),
+            ),
+        ]);
+
+        let hints = detect_hints(jar.path()).unwrap();
+        let modules: Vec<_> = hints.iter().map(|h| h.module).collect();
+        assert_eq!(
+            modules,
+            vec![
+                "java.sql",
+                "jdk.charsets",
+                "jdk.crypto.ec",
+                "jdk.unsupported",
+                "jdk.zipfs",
+            ]
+        );
+        let tls = hints.iter().find(|h| h.module == "jdk.crypto.ec").unwrap();
+        assert_eq!(
+            tls.reason,
+            "TLS (\"javax/net/ssl/SSLContext\" in com/example/Http.class)"
+        );
+        let charsets = hints.iter().find(|h| h.module == "jdk.charsets").unwrap();
+        assert_eq!(
+            charsets.reason,
+            "extended charset (\"Shift_JIS\" with (Ljava/lang/String;)Ljava/nio/charset/Charset; \
+             in com/example/Text.class)"
+        );
+    }
+
+    #[test]
+    fn bare_strings_without_their_api_are_not_hints() {
+        let jar = create_jar(&[
+            (
+                "com/example/Links.class",
+                class_with_constants(&["https://example.com", "jar:file:/x", "ldap://host"]),
+            ),
+            (
+                "com/example/Codes.class",
+                class_with_constants(&["GBK", "Big5", "sun.misc.Unsafe"]),
+            ),
+            ("i18n/messages_de.properties", b"hello=Hallo\n".to_vec()),
+        ]);
+        assert!(detect_hints(jar.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn class_needle_matches_names_and_descriptors() {
+        let ssl = Needle::Class("javax/net/ssl/");
+        assert!(matches_constant(&ssl, "javax/net/ssl/SSLSocketFactory"));
+        assert!(matches_constant(&ssl, "()Ljavax/net/ssl/SSLContext;"));
+        assert!(!matches_constant(&ssl, "see javax/net/ssl/ docs"));
+        assert!(!matches_constant(&ssl, "javax.net.ssl.SSLContext"));
+    }
+
+    #[test]
+    fn plain_jar_has_no_hints() {
+        let jar = create_jar(&[(
+            "com/example/Main.class",
+            class_with_constants(&["java/lang/String", "hello"]),
+        )]);
+        assert!(detect_hints(jar.path()).unwrap().is_empty());
+    }
+}
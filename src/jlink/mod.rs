@@ -0,0 +1,399 @@
+mod heuristics;
+mod trace;
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::{BuildConfig, JlinkVm};
+use crate::error::PackError;
+use crate::jvm::cache::jdk_bin;
+pub use heuristics::{detect_hints, ModuleHint};
+pub use trace::{missing_modules, trace_modules};
+
+/// jlink tuning from `[jlink]` in jbundle.toml.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JlinkOptions {
+    /// Drop every command in `bin/` except `java`
+    pub strip_native_commands: bool,
+    pub vm: Option<JlinkVm>,
+    /// Locales kept from jdk.localedata (e.g. `en`, `pt-BR`)
+    pub include_locales: Vec<String>,
+    /// Build the runtime's default CDS archive
+    pub generate_cds_archive: bool,
+    pub dedup_legal_notices: bool,
+    /// Link service providers of the included modules too
+    pub bind_services: bool,
+    /// Passed to jlink as-is, after everything else
+    pub extra_args: Vec<String>,
+}
+
+impl JlinkOptions {
+    /// jlink arguments besides `--add-modules` and `--output`.
+    pub fn args(&self) -> Vec<String> {
+        let mut args: Vec<String> = [
+            "--strip-debug",
+            "--no-man-pages",
+            "--no-header-files",
+            "--compress=zip-6",
+        ]
+        .map(String::from)
+        .to_vec();
+        if let Some(vm) = self.vm {
+            args.push(format!("--vm={}", vm.name()));
+        }
+        if !self.include_locales.is_empty() {
+            args.push(format!(
+                "--include-locales={}",
+                self.include_locales.join(",")
+            ));
+        }
+        if self.generate_cds_archive {
+            args.push("--generate-cds-archive".to_string());
+        }
+        if self.dedup_legal_notices {
+            args.push("--dedup-legal-notices".to_string());
+        }
+        if self.bind_services {
+            args.push("--bind-services".to_string());
+        }
+        args.extend(self.extra_args.iter().cloned());
+        args
+    }
+}
+
+/// Runs jdeps on the JAR. `classpath` holds the project's resolved dependency
+/// JARs, letting jdeps follow references the uberjar doesn't resolve itself;
+/// `java_version` selects the Multi-Release variant of versioned classes.
+pub fn detect_modules(
+    jdk_path: &Path,
+    jar_path: &Path,
+    java_version: u8,
+    classpath: &[PathBuf],
+) -> Result<String, PackError> {
+    let jdeps = jdk_bin(jdk_path, "jdeps");
+
+    // jdeps can't look inside Spring Boot's nested JARs; analyze them unpacked
+    let boot_dir = tempfile::tempdir()?;
+    let inputs = if crate::boot::is_boot_jar(jar_path)? {
+        let layout = crate::boot::extract_layout(jar_path, boot_dir.path())?;
+        tracing::info!(
+            "Spring Boot JAR: analyzing BOOT-INF/classes and {} nested JARs",
+            layout.libs.len()
+        );
+        std::iter::once(layout.classes).chain(layout.libs).collect()
+    } else {
+        vec![jar_path.to_path_buf()]
+    };
+
+    let release = java_version.to_string();
+    let mut args = vec![
+        "--print-module-deps",
+        "--ignore-missing-deps",
+        "--multi-release",
+        release.as_str(),
+    ];
+    let classpath = if classpath.is_empty() {
+        None
+    } else {
+        let joined = std::env::join_paths(classpath)
+            .map_err(|e| PackError::JdepsFailed(format!("invalid classpath: {e}")))?;
+        Some(
+            joined
+                .into_string()
+                .map_err(|_| PackError::JdepsFailed("classpath contains invalid UTF-8".into()))?,
+        )
+    };
+    if let Some(ref cp) = classpath {
+        args.push("--class-path");
+        args.push(cp);
+    }
+    for input in &inputs {
+        args.push(
+            input
+                .to_str()
+                .ok_or_else(|| PackError::JdepsFailed("JAR path contains invalid UTF-8".into()))?,
+        );
+    }
+
+    let cmd_str = format!("{} {}", jdeps.display(), args.join(" "));
+    tracing::info!("running: {cmd_str}");
+
+    let output = Command::new(&jdeps)
+        .args(args)
+        .output()
+        .map_err(|e| PackError::JdepsFailed(format!("failed to run jdeps: {e}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(PackError::JdepsFailed(format!(
+            "command: {cmd_str}\n{}\n\
+             Fix: list the modules yourself with --modules or `modules = [...]` in jbundle.toml",
+            stderr.trim()
+        )));
+    }
+
+    let modules = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if modules.is_empty() {
+        return Ok("java.base".to_string());
+    }
+
+    Ok(modules)
+}
+
+/// Drops hinted modules the JDK doesn't ship, checked against its `jmods/`.
+/// JDKs without `jmods/` (linkable runtime images) keep every hint.
+pub fn available_hints(jdk_path: &Path, hints: Vec<ModuleHint>) -> Vec<ModuleHint> {
+    let jmods = jdk_bin(jdk_path, "jlink")
+        .parent()
+        .and_then(Path::parent)
+        .map(|home| home.join("jmods"));
+    match jmods {
+        Some(dir) if dir.is_dir() => hints
+            .into_iter()
+            .filter(|h| dir.join(format!("{}.jmod", h.module)).exists())
+            .collect(),
+        _ => hints,
+    }
+}
+
+pub fn create_runtime(
+    jdk_path: &Path,
+    modules: &str,
+    options: &JlinkOptions,
+    output_dir: &Path,
+) -> Result<PathBuf, PackError> {
+    let jlink_bin = jdk_bin(jdk_path, "jlink");
+    let runtime_path = output_dir.join("runtime");
+
+    if runtime_path.exists() {
+        std::fs::remove_dir_all(&runtime_path)?;
+    }
+
+    let runtime_str = runtime_path
+        .to_str()
+        .ok_or_else(|| PackError::JlinkFailed("runtime path contains invalid UTF-8".into()))?;
+
+    // --include-locales picks the locales out of jdk.localedata
+    let modules = if !options.include_locales.is_empty()
+        && !modules.split(',').any(|m| m == "jdk.localedata")
+    {
+        format!("{modules},jdk.localedata")
+    } else {
+        modules.to_string()
+    };
+
+    let mut args = vec!["--add-modules".to_string(), modules];
+    args.extend(options.args());
+    args.push("--output".to_string());
+    args.push(runtime_str.to_string());
+
+    let cmd_str = format!("{} {}", jlink_bin.display(), args.join(" "));
+    tracing::info!("running: {cmd_str}");
+
+    let output = Command::new(&jlink_bin)
+        .args(&args)
+        .output()
+        .map_err(|e| PackError::JlinkFailed(format!("failed to run jlink: {e}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut msg = format!("command: {cmd_str}\n");
+        if !stderr.is_empty() {
+            msg.push_str(&format!("stderr:\n{stderr}"));
+        }
+        if !stdout.is_empty() {
+            msg.push_str(&format!("stdout:\n{stdout}"));
+        }
+        if stderr.is_empty() && stdout.is_empty() {
+            msg.push_str(&format!(
+                "process exited with {}",
+                output
+                    .status
+                    .code()
+                    .map(|c| c.to_string())
+                    .unwrap_or_else(|| "signal".to_string())
+            ));
+        }
+        return Err(PackError::JlinkFailed(msg));
+    }
+
+    if options.strip_native_commands {
+        strip_native_commands(&runtime_path)?;
+    }
+
+    Ok(runtime_path)
+}
+
+/// Returns the runtime cached for this JDK, module set and options, running
+/// jlink only on a miss. The flag is true for a cache hit.
+pub fn cached_runtime(
+    jdk_path: &Path,
+    modules: &str,
+    options: &JlinkOptions,
+) -> Result<(PathBuf, bool), PackError> {
+    let cache_dir = BuildConfig::cache_dir()?;
+    let dest = cache_dir.join(format!(
+        "jlink-{}",
+        runtime_cache_key(jdk_path, modules, options)
+    ));
+    if jdk_bin(&dest, "java").exists() {
+        tracing::info!("using cached runtime: {}", dest.display());
+        return Ok((dest, true));
+    }
+
+    // Link next to the cache entry and move it in whole, so an interrupted
+    // build never leaves a half-written runtime behind
+    std::fs::create_dir_all(&cache_dir)?;
+    let staging = tempfile::tempdir_in(&cache_dir)?;
+    let runtime = create_runtime(jdk_path, modules, options, staging.path())?;
+    if dest.exists() {
+        std::fs::remove_dir_all(&dest)?;
+    }
+    std::fs::rename(&runtime, &dest)?;
+    Ok((dest, false))
+}
+
+/// Identifies a runtime by the JDK build it was linked from, the module set
+/// (in any order) and every option that changes the image.
+fn runtime_cache_key(jdk_path: &Path, modules: &str, options: &JlinkOptions) -> String {
+    let release = jdk_bin(jdk_path, "java")
+        .parent()
+        .and_then(Path::parent)
+        .and_then(|home| std::fs::read_to_string(home.join("release")).ok())
+        .unwrap_or_default();
+    let mut modules: Vec<&str> = modules.split(',').map(str::trim).collect();
+    modules.sort_unstable();
+    modules.dedup();
+
+    let key = format!(
+        "{}\n{}\n{}\n{}\nstrip-native-commands={}",
+        jdk_path.display(),
+        release,
+        modules.join(","),
+        options.args().join(" "),
+        options.strip_native_commands
+    );
+    crate::pack::archive::hash_bytes(key.as_bytes())
+}
+
+/// Removes the runtime's command-line tools (keytool, jrunscript, ...) except
+/// `java`, which the launcher needs. jlink's own `--strip-native-commands`
+/// would drop `java` too.
+fn strip_native_commands(runtime_path: &Path) -> Result<(), PackError> {
+    let bin = runtime_path.join("bin");
+    if !bin.is_dir() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(&bin)? {
+        let path = entry?.path();
+        let keep = path.file_stem().is_some_and(|n| n == "java");
+        if !keep && path.is_file() {
+            tracing::info!("stripping {}", path.display());
+            std::fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn default_args_match_previous_fixed_list() {
+        assert_eq!(
+            JlinkOptions::default().args(),
+            vec![
+                "--strip-debug",
+                "--no-man-pages",
+                "--no-header-files",
+                "--compress=zip-6"
+            ]
+        );
+    }
+
+    #[test]
+    fn tuning_options_become_jlink_args() {
+        let options = JlinkOptions {
+            strip_native_commands: true,
+            vm: Some(JlinkVm::Minimal),
+            include_locales: vec!["en".to_string(), "pt-BR".to_string()],
+            generate_cds_archive: true,
+            dedup_legal_notices: true,
+            bind_services: true,
+            extra_args: vec!["--ignore-signing-information".to_string()],
+        };
+        let args = options.args();
+        assert_eq!(
+            args[4..],
+            [
+                "--vm=minimal",
+                "--include-locales=en,pt-BR",
+                "--generate-cds-archive",
+                "--dedup-legal-notices",
+                "--bind-services",
+                "--ignore-signing-information",
+            ]
+        );
+        // Handled after linking, so java survives
+        assert!(!args.iter().any(|a| a == "--strip-native-commands"));
+    }
+
+    #[test]
+    fn cache_key_follows_modules_and_options() {
+        let jdk = tempdir().unwrap();
+        std::fs::write(jdk.path().join("release"), "JAVA_VERSION=\"21.0.4\"\n").unwrap();
+        let defaults = JlinkOptions::default();
+        let key = runtime_cache_key(jdk.path(), "java.base,java.sql", &defaults);
+
+        assert_eq!(
+            key,
+            runtime_cache_key(jdk.path(), "java.sql,java.base", &defaults)
+        );
+        assert_ne!(key, runtime_cache_key(jdk.path(), "java.base", &defaults));
+        let stripped = JlinkOptions {
+            strip_native_commands: true,
+            ..Default::default()
+        };
+        assert_ne!(
+            key,
+            runtime_cache_key(jdk.path(), "java.base,java.sql", &stripped)
+        );
+        let server = JlinkOptions {
+            vm: Some(JlinkVm::Server),
+            ..Default::default()
+        };
+        assert_ne!(
+            key,
+            runtime_cache_key(jdk.path(), "java.base,java.sql", &server)
+        );
+
+        // A JDK update changes the key too
+        std::fs::write(jdk.path().join("release"), "JAVA_VERSION=\"21.0.5\"\n").unwrap();
+        assert_ne!(
+            key,
+            runtime_cache_key(jdk.path(), "java.base,java.sql", &defaults)
+        );
+    }
+
+    #[test]
+    fn strips_commands_but_keeps_java() {
+        let runtime = tempdir().unwrap();
+        let bin = runtime.path().join("bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        for tool in ["java", "keytool", "jrunscript", "jwebserver"] {
+            std::fs::write(bin.join(tool), b"").unwrap();
+        }
+
+        strip_native_commands(runtime.path()).unwrap();
+
+        let mut left: Vec<_> = std::fs::read_dir(&bin)
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        left.sort();
+        assert_eq!(left, vec!["java"]);
+    }
+}
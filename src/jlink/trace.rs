@@ -0,0 +1,214 @@
+//! Runtime module tracing (`jbundle build --trace-modules`).
+//!
+//! Runs the app once on the full JDK with class loading logged, so modules
+//! loaded by reflection or ServiceLoader show up even when jdeps can't see them.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+
+use crate::error::PackError;
+use crate::jvm::cache::jdk_bin;
+use crate::pack::stub::RUNTIME_VARS;
+
+/// Servers never exit on their own; they're stopped after this long.
+pub const TRACE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Lines of the app's output quoted when the traced run fails.
+const OUTPUT_TAIL: usize = 20;
+
+/// Runs `java -jar <jar> <app_args>` and returns the modules it loaded classes from.
+pub fn trace_modules(
+    jdk_path: &Path,
+    jar_path: &Path,
+    jvm_args: &[String],
+    app_args: &[String],
+    work_dir: &Path,
+) -> Result<BTreeSet<String>, PackError> {
+    let java = jdk_bin(jdk_path, "java");
+    let log = work_dir.join("class-load.log");
+    let output_path = work_dir.join("trace-output.log");
+    let output = std::fs::File::create(&output_path)?;
+
+    // CDS-archived classes are logged as "shared objects file" without their
+    // module, so sharing is turned off. The app's own output would interleave
+    // with the build's progress, so it goes to a file.
+    let mut cmd = Command::new(&java);
+    cmd.arg("-Xshare:off")
+        .arg(format!("-Xlog:class+load=info:file={}", log.display()))
+        .args(traceable_jvm_args(jvm_args))
+        .arg("-jar")
+        .arg(jar_path)
+        .args(app_args)
+        .stdin(Stdio::null())
+        .stdout(output.try_clone()?)
+        .stderr(output);
+    tracing::info!("running: {:?}", cmd);
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| PackError::TraceFailed(format!("failed to run java: {e}")))?;
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if started.elapsed() >= TRACE_TIMEOUT {
+            tracing::info!(
+                "stopping the app after {}s of tracing",
+                TRACE_TIMEOUT.as_secs()
+            );
+            child.kill().ok();
+            child.wait().ok();
+            break None;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    };
+    if let Some(status) = status.filter(|s| !s.success()) {
+        // Still useful: the classes loaded up to the failure are in the log
+        let output = std::fs::read_to_string(&output_path).unwrap_or_default();
+        let lines: Vec<&str> = output.lines().collect();
+        tracing::warn!(
+            "traced app exited with {status}:\n{}",
+            lines[lines.len().saturating_sub(OUTPUT_TAIL)..].join("\n")
+        );
+    }
+
+    let content = std::fs::read_to_string(&log)
+        .map_err(|e| PackError::TraceFailed(format!("no class load log written: {e}")))?;
+    Ok(parse_class_load_log(&content))
+}
+
+/// `jvm_args` that can be passed to a plain `java` run. Arguments using the
+/// launcher's `${APP_DIR}`-style placeholders only make sense inside the
+/// packed binary, so they are left out rather than passed as literal text.
+fn traceable_jvm_args(jvm_args: &[String]) -> Vec<&str> {
+    jvm_args
+        .iter()
+        .map(String::as_str)
+        .filter(|arg| {
+            let placeholder = RUNTIME_VARS
+                .iter()
+                .any(|var| arg.contains(&format!("${{{var}}}")));
+            if placeholder {
+                tracing::warn!("not passing {arg} while tracing: it uses a launcher placeholder");
+            }
+            !placeholder
+        })
+        .collect()
+}
+
+/// Modules named by `source: jrt:/<module>` in `-Xlog:class+load` output.
+fn parse_class_load_log(log: &str) -> BTreeSet<String> {
+    let source = Regex::new(r"source: jrt:/([\w.]+)").unwrap();
+    log.lines()
+        .filter_map(|line| source.captures(line))
+        .map(|c| c[1].to_string())
+        .collect()
+}
+
+/// Modules loaded at runtime that the detected set lacks.
+pub fn missing_modules<'a>(traced: &'a BTreeSet<String>, detected: &str) -> Vec<&'a str> {
+    let detected: BTreeSet<&str> = detected.split(',').collect();
+    traced
+        .iter()
+        .map(String::as_str)
+        .filter(|m| !detected.contains(m))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_modules_from_class_load_log() {
+        let log = "\
+[0.010s][info][class,load] java.lang.Object source: jrt:/java.base
+[0.011s][info][class,load] java.lang.String source: jrt:/java.base
+[0.210s][info][class,load] java.sql.DriverManager source: jrt:/java.sql
+[0.212s][info][class,load] sun.security.ec.SunEC source: jrt:/jdk.crypto.ec
+[0.300s][info][class,load] com.example.Main source: file:/tmp/app.jar
+[0.301s][info][class,load] com.example.Main$$Lambda/0x01 source: com.example.Main
+";
+        let modules = parse_class_load_log(log);
+        assert_eq!(
+            modules.into_iter().collect::<Vec<_>>(),
+            vec!["java.base", "java.sql", "jdk.crypto.ec"]
+        );
+    }
+
+    #[test]
+    fn drops_jvm_args_with_launcher_placeholders() {
+        let args: Vec<String> = [
+            "-Xmx512m",
+            "-Dlogback.configurationFile=${BINARY_DIR}/logback.xml",
+            "-Dhome=${HOME}",
+            "-Dlib=${RT_DIR}/lib",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        assert_eq!(
+            traceable_jvm_args(&args),
+            vec!["-Xmx512m", "-Dhome=${HOME}"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn traces_fake_java_with_output_in_work_dir() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let jdk = dir.path().join("jdk");
+        std::fs::create_dir_all(jdk.join("bin")).unwrap();
+        let java = jdk.join("bin").join("java");
+        // Writes the class load log named by -Xlog and echoes its arguments
+        std::fs::write(
+            &java,
+            "#!/bin/sh\n\
+             for a in \"$@\"; do case \"$a\" in -Xlog:*) log=\"${a##*file=}\";; esac; done\n\
+             echo \"[info][class,load] java.sql.Driver source: jrt:/java.sql\" > \"$log\"\n\
+             echo \"args: $*\"\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&java, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let work = dir.path().join("work");
+        std::fs::create_dir_all(&work).unwrap();
+        let jvm_args = vec![
+            "-Xmx64m".to_string(),
+            "-Dconf=${APP_DIR}/app.conf".to_string(),
+        ];
+        let traced = trace_modules(
+            &jdk,
+            Path::new("app.jar"),
+            &jvm_args,
+            &["serve".to_string()],
+            &work,
+        )
+        .unwrap();
+        assert_eq!(traced.into_iter().collect::<Vec<_>>(), vec!["java.sql"]);
+
+        let output = std::fs::read_to_string(work.join("trace-output.log")).unwrap();
+        assert!(output.contains("-Xmx64m -jar app.jar serve"), "{output}");
+        assert!(!output.contains("APP_DIR"), "{output}");
+    }
+
+    #[test]
+    fn missing_modules_diffs_against_detected() {
+        let traced: BTreeSet<String> = ["java.base", "java.sql", "jdk.crypto.ec"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(
+            missing_modules(&traced, "java.base,java.logging"),
+            vec!["java.sql", "jdk.crypto.ec"]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::config::Target;
 use crate::error::PackError;
@@ -15,7 +15,7 @@ pub struct Binary {
     pub package: Package,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Package {
     pub link: String,
     pub checksum: String,
@@ -3,6 +3,8 @@ use std::path::{Path, PathBuf};
 use crate::config::{BuildConfig, Target};
 use crate::error::PackError;
 
+use super::adoptium::Package;
+
 pub fn cached_jdk_path(version: u8, target: &Target) -> Result<PathBuf, PackError> {
     let dir_name = format!(
         "jdk-{}-{}-{}",
@@ -45,6 +47,21 @@ pub fn extract_and_cache(
     Ok(dest)
 }
 
+/// Records which Adoptium asset a cached JDK came from, next to it.
+pub fn write_release_info(jdk: &Path, package: &Package) -> Result<(), PackError> {
+    std::fs::write(
+        jdk.with_extension("json"),
+        serde_json::to_vec_pretty(package)?,
+    )?;
+    Ok(())
+}
+
+/// The Adoptium asset a cached JDK came from, if it was recorded.
+pub fn read_release_info(jdk: &Path) -> Option<Package> {
+    let content = std::fs::read(jdk.with_extension("json")).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
 fn extract_tar_gz(archive: &Path, dest: &Path) -> Result<(), PackError> {
     let file = std::fs::File::open(archive)?;
     let decoder = flate2::read::GzDecoder::new(file);
@@ -162,4 +179,23 @@ mod tests {
 
         assert!(dir.path().join("file.txt").exists());
     }
+
+    #[test]
+    fn release_info_roundtrip() {
+        let dir = tempdir().unwrap();
+        let jdk = dir.path().join("jdk-21-linux-x64");
+        std::fs::create_dir(&jdk).unwrap();
+        assert!(read_release_info(&jdk).is_none());
+
+        let package = Package {
+            link: "https://example.com/jdk.tar.gz".to_string(),
+            checksum: "abc123".to_string(),
+            size: 42,
+            name: "OpenJDK21U-jdk_x64_linux_hotspot_21.0.5_11.tar.gz".to_string(),
+        };
+        write_release_info(&jdk, &package).unwrap();
+        let read = read_release_info(&jdk).unwrap();
+        assert_eq!(read.name, package.name);
+        assert_eq!(read.checksum, "abc123");
+    }
 }
@@ -69,7 +69,9 @@ pub async fn ensure_jdk(
     let result = async {
         let release = adoptium::fetch_latest_release(version, target).await?;
         let archive_path = download::download_jdk(&release, mp).await?;
-        cache::extract_and_cache(version, target, &archive_path)
+        let jdk = cache::extract_and_cache(version, target, &archive_path)?;
+        cache::write_release_info(&jdk, &release.binary.package)?;
+        Ok(jdk)
     }
     .await;
 
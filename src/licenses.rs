@@ -0,0 +1,847 @@
+use std::collections::HashSet;
+use std::io::{Read, Seek};
+use std::path::Path;
+
+use zip::ZipArchive;
+
+use crate::analyze::maven::{archive_coordinates, MavenCoordinate};
+use crate::error::PackError;
+use crate::pack::archive::sha256_hex;
+
+/// How a license constrains redistribution of the bundled binary.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LicenseKind {
+    Permissive,
+    /// File- or library-level copyleft (LGPL, MPL, EPL, CDDL)
+    WeakCopyleft,
+    /// Whole-program copyleft (GPL, AGPL)
+    StrongCopyleft,
+}
+
+/// Known SPDX identifiers and how they are classified.
+const KNOWN_LICENSES: &[(&str, LicenseKind)] = &[
+    ("Apache-2.0", LicenseKind::Permissive),
+    ("MIT", LicenseKind::Permissive),
+    ("BSD-2-Clause", LicenseKind::Permissive),
+    ("BSD-3-Clause", LicenseKind::Permissive),
+    ("ISC", LicenseKind::Permissive),
+    ("CC0-1.0", LicenseKind::Permissive),
+    ("EPL-1.0", LicenseKind::WeakCopyleft),
+    ("EPL-2.0", LicenseKind::WeakCopyleft),
+    ("LGPL-2.1", LicenseKind::WeakCopyleft),
+    ("LGPL-3.0", LicenseKind::WeakCopyleft),
+    ("MPL-1.1", LicenseKind::WeakCopyleft),
+    ("MPL-2.0", LicenseKind::WeakCopyleft),
+    ("CDDL-1.0", LicenseKind::WeakCopyleft),
+    ("CDDL-1.1", LicenseKind::WeakCopyleft),
+    (
+        "GPL-2.0-with-classpath-exception",
+        LicenseKind::WeakCopyleft,
+    ),
+    ("GPL-2.0", LicenseKind::StrongCopyleft),
+    ("GPL-3.0", LicenseKind::StrongCopyleft),
+    ("AGPL-3.0", LicenseKind::StrongCopyleft),
+];
+
+pub fn license_kind(spdx: &str) -> Option<LicenseKind> {
+    KNOWN_LICENSES
+        .iter()
+        .find(|(id, _)| *id == spdx)
+        .map(|(_, kind)| *kind)
+}
+
+/// A `<license>` block from a dependency's `pom.xml`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct License {
+    pub name: String,
+    pub url: Option<String>,
+    /// SPDX identifier, when the name or URL is recognized
+    pub spdx: Option<&'static str>,
+}
+
+impl License {
+    /// SPDX identifier, or the declared name for unrecognized licenses.
+    pub fn id(&self) -> &str {
+        self.spdx.unwrap_or(&self.name)
+    }
+}
+
+#[derive(Debug)]
+pub struct DependencyLicenses {
+    pub coordinate: MavenCoordinate,
+    /// Declared licenses; more than one means the dependency is dual-licensed
+    pub licenses: Vec<License>,
+}
+
+/// A LICENSE/NOTICE/COPYING file found in the JAR.
+#[derive(Debug)]
+pub struct LicenseFile {
+    pub path: String,
+    /// Dependency the file belongs to, when its path identifies one
+    pub owner: Option<MavenCoordinate>,
+    pub content: String,
+}
+
+#[derive(Debug)]
+pub struct LicenseReport {
+    pub dependencies: Vec<DependencyLicenses>,
+    pub files: Vec<LicenseFile>,
+}
+
+/// Licenses flagged by `--allow`/`--deny` (or `licenses_allow`/`licenses_deny`).
+/// Entries are SPDX identifiers, or declared names for unrecognized licenses.
+#[derive(Debug, Default)]
+pub struct LicensePolicy {
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Finding {
+    /// No license declared, or none recognized
+    Unknown,
+    /// Copyleft license not explicitly allowed
+    Copyleft(String),
+    /// Every declared license is on the deny list
+    Denied(String),
+    /// An allow list is set and no declared license is on it
+    NotAllowed(String),
+}
+
+impl Finding {
+    /// Violations fail `jbundle licenses`; other findings are warnings.
+    pub fn is_violation(&self) -> bool {
+        matches!(self, Finding::Denied(_) | Finding::NotAllowed(_))
+    }
+}
+
+impl std::fmt::Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Finding::Unknown => write!(f, "unknown license"),
+            Finding::Copyleft(id) => write!(f, "copyleft ({id})"),
+            Finding::Denied(id) => write!(f, "denied ({id})"),
+            Finding::NotAllowed(id) => write!(f, "not allowed ({id})"),
+        }
+    }
+}
+
+/// Collects the declared licenses of every Maven artifact in the JAR, plus
+/// the license files it carries. Spring Boot nested JARs are scanned too;
+/// their entries are reported as `BOOT-INF/lib/<jar>!/<path>`.
+pub fn scan_jar(jar_path: &Path) -> Result<LicenseReport, PackError> {
+    let file = std::fs::File::open(jar_path)?;
+    let mut archive = ZipArchive::new(file)?;
+
+    let mut report = LicenseReport {
+        dependencies: Vec::new(),
+        files: Vec::new(),
+    };
+    scan_archive(&mut archive, "", &mut report)?;
+    for name in crate::boot::nested_jar_names(&archive) {
+        if let Some(mut nested) = crate::boot::open_nested(&mut archive, &name)? {
+            scan_archive(&mut nested, &format!("{name}!/"), &mut report)?;
+        }
+    }
+
+    report
+        .dependencies
+        .sort_by(|a, b| a.coordinate.cmp(&b.coordinate));
+    report
+        .dependencies
+        .dedup_by(|a, b| a.coordinate == b.coordinate);
+    report.files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(report)
+}
+
+/// Adds one archive's POM licenses and license files, with entry paths
+/// prefixed by `prefix`.
+fn scan_archive<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    prefix: &str,
+    report: &mut LicenseReport,
+) -> Result<(), PackError> {
+    let coordinates = archive_coordinates(archive)?;
+    // A nested JAR is a single artifact, which owns all of its license files
+    let nested_owner = match coordinates.as_slice() {
+        [only] if !prefix.is_empty() => Some(only.clone()),
+        _ => None,
+    };
+
+    for coordinate in &coordinates {
+        let pom = format!(
+            "META-INF/maven/{}/{}/pom.xml",
+            coordinate.group_id, coordinate.artifact_id
+        );
+        let licenses = match read_entry(archive, &pom)? {
+            Some(xml) => parse_pom_licenses(&xml),
+            None => Vec::new(),
+        };
+        report.dependencies.push(DependencyLicenses {
+            coordinate: coordinate.clone(),
+            licenses,
+        });
+    }
+
+    let names: Vec<String> = archive
+        .file_names()
+        .filter(|n| is_license_file(n))
+        .map(str::to_string)
+        .collect();
+    for path in names {
+        let Some(content) = read_entry(archive, &path)? else {
+            continue;
+        };
+        let owner = nested_owner.clone().or_else(|| {
+            coordinates
+                .iter()
+                .find(|c| path.split('/').any(|segment| segment == c.artifact_id))
+                .cloned()
+        });
+        report.files.push(LicenseFile {
+            path: format!("{prefix}{path}"),
+            owner,
+            content,
+        });
+    }
+    Ok(())
+}
+
+fn read_entry<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+) -> Result<Option<String>, PackError> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    if entry.is_dir() {
+        return Ok(None);
+    }
+    let mut bytes = Vec::new();
+    entry.read_to_end(&mut bytes)?;
+    Ok(Some(String::from_utf8_lossy(&bytes).to_string()))
+}
+
+/// LICENSE, NOTICE and COPYING files, with or without an extension.
+fn is_license_file(path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or("").to_lowercase();
+    let stem = file_name.split('.').next().unwrap_or("");
+    ["license", "licence", "notice", "copying"]
+        .iter()
+        .any(|prefix| stem.starts_with(prefix))
+        && !file_name.ends_with(".class")
+}
+
+/// Extracts `<license>` blocks from a POM.
+fn parse_pom_licenses(xml: &str) -> Vec<License> {
+    let block = regex::Regex::new(r"(?s)<license>(.*?)</license>").unwrap();
+    let tag = |body: &str, name: &str| {
+        let re = regex::Regex::new(&format!(r"(?s)<{name}>\s*(.*?)\s*</{name}>")).unwrap();
+        re.captures(body)
+            .map(|c| c[1].to_string())
+            .filter(|v| !v.is_empty())
+    };
+    block
+        .captures_iter(xml)
+        .filter_map(|c| {
+            let body = &c[1];
+            let url = tag(body, "url");
+            let name = tag(body, "name").or_else(|| url.clone())?;
+            let spdx = normalize_license(&name, url.as_deref());
+            Some(License { name, url, spdx })
+        })
+        .collect()
+}
+
+/// Maps a declared license name or URL to its SPDX identifier.
+pub fn normalize_license(name: &str, url: Option<&str>) -> Option<&'static str> {
+    if let Some((id, _)) = KNOWN_LICENSES
+        .iter()
+        .find(|(id, _)| id.eq_ignore_ascii_case(name.trim()))
+    {
+        return Some(id);
+    }
+
+    let text = format!("{} {}", name, url.unwrap_or("")).to_lowercase();
+    let has = |s: &str| text.contains(s);
+    // Short acronyms must match whole words ("mpl" is also in "example")
+    let words: Vec<&str> = text
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let word = |prefix: &str| {
+        words.iter().any(|w| {
+            w.strip_prefix(prefix).is_some_and(|rest| {
+                rest.is_empty() || rest.starts_with('v') || rest.chars().all(|c| c.is_ascii_digit())
+            })
+        })
+    };
+    let v3 = has("3.0")
+        || has("version 3")
+        || words
+            .iter()
+            .any(|w| w.ends_with("v3") || w.ends_with("gpl3"));
+
+    let id = if has("apache") && (has("2.0") || has("license-2") || has("version 2")) {
+        "Apache-2.0"
+    } else if has("affero") || word("agpl") {
+        "AGPL-3.0"
+    } else if has("lesser") || word("lgpl") || has("library general public") {
+        if v3 {
+            "LGPL-3.0"
+        } else {
+            "LGPL-2.1"
+        }
+    } else if has("classpath exception")
+        || has("classpath-exception")
+        // "GPL2 w/ CPE" (javax/jakarta artifacts), openjdk.org/legal/gplv2+ce.html
+        || word("cpe")
+        || has("gplv2+ce")
+    {
+        "GPL-2.0-with-classpath-exception"
+    } else if has("general public license") || word("gpl") {
+        if v3 {
+            "GPL-3.0"
+        } else {
+            "GPL-2.0"
+        }
+    } else if has("eclipse public license") || word("epl") {
+        if has("2.0") || word("epl2") || has("epl-v20") {
+            "EPL-2.0"
+        } else {
+            "EPL-1.0"
+        }
+    } else if has("mozilla") || word("mpl") {
+        if has("1.1") {
+            "MPL-1.1"
+        } else {
+            "MPL-2.0"
+        }
+    } else if word("cddl") || has("common development and distribution") {
+        if has("1.1") {
+            "CDDL-1.1"
+        } else {
+            "CDDL-1.0"
+        }
+    } else if word("bsd") {
+        if has("2-clause") || has("simplified") || has("freebsd") {
+            "BSD-2-Clause"
+        } else if has("3-clause") || has("new") || has("revised") {
+            "BSD-3-Clause"
+        } else {
+            return None;
+        }
+    } else if word("mit") {
+        "MIT"
+    } else if word("cc0") || has("public domain") {
+        "CC0-1.0"
+    } else if has("isc license") {
+        "ISC"
+    } else {
+        return None;
+    };
+    Some(id)
+}
+
+/// Checks every dependency against the policy, returning flagged ones.
+pub fn evaluate<'a>(
+    report: &'a LicenseReport,
+    policy: &LicensePolicy,
+) -> Vec<(&'a MavenCoordinate, Finding)> {
+    let listed = |list: &[String], license: &License| {
+        list.iter().any(|entry| {
+            entry.eq_ignore_ascii_case(license.id()) || entry.eq_ignore_ascii_case(&license.name)
+        })
+    };
+
+    let mut findings = Vec::new();
+    for dep in &report.dependencies {
+        let licenses = &dep.licenses;
+        let finding = if licenses.is_empty() {
+            if policy.allow.is_empty() {
+                Some(Finding::Unknown)
+            } else {
+                Some(Finding::NotAllowed("no license declared".to_string()))
+            }
+        } else if licenses.iter().all(|l| listed(&policy.deny, l)) {
+            Some(Finding::Denied(join_ids(licenses)))
+        } else if !policy.allow.is_empty() && !licenses.iter().any(|l| listed(&policy.allow, l)) {
+            Some(Finding::NotAllowed(join_ids(licenses)))
+        } else {
+            // A dual-licensed dependency can be used under any acceptable option
+            let usable: Vec<&License> = licenses
+                .iter()
+                .filter(|l| !listed(&policy.deny, l))
+                .collect();
+            if usable.iter().any(|l| listed(&policy.allow, l)) {
+                None
+            } else if usable.iter().all(|l| l.spdx.is_none()) {
+                Some(Finding::Unknown)
+            } else if usable.iter().all(|l| {
+                l.spdx
+                    .and_then(license_kind)
+                    .is_some_and(|k| k != LicenseKind::Permissive)
+            }) {
+                let copyleft: Vec<License> = usable.into_iter().cloned().collect();
+                Some(Finding::Copyleft(join_ids(&copyleft)))
+            } else {
+                None
+            }
+        };
+        if let Some(finding) = finding {
+            findings.push((&dep.coordinate, finding));
+        }
+    }
+    findings
+}
+
+fn join_ids(licenses: &[License]) -> String {
+    licenses
+        .iter()
+        .map(License::id)
+        .collect::<Vec<_>>()
+        .join(" OR ")
+}
+
+/// Reads the JDK's `legal/` notices from a jlinked runtime directory, or from
+/// the runtime layer of a jbundle binary.
+pub fn read_runtime_legal(path: &Path) -> Result<Vec<(String, String)>, PackError> {
+    Ok(crate::pack::layout::read_runtime_files(path, "legal")?
+        .into_iter()
+        .map(|(name, bytes)| (name, String::from_utf8_lossy(&bytes).to_string()))
+        .collect())
+}
+
+const RULE: &str =
+    "--------------------------------------------------------------------------------";
+
+/// Renders an aggregated THIRD_PARTY_NOTICES text. Identical license texts
+/// are printed once and referenced by path afterwards.
+pub fn render_notices(report: &LicenseReport, runtime_legal: &[(String, String)]) -> String {
+    let mut out = String::from("THIRD-PARTY NOTICES\n\n");
+    out.push_str("This software bundles the following third-party components.\n");
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut push_text = |out: &mut String, path: &str, content: &str| {
+        if seen.insert(sha256_hex(content.trim().as_bytes())) {
+            out.push_str(&format!("\n{path}:\n\n{}\n", content.trim_end()));
+        } else {
+            out.push_str(&format!("\n{path}: same text as above\n"));
+        }
+    };
+
+    for dep in &report.dependencies {
+        out.push_str(&format!("\n{RULE}\n{}\n", dep.coordinate));
+        if dep.licenses.is_empty() {
+            out.push_str("License: not declared\n");
+        }
+        for license in &dep.licenses {
+            match license.url {
+                Some(ref url) => out.push_str(&format!("License: {} ({url})\n", license.name)),
+                None => out.push_str(&format!("License: {}\n", license.name)),
+            }
+        }
+        for file in report
+            .files
+            .iter()
+            .filter(|f| f.owner.as_ref() == Some(&dep.coordinate))
+        {
+            push_text(&mut out, &file.path, &file.content);
+        }
+    }
+
+    let unattributed: Vec<&LicenseFile> =
+        report.files.iter().filter(|f| f.owner.is_none()).collect();
+    if !unattributed.is_empty() {
+        out.push_str(&format!(
+            "\n{RULE}\nOther license files found in the application\n"
+        ));
+        for file in unattributed {
+            push_text(&mut out, &file.path, &file.content);
+        }
+    }
+
+    if !runtime_legal.is_empty() {
+        out.push_str(&format!("\n{RULE}\nJava runtime\n"));
+        for (path, content) in runtime_legal {
+            push_text(&mut out, path, content);
+        }
+    }
+    out
+}
+
+pub fn render_report(report: &LicenseReport, findings: &[(&MavenCoordinate, Finding)]) {
+    eprintln!();
+    if report.dependencies.is_empty() {
+        eprintln!("No Maven dependencies found (no META-INF/maven/**/pom.properties).");
+    } else {
+        eprintln!("{:<50} License", "Dependency");
+        eprintln!("{}", "\u{2500}".repeat(72));
+        for dep in &report.dependencies {
+            let licenses = if dep.licenses.is_empty() {
+                "?".to_string()
+            } else {
+                join_ids(&dep.licenses)
+            };
+            eprintln!("{:<50} {}", dep.coordinate.to_string(), licenses);
+        }
+    }
+    eprintln!();
+    eprintln!(
+        "License files: {} ({} not attributed to a dependency)",
+        report.files.len(),
+        report.files.iter().filter(|f| f.owner.is_none()).count()
+    );
+
+    if !findings.is_empty() {
+        eprintln!();
+        eprintln!("Flagged:");
+        for (coordinate, finding) in findings {
+            let marker = if finding.is_violation() {
+                "error"
+            } else {
+                "warn"
+            };
+            eprintln!("  {marker}: {coordinate}: {finding}");
+        }
+    }
+    eprintln!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn create_jar(entries: &[(&str, &str)]) -> NamedTempFile {
+        let file = NamedTempFile::new().unwrap();
+        let mut zip = ZipWriter::new(file.reopen().unwrap());
+        for (name, content) in entries {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+        file
+    }
+
+    fn dependency(artifact: &str, licenses: &[&str]) -> DependencyLicenses {
+        DependencyLicenses {
+            coordinate: MavenCoordinate {
+                group_id: "org.example".to_string(),
+                artifact_id: artifact.to_string(),
+                version: "1.0".to_string(),
+            },
+            licenses: licenses
+                .iter()
+                .map(|name| License {
+                    name: name.to_string(),
+                    url: None,
+                    spdx: normalize_license(name, None),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn normalize_common_license_names() {
+        let cases = [
+            ("The Apache Software License, Version 2.0", "Apache-2.0"),
+            ("Eclipse Public License 1.0", "EPL-1.0"),
+            ("EPL-2.0", "EPL-2.0"),
+            ("MIT License", "MIT"),
+            ("The MIT License", "MIT"),
+            ("GNU Lesser General Public License v3.0", "LGPL-3.0"),
+            ("GNU General Public License, version 2", "GPL-2.0"),
+            ("GPL2 w/ CPE", "GPL-2.0-with-classpath-exception"),
+            ("GPLv2+CE", "GPL-2.0-with-classpath-exception"),
+            (
+                "GNU General Public License, version 2 with the Classpath Exception",
+                "GPL-2.0-with-classpath-exception",
+            ),
+            ("New BSD License", "BSD-3-Clause"),
+            ("Mozilla Public License Version 2.0", "MPL-2.0"),
+            (
+                "CDDL + GPLv2 with classpath exception",
+                "GPL-2.0-with-classpath-exception",
+            ),
+        ];
+        for (name, expected) in cases {
+            assert_eq!(normalize_license(name, None), Some(expected), "{name}");
+        }
+        assert_eq!(
+            normalize_license("GPL2 w/ CPE", None).and_then(license_kind),
+            Some(LicenseKind::WeakCopyleft)
+        );
+        assert_eq!(
+            normalize_license(
+                "Custom",
+                Some("https://www.apache.org/licenses/LICENSE-2.0.txt")
+            ),
+            Some("Apache-2.0")
+        );
+        assert_eq!(normalize_license("Proprietary", None), None);
+        assert_eq!(
+            normalize_license("Sample License", Some("https://example.com/license")),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_pom_license_blocks() {
+        let xml = r#"<project>
+  <licenses>
+    <license>
+      <name>Eclipse Public License 1.0</name>
+      <url>http://opensource.org/licenses/eclipse-1.0.php</url>
+      <distribution>repo</distribution>
+    </license>
+    <license><name>Apache-2.0</name></license>
+  </licenses>
+</project>"#;
+        let licenses = parse_pom_licenses(xml);
+        assert_eq!(licenses.len(), 2);
+        assert_eq!(licenses[0].spdx, Some("EPL-1.0"));
+        assert_eq!(
+            licenses[0].url.as_deref(),
+            Some("http://opensource.org/licenses/eclipse-1.0.php")
+        );
+        assert_eq!(licenses[1].id(), "Apache-2.0");
+    }
+
+    #[test]
+    fn scan_jar_maps_poms_and_files() {
+        let jar = create_jar(&[
+            (
+                "META-INF/maven/org.clojure/clojure/pom.properties",
+                "groupId=org.clojure\nartifactId=clojure\nversion=1.12.0\n",
+            ),
+            (
+                "META-INF/maven/org.clojure/clojure/pom.xml",
+                "<licenses><license><name>Eclipse Public License 1.0</name></license></licenses>",
+            ),
+            ("META-INF/clojure/LICENSE.txt", "Eclipse Public License"),
+            ("META-INF/LICENSE", "Apache License"),
+            ("META-INF/NOTICE.txt", "Copyright"),
+            ("clojure/core.clj", "(ns clojure.core)"),
+        ]);
+
+        let report = scan_jar(jar.path()).unwrap();
+        assert_eq!(report.dependencies.len(), 1);
+        assert_eq!(report.dependencies[0].licenses[0].id(), "EPL-1.0");
+        let paths: Vec<&str> = report.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "META-INF/LICENSE",
+                "META-INF/NOTICE.txt",
+                "META-INF/clojure/LICENSE.txt"
+            ]
+        );
+        assert!(report.files[0].owner.is_none());
+        assert_eq!(
+            report.files[2].owner.as_ref().unwrap().artifact_id,
+            "clojure"
+        );
+    }
+
+    #[test]
+    fn scan_jar_reads_boot_nested_jars() {
+        let mut nested = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, content) in [
+            (
+                "META-INF/maven/org.postgresql/postgresql/pom.properties",
+                "groupId=org.postgresql\nartifactId=postgresql\nversion=42.7.3\n",
+            ),
+            (
+                "META-INF/maven/org.postgresql/postgresql/pom.xml",
+                "<licenses><license><name>BSD-2-Clause</name></license></licenses>",
+            ),
+            ("META-INF/LICENSE", "BSD 2-Clause"),
+        ] {
+            nested
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            nested.write_all(content.as_bytes()).unwrap();
+        }
+        let nested = nested.finish().unwrap().into_inner();
+
+        let file = NamedTempFile::new().unwrap();
+        let mut zip = ZipWriter::new(file.reopen().unwrap());
+        let stored =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        zip.start_file("BOOT-INF/classes/com/example/App.class", stored)
+            .unwrap();
+        zip.start_file("BOOT-INF/lib/postgresql-42.7.3.jar", stored)
+            .unwrap();
+        zip.write_all(&nested).unwrap();
+        zip.finish().unwrap();
+
+        let report = scan_jar(file.path()).unwrap();
+        assert_eq!(report.dependencies.len(), 1);
+        assert_eq!(report.dependencies[0].coordinate.artifact_id, "postgresql");
+        assert_eq!(report.dependencies[0].licenses[0].id(), "BSD-2-Clause");
+        assert_eq!(report.files.len(), 1);
+        assert_eq!(
+            report.files[0].path,
+            "BOOT-INF/lib/postgresql-42.7.3.jar!/META-INF/LICENSE"
+        );
+        assert_eq!(
+            report.files[0].owner.as_ref().unwrap().artifact_id,
+            "postgresql"
+        );
+    }
+
+    #[test]
+    fn evaluate_flags_against_policy() {
+        let report = LicenseReport {
+            dependencies: vec![
+                dependency("permissive", &["Apache-2.0"]),
+                dependency("gpl", &["GPL-3.0"]),
+                dependency("dual", &["GPL-2.0", "MIT"]),
+                dependency("none", &[]),
+                dependency("custom", &["Acme Commercial License"]),
+            ],
+            files: Vec::new(),
+        };
+
+        let findings = evaluate(&report, &LicensePolicy::default());
+        let flagged: Vec<(&str, &Finding)> = findings
+            .iter()
+            .map(|(c, f)| (c.artifact_id.as_str(), f))
+            .collect();
+        assert_eq!(
+            flagged,
+            vec![
+                ("gpl", &Finding::Copyleft("GPL-3.0".to_string())),
+                ("none", &Finding::Unknown),
+                ("custom", &Finding::Unknown),
+            ]
+        );
+        assert!(findings.iter().all(|(_, f)| !f.is_violation()));
+
+        let policy = LicensePolicy {
+            allow: vec!["Apache-2.0".to_string(), "MIT".to_string()],
+            deny: vec!["GPL-3.0".to_string()],
+        };
+        let findings = evaluate(&report, &policy);
+        let flagged: Vec<(&str, bool)> = findings
+            .iter()
+            .map(|(c, f)| (c.artifact_id.as_str(), f.is_violation()))
+            .collect();
+        assert_eq!(
+            flagged,
+            vec![("gpl", true), ("none", true), ("custom", true)]
+        );
+        assert_eq!(findings[0].1, Finding::Denied("GPL-3.0".to_string()));
+    }
+
+    #[test]
+    fn notices_include_dependencies_and_runtime() {
+        let mut dep = dependency("clojure", &["Eclipse Public License 1.0"]);
+        dep.licenses[0].url = Some("https://www.eclipse.org/legal/epl-v10.html".to_string());
+        let report = LicenseReport {
+            files: vec![
+                LicenseFile {
+                    path: "META-INF/clojure/LICENSE".to_string(),
+                    owner: Some(dep.coordinate.clone()),
+                    content: "EPL text".to_string(),
+                },
+                LicenseFile {
+                    path: "META-INF/LICENSE".to_string(),
+                    owner: None,
+                    content: "EPL text".to_string(),
+                },
+            ],
+            dependencies: vec![dep],
+        };
+        let legal = vec![(
+            "legal/java.base/LICENSE".to_string(),
+            "GPLv2 with Classpath Exception".to_string(),
+        )];
+
+        let notices = render_notices(&report, &legal);
+        assert!(notices.contains("org.example:clojure:1.0"));
+        assert!(notices.contains(
+            "License: Eclipse Public License 1.0 (https://www.eclipse.org/legal/epl-v10.html)"
+        ));
+        assert_eq!(notices.matches("EPL text").count(), 1);
+        assert!(notices.contains("META-INF/LICENSE: same text as above"));
+        assert!(notices.contains("legal/java.base/LICENSE:\n\nGPLv2 with Classpath Exception"));
+    }
+
+    #[test]
+    fn read_runtime_legal_from_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let legal = dir.path().join("legal").join("java.base");
+        std::fs::create_dir_all(&legal).unwrap();
+        std::fs::write(legal.join("LICENSE"), "GPLv2 + CPE").unwrap();
+        std::fs::write(legal.join("ADDITIONAL_LICENSE_INFO"), "info").unwrap();
+
+        let notices = read_runtime_legal(dir.path()).unwrap();
+        let paths: Vec<&str> = notices.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "legal/java.base/ADDITIONAL_LICENSE_INFO",
+                "legal/java.base/LICENSE"
+            ]
+        );
+    }
+
+    #[test]
+    fn read_runtime_legal_from_binary() {
+        use crate::config::{Banner, JvmProfile, WorkDir};
+
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = dir.path().join("runtime");
+        std::fs::create_dir_all(runtime.join("bin")).unwrap();
+        std::fs::write(runtime.join("bin").join("java"), b"#!/bin/sh\n").unwrap();
+        std::fs::create_dir_all(runtime.join("legal").join("java.base")).unwrap();
+        std::fs::write(
+            runtime.join("legal").join("java.base").join("LICENSE"),
+            "GPLv2 + CPE",
+        )
+        .unwrap();
+        let jar = dir.path().join("app.jar");
+        std::fs::write(&jar, b"fake jar").unwrap();
+        let output = dir.path().join("app");
+
+        crate::pack::create_binary(&crate::pack::PackOptions {
+            runtime_dir: &runtime,
+            jar_path: &jar,
+            crac_path: None,
+            resources: &[],
+            deps: &[],
+            main_class: None,
+            output: &output,
+            jvm_args: &[],
+            profile: &JvmProfile::Server,
+            appcds: false,
+            java_version: 21,
+            banner: &Banner::Full,
+            banner_first_run_only: false,
+            modules: "java.base",
+            flag_prefix: "jbundle",
+            target: "linux-x64",
+            update_url: None,
+            signature_pubkey: None,
+            sbom: None,
+            env: &Default::default(),
+            workdir: WorkDir::Cwd,
+        })
+        .unwrap();
+
+        let notices = read_runtime_legal(&output).unwrap();
+        assert_eq!(
+            notices,
+            vec![(
+                "legal/java.base/LICENSE".to_string(),
+                "GPLv2 + CPE".to_string()
+            )]
+        );
+    }
+}
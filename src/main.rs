@@ -1,4 +1,7 @@
 mod analyze;
+mod audit;
+mod boot;
+mod budget;
 mod build;
 mod cli;
 mod config;
@@ -9,21 +12,32 @@ mod error;
 mod gradle;
 mod jlink;
 mod jvm;
+mod licenses;
+mod maven;
 mod pack;
+mod patch;
 mod progress;
 mod project_config;
+mod sbom;
 mod shrink;
+mod sign;
+mod smoke;
+mod thin;
 mod validate;
 
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use clap::Parser;
 use indicatif::HumanBytes;
 
-use cli::{Cli, Command};
-use config::{detect_gc_conflict, BuildConfig, JvmProfile, Target};
+use cli::{BuildArgs, Cli, Command};
+use config::{
+    detect_gc_conflict, parse_size, validate_env, validate_flag_prefix, validate_update_url,
+    Banner, BuildConfig, BuildSystem, JlinkVm, JvmProfile, SbomFormat, Target, TargetOs, WorkDir,
+    DEFAULT_FLAG_PREFIX,
+};
 use error::PackError;
 use gradle::Subproject;
 use progress::Pipeline;
@@ -33,7 +47,10 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
 
     // Extract verbose flag before initializing tracing
-    let verbose = matches!(&cli.command, Command::Build { verbose: true, .. });
+    let verbose = matches!(
+        &cli.command,
+        Command::Build(args) if args.verbose
+    );
 
     let default_level = if verbose {
         "jbundle=info"
@@ -50,23 +67,42 @@ async fn main() -> Result<()> {
         .init();
 
     match cli.command {
-        Command::Build {
-            input,
-            output,
-            java_version,
-            target,
-            jvm_args,
-            shrink,
-            profile,
-            no_appcds,
-            crac,
-            gradle_project,
-            all,
-            modules,
-            jlink_runtime,
-            verbose: _,
-            compact_banner,
-        } => {
+        Command::Build(args) => {
+            let BuildArgs {
+                input,
+                output,
+                java_version,
+                target,
+                jvm_args,
+                shrink,
+                profile,
+                no_appcds,
+                no_module_hints,
+                crac,
+                thin,
+                gradle_project,
+                maven_module,
+                bazel_target,
+                buck_target,
+                mill_module,
+                sbt_project,
+                all,
+                modules,
+                trace_modules,
+                trace_args,
+                jlink_runtime,
+                verbose: _,
+                compact_banner,
+                banner,
+                banner_file,
+                banner_first_run_only,
+                launcher_flag_prefix,
+                update_url,
+                sign_key,
+                require_signature,
+                sbom,
+                embed_sbom,
+            } = *args;
             let input_path =
                 std::fs::canonicalize(&input).unwrap_or_else(|_| PathBuf::from(&input));
 
@@ -135,10 +171,48 @@ async fn main() -> Result<()> {
                     .and_then(|c| c.crac)
                     .unwrap_or(false);
 
-            let compact_banner = compact_banner
+            let thin = thin
+                || project_config
+                    .as_ref()
+                    .and_then(|c| c.thin)
+                    .unwrap_or(false);
+
+            // Banner (CLI > config file; --compact-banner is shorthand for "compact",
+            // a banner file alone implies "custom")
+            let config_compact = project_config
+                .as_ref()
+                .and_then(|c| c.compact_banner)
+                .unwrap_or(false);
+            let banner_mode = banner
+                .or_else(|| compact_banner.then(|| "compact".to_string()))
+                .or_else(|| project_config.as_ref().and_then(|c| c.banner.clone()))
+                .or_else(|| config_compact.then(|| "compact".to_string()));
+            let banner_file = banner_file.or_else(|| {
+                project_config
+                    .as_ref()
+                    .and_then(|c| c.banner_file.as_ref())
+                    .map(|f| project_dir.join(f))
+            });
+            let banner_mode = match (banner_mode, &banner_file) {
+                (Some(mode), Some(_)) if mode != "custom" => {
+                    return Err(PackError::BannerFileConflict(mode).into());
+                }
+                (None, Some(_)) => "custom".to_string(),
+                (mode, _) => mode.unwrap_or_else(|| "full".to_string()),
+            };
+            let banner_text = match &banner_file {
+                Some(path) => Some(
+                    std::fs::read_to_string(path)
+                        .context(format!("failed to read banner file {}", path.display()))?,
+                ),
+                None => None,
+            };
+            let banner = Banner::parse(&banner_mode, banner_text)?;
+
+            let banner_first_run_only = banner_first_run_only
                 || project_config
                     .as_ref()
-                    .and_then(|c| c.compact_banner)
+                    .and_then(|c| c.banner_first_run_only)
                     .unwrap_or(false);
 
             // Gradle subproject selection (CLI > config file)
@@ -148,6 +222,22 @@ async fn main() -> Result<()> {
                     .and_then(|c| c.gradle_project.clone())
             });
 
+            // Maven module selection (CLI > config file)
+            let maven_module = maven_module
+                .or_else(|| project_config.as_ref().and_then(|c| c.maven_module.clone()));
+
+            // Bazel / Buck2 targets (CLI > config file)
+            let bazel_target = bazel_target
+                .or_else(|| project_config.as_ref().and_then(|c| c.bazel_target.clone()));
+            let buck_target =
+                buck_target.or_else(|| project_config.as_ref().and_then(|c| c.buck_target.clone()));
+
+            // Mill module / sbt subproject (CLI > config file)
+            let mill_module =
+                mill_module.or_else(|| project_config.as_ref().and_then(|c| c.mill_module.clone()));
+            let sbt_project =
+                sbt_project.or_else(|| project_config.as_ref().and_then(|c| c.sbt_project.clone()));
+
             // Manual modules override (CLI > config file)
             let modules_override = modules
                 .map(|m| m.split(',').map(|s| s.trim().to_string()).collect())
@@ -161,6 +251,130 @@ async fn main() -> Result<()> {
                     .map(PathBuf::from)
             });
 
+            // jlink tuning (config file only)
+            let jlink_options = match project_config.as_ref().and_then(|c| c.jlink.as_ref()) {
+                Some(j) => jlink::JlinkOptions {
+                    strip_native_commands: j.strip_native_commands.unwrap_or(false),
+                    vm: j.vm.as_deref().map(JlinkVm::from_str).transpose()?,
+                    include_locales: j.include_locales.clone().unwrap_or_default(),
+                    generate_cds_archive: j.generate_cds_archive.unwrap_or(false),
+                    dedup_legal_notices: j.dedup_legal_notices.unwrap_or(false),
+                    bind_services: j.bind_services.unwrap_or(false),
+                    extra_args: j.extra_args.clone().unwrap_or_default(),
+                },
+                None => jlink::JlinkOptions::default(),
+            };
+
+            // Module hints (CLI opt-out > config file > on)
+            let module_hints = !no_module_hints
+                && project_config
+                    .as_ref()
+                    .and_then(|c| c.jlink.as_ref())
+                    .and_then(|j| j.heuristics)
+                    .unwrap_or(true);
+
+            // Launcher flag prefix (CLI > config file)
+            let flag_prefix = launcher_flag_prefix
+                .or_else(|| {
+                    project_config
+                        .as_ref()
+                        .and_then(|c| c.launcher_flag_prefix.clone())
+                })
+                .unwrap_or_else(|| DEFAULT_FLAG_PREFIX.to_string());
+            validate_flag_prefix(&flag_prefix)?;
+
+            // Self-update manifest URL (CLI > config file)
+            let update_url =
+                update_url.or_else(|| project_config.as_ref().and_then(|c| c.update_url.clone()));
+            if let Some(ref url) = update_url {
+                validate_update_url(url)?;
+            }
+
+            // Signing (CLI > config file; key path relative to the project)
+            let sign_key = sign_key.or_else(|| {
+                project_config
+                    .as_ref()
+                    .and_then(|c| c.sign_key.as_ref())
+                    .map(|k| project_dir.join(k))
+            });
+            let require_signature = require_signature
+                || project_config
+                    .as_ref()
+                    .and_then(|c| c.require_signature)
+                    .unwrap_or(false);
+            if require_signature && sign_key.is_none() {
+                return Err(PackError::MissingSignKey.into());
+            }
+
+            // SBOM (CLI > config file; embedding alone implies cyclonedx)
+            let embed_sbom = embed_sbom
+                || project_config
+                    .as_ref()
+                    .and_then(|c| c.embed_sbom)
+                    .unwrap_or(false);
+            let sbom = match sbom.or_else(|| project_config.as_ref().and_then(|c| c.sbom.clone())) {
+                Some(s) => Some(SbomFormat::from_str(&s)?),
+                None => embed_sbom.then_some(SbomFormat::CycloneDx),
+            };
+
+            // Size budget (config file only)
+            let budget = match project_config.as_ref().and_then(|c| c.budget.as_ref()) {
+                Some(b) => {
+                    let parse = |s: &Option<String>| s.as_deref().map(parse_size).transpose();
+                    budget::SizeBudget {
+                        max_binary: parse(&b.max_binary_size)?,
+                        max_runtime: parse(&b.max_runtime_size)?,
+                        max_app: parse(&b.max_app_size)?,
+                    }
+                }
+                None => budget::SizeBudget::default(),
+            };
+
+            // Smoke test of the packed binary (config file only)
+            let smoke_test = match project_config.as_ref().and_then(|c| c.test.as_ref()) {
+                Some(t) => Some(smoke::SmokeTest {
+                    args: t.args.clone().unwrap_or_default(),
+                    expect_exit: t.expect_exit.unwrap_or(0),
+                    expect_stdout: t
+                        .expect_stdout_regex
+                        .as_deref()
+                        .map(regex::Regex::new)
+                        .transpose()
+                        .map_err(|e| PackError::InvalidTestRegex(e.to_string()))?,
+                    timeout: t
+                        .timeout_secs
+                        .map(std::time::Duration::from_secs)
+                        .unwrap_or(smoke::DEFAULT_TIMEOUT),
+                }),
+                None => None,
+            };
+
+            // Launcher environment and working directory (config file only)
+            let env = project_config
+                .as_ref()
+                .and_then(|c| c.env.clone())
+                .unwrap_or_default();
+            validate_env(&env)?;
+            let workdir_str = project_config
+                .as_ref()
+                .and_then(|c| c.workdir.clone())
+                .unwrap_or_else(|| "cwd".to_string());
+            let workdir = WorkDir::from_str(&workdir_str)?;
+
+            // Resource directories, relative to the project (config file only).
+            // Names are checked as written: "." joined to the project dir would
+            // otherwise be stored under the project's directory name.
+            let configured: Vec<PathBuf> = project_config
+                .as_ref()
+                .and_then(|c| c.resources.as_ref())
+                .map(|r| r.iter().map(PathBuf::from).collect())
+                .unwrap_or_default();
+            pack::archive::resource_names(&configured)?;
+            let resources: Vec<PathBuf> = configured.iter().map(|p| project_dir.join(p)).collect();
+            if let Some(missing) = resources.iter().find(|p| !p.exists()) {
+                return Err(PackError::ResourceNotFound(missing.clone()).into());
+            }
+
             // Check for GC conflicts between profile and jvm_args
             if let Some(conflict) = detect_gc_conflict(&jvm_profile, &jvm_args) {
                 tracing::warn!(
@@ -185,11 +399,32 @@ async fn main() -> Result<()> {
                 profile: jvm_profile,
                 appcds,
                 crac,
-                compact_banner,
+                thin,
+                banner,
+                banner_first_run_only,
                 gradle_project,
+                maven_module,
                 build_all: all,
+                bazel_target,
+                buck_target,
+                mill_module,
+                sbt_project,
                 modules_override,
+                trace_modules: trace_modules.then_some(trace_args),
                 jlink_runtime,
+                jlink_options,
+                module_hints,
+                flag_prefix,
+                env,
+                workdir,
+                resources,
+                update_url,
+                sign_key,
+                require_signature,
+                sbom,
+                embed_sbom,
+                budget,
+                smoke_test,
             };
 
             if config.build_all {
@@ -198,10 +433,149 @@ async fn main() -> Result<()> {
                 run_build(config).await?;
             }
         }
-        Command::Analyze { input } => {
+        Command::Analyze {
+            input,
+            path,
+            format,
+            baseline,
+            max_growth,
+        } => {
+            let input = path.unwrap_or(input);
             let input_path =
                 std::fs::canonicalize(&input).unwrap_or_else(|_| PathBuf::from(&input));
-            analyze::run_analyze(&input_path)?;
+            let format = analyze::ReportFormat::from_str(&format)?;
+            let max_growth = max_growth
+                .map(|s| analyze::MaxGrowth::from_str(&s))
+                .transpose()?;
+            let baseline = baseline.map(|b| std::fs::canonicalize(&b).unwrap_or(b));
+            analyze::run_analyze(&input_path, format, baseline.as_deref(), max_growth)?;
+        }
+        Command::Licenses {
+            input,
+            allow,
+            deny,
+            notices,
+            runtime,
+        } => {
+            let input_path =
+                std::fs::canonicalize(&input).unwrap_or_else(|_| PathBuf::from(&input));
+            let project_dir = if input_path.is_dir() {
+                input_path.clone()
+            } else {
+                std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+            };
+            let project_config = project_config::load_project_config(&project_dir)?;
+
+            // Policy (CLI > config file)
+            let policy = licenses::LicensePolicy {
+                allow: if allow.is_empty() {
+                    project_config
+                        .as_ref()
+                        .and_then(|c| c.licenses_allow.clone())
+                        .unwrap_or_default()
+                } else {
+                    allow
+                },
+                deny: if deny.is_empty() {
+                    project_config
+                        .as_ref()
+                        .and_then(|c| c.licenses_deny.clone())
+                        .unwrap_or_default()
+                } else {
+                    deny
+                },
+            };
+            run_licenses(&input_path, &policy, notices.as_deref(), runtime.as_deref())?;
+        }
+        Command::Audit {
+            input,
+            advisories,
+            jdk_advisories,
+            runtime,
+            fail_on,
+        } => {
+            let input_path =
+                std::fs::canonicalize(&input).unwrap_or_else(|_| PathBuf::from(&input));
+            let project_dir = if input_path.is_dir() {
+                input_path.clone()
+            } else {
+                std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+            };
+            let project_config = project_config::load_project_config(&project_dir)?;
+
+            // CLI > config file (paths in jbundle.toml are relative to the project)
+            let advisories = advisories
+                .or_else(|| {
+                    project_config
+                        .as_ref()
+                        .and_then(|c| c.audit_advisories.as_ref())
+                        .map(|p| project_dir.join(p))
+                })
+                .ok_or_else(|| {
+                    PackError::AuditFailed(
+                        "no advisory database; pass --advisories or set audit_advisories"
+                            .to_string(),
+                    )
+                })?;
+            let fail_on = fail_on
+                .or_else(|| {
+                    project_config
+                        .as_ref()
+                        .and_then(|c| c.audit_fail_on.clone())
+                })
+                .map(|s| audit::Severity::from_str(&s))
+                .transpose()?;
+            let jdk_advisories = jdk_advisories.or_else(|| {
+                project_config
+                    .as_ref()
+                    .and_then(|c| c.audit_jdk_advisories.as_ref())
+                    .map(|p| project_dir.join(p))
+            });
+
+            // The runtime to check: --runtime, else the binary being audited,
+            // else the JDK a build of this project bundles (if downloaded)
+            let runtime = runtime.or_else(|| {
+                if input_path.is_dir() {
+                    let java_version = project_config
+                        .as_ref()
+                        .and_then(|c| c.java_version)
+                        .unwrap_or(21);
+                    jvm::cache::cached_jdk_path(java_version, &Target::current())
+                        .ok()
+                        .filter(|jdk| jdk.is_dir())
+                } else if pack::layout::read_layout(&input_path).is_ok() {
+                    Some(input_path.clone())
+                } else {
+                    None
+                }
+            });
+            run_audit(
+                &input_path,
+                &advisories,
+                jdk_advisories.as_deref(),
+                runtime.as_deref(),
+                fail_on,
+            )?;
+        }
+        Command::Diff { old, new, output } => {
+            run_diff(&old, &new, &output)?;
+        }
+        Command::Patch {
+            base,
+            patch,
+            output,
+        } => {
+            patch::apply_patch(&base, &patch, &output)?;
+            eprintln!("Patched binary written to {}", output.display());
+        }
+        Command::Verify { binary, pubkey } => {
+            let key = sign::load_verifying_key(&pubkey)?;
+            sign::verify_binary(&binary, &key)?;
+            eprintln!(
+                "{}: signature ok (key {})",
+                binary.display(),
+                sign::key_fingerprint(&key)
+            );
         }
         Command::Clean => {
             run_clean()?;
@@ -238,21 +612,65 @@ fn select_gradle_subproject<'a>(
     }
 
     // Multiple subprojects: prompt user for selection
-    eprintln!("\nMultiple application subprojects found:");
-    for (i, sub) in app_subprojects.iter().enumerate() {
-        let desc = sub
-            .main_class
-            .as_deref()
-            .unwrap_or("(no main class detected)");
-        eprintln!("  [{}] {} - {}", i + 1, sub.name, desc);
+    let choices: Vec<_> = app_subprojects
+        .iter()
+        .map(|s| (s.name.as_str(), s.main_class.as_deref()))
+        .collect();
+    let choice = prompt_selection("subproject", &choices, "gradle_project")?;
+    choice.map(|i| &app_subprojects[i]).ok_or_else(|| {
+        PackError::MultipleApplicationSubprojects(
+            app_subprojects.iter().map(|s| s.name.clone()).collect(),
+        )
+    })
+}
+
+/// Select a Maven module based on CLI flag or interactive prompt.
+fn select_maven_module<'a>(
+    app_modules: &'a [maven::Module],
+    cli_selection: Option<&str>,
+) -> Result<&'a maven::Module, PackError> {
+    if let Some(name) = cli_selection {
+        let name = name.trim_end_matches('/');
+        return app_modules
+            .iter()
+            .find(|m| m.name == name)
+            .ok_or_else(|| PackError::MavenModuleNotFound(name.to_string()));
+    }
+
+    // Detection only reports reactors with at least one application module
+    if app_modules.len() == 1 {
+        return Ok(&app_modules[0]);
+    }
+
+    let choices: Vec<_> = app_modules
+        .iter()
+        .map(|m| (m.name.as_str(), m.main_class.as_deref()))
+        .collect();
+    let choice = prompt_selection("module", &choices, "maven_module")?;
+    choice.map(|i| &app_modules[i]).ok_or_else(|| {
+        PackError::MultipleApplicationModules(app_modules.iter().map(|m| m.name.clone()).collect())
+    })
+}
+
+/// Lists `(name, main class)` choices and reads a 1-based selection from stdin.
+/// Returns None when the answer isn't a valid choice.
+fn prompt_selection(
+    kind: &str,
+    choices: &[(&str, Option<&str>)],
+    config_key: &str,
+) -> Result<Option<usize>, PackError> {
+    eprintln!("\nMultiple application {kind}s found:");
+    for (i, (name, main_class)) in choices.iter().enumerate() {
+        let desc = main_class.unwrap_or("(no main class detected)");
+        eprintln!("  [{}] {} - {}", i + 1, name, desc);
     }
     eprintln!();
     eprintln!(
-        "Tip: Add 'gradle_project = \"{}\"' to jbundle.toml to skip this prompt",
-        app_subprojects[0].name
+        "Tip: Add '{} = \"{}\"' to jbundle.toml to skip this prompt",
+        config_key, choices[0].0
     );
     eprintln!();
-    eprint!("Select subproject [1-{}]: ", app_subprojects.len());
+    eprint!("Select {kind} [1-{}]: ", choices.len());
     std::io::stderr().flush().ok();
 
     let mut input = String::new();
@@ -260,80 +678,90 @@ fn select_gradle_subproject<'a>(
         .read_line(&mut input)
         .map_err(|e| PackError::BuildFailed(format!("failed to read input: {e}")))?;
 
-    let choice: usize = input.trim().parse().map_err(|_| {
-        PackError::MultipleApplicationSubprojects(
-            app_subprojects.iter().map(|s| s.name.clone()).collect(),
-        )
-    })?;
-
-    if choice == 0 || choice > app_subprojects.len() {
-        return Err(PackError::MultipleApplicationSubprojects(
-            app_subprojects.iter().map(|s| s.name.clone()).collect(),
-        ));
-    }
-
-    Ok(&app_subprojects[choice - 1])
+    Ok(input
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|&choice| choice >= 1 && choice <= choices.len())
+        .map(|choice| choice - 1))
 }
 
-/// Build all application subprojects in a Gradle multi-project.
+/// Build all application subprojects of a Gradle multi-project, or all
+/// application modules of a Maven reactor.
 async fn run_build_all(config: BuildConfig) -> Result<()> {
     // Detect multi-project
     let detected = detect::detect_build_system_enhanced(&config.input)?;
 
-    let app_subprojects = match detected {
+    // (name, main class) of each application to build
+    let (kind, apps): (&str, Vec<(String, Option<String>)>) = match detected {
         detect::DetectedBuild::GradleMultiProject {
             app_subprojects, ..
-        } => app_subprojects,
+        } => (
+            "subprojects",
+            app_subprojects
+                .into_iter()
+                .map(|s| (s.name, s.main_class))
+                .collect(),
+        ),
+        detect::DetectedBuild::MavenMultiModule { app_modules, .. } => (
+            "modules",
+            app_modules
+                .into_iter()
+                .map(|m| (m.name, m.main_class))
+                .collect(),
+        ),
         detect::DetectedBuild::Simple(_) => {
             anyhow::bail!(
-                "--all flag requires a Gradle multi-project build. \
-                 No subprojects with application plugin found."
+                "--all flag requires a Gradle multi-project or Maven multi-module build. \
+                 No subprojects with application plugin or modules with a \
+                 shade/assembly/spring-boot plugin found."
             );
         }
     };
+    let is_maven = kind == "modules";
 
-    if app_subprojects.is_empty() {
-        anyhow::bail!("No application subprojects found in Gradle multi-project.");
+    if apps.is_empty() {
+        anyhow::bail!("No application {kind} found.");
     }
 
     eprintln!();
-    eprintln!(
-        "Building {} application subprojects:",
-        app_subprojects.len()
-    );
-    for sub in &app_subprojects {
-        let desc = sub
-            .main_class
-            .as_deref()
-            .unwrap_or("(no main class detected)");
-        eprintln!("  - {} ({})", sub.name, desc);
+    eprintln!("Building {} application {}:", apps.len(), kind);
+    for (name, main_class) in &apps {
+        let desc = main_class.as_deref().unwrap_or("(no main class detected)");
+        eprintln!("  - {} ({})", name, desc);
     }
     eprintln!();
 
     let base_output = config.output.clone();
     let mut built = Vec::new();
 
-    for sub in &app_subprojects {
+    for (name, _) in &apps {
         // Create output path: base_output/subproject_name
-        let output = base_output.join(&sub.name);
+        let output = base_output.join(name);
 
         // Create parent directory if needed
         if let Some(parent) = output.parent() {
             std::fs::create_dir_all(parent)?;
         }
 
-        eprintln!("━━━ Building {} ━━━", sub.name);
+        eprintln!("━━━ Building {} ━━━", name);
 
         // Clone config with subproject-specific settings
+        let (gradle_project, maven_module) = if is_maven {
+            (None, Some(name.clone()))
+        } else {
+            (Some(name.clone()), None)
+        };
         let sub_config = BuildConfig {
-            gradle_project: Some(sub.name.clone()),
+            gradle_project,
+            maven_module,
             output,
             build_all: false, // Prevent recursion
             ..config.clone()
         };
 
         run_build(sub_config).await?;
-        built.push(sub.name.clone());
+        built.push(name.clone());
         eprintln!();
     }
 
@@ -346,26 +774,79 @@ async fn run_build_all(config: BuildConfig) -> Result<()> {
     Ok(())
 }
 
-fn calculate_steps(is_jar_input: bool, shrink: bool, crac: bool) -> usize {
+fn calculate_steps(
+    is_jar_input: bool,
+    shrink: bool,
+    trace: bool,
+    crac: bool,
+    thin: bool,
+    sbom: bool,
+    smoke_test: bool,
+) -> usize {
     let base = if is_jar_input { 1 } else { 2 }; // JAR or detect+build
     let shrink_step = if shrink { 1 } else { 0 };
+    let trace_step = if trace { 1 } else { 0 };
     let crac_step = if crac { 1 } else { 0 };
-    base + shrink_step + 4 + crac_step // +4 = JDK, jdeps, jlink, pack
+    let thin_step = if thin { 1 } else { 0 };
+    let sbom_step = if sbom { 1 } else { 0 };
+    let smoke_step = if smoke_test { 1 } else { 0 };
+    // +4 = JDK, jdeps, jlink, pack
+    base + shrink_step + 4 + trace_step + crac_step + thin_step + sbom_step + smoke_step
 }
 
 async fn run_build(config: BuildConfig) -> Result<()> {
     let is_jar_input = config.input.extension().is_some_and(|e| e == "jar");
-    let total_steps = calculate_steps(is_jar_input, config.shrink, config.crac);
+    let is_host_target = config.target.name() == Target::current().name();
+    // Binaries for other platforms can't be run here
+    let smoke_test = config.smoke_test.as_ref().filter(|_| {
+        if !is_host_target {
+            tracing::warn!(
+                "skipping [test]: the binary targets {}, not this machine",
+                config.target.name()
+            );
+        }
+        is_host_target
+    });
+    let total_steps = calculate_steps(
+        is_jar_input,
+        config.shrink,
+        config.trace_modules.is_some(),
+        config.crac,
+        config.thin,
+        config.sbom.is_some(),
+        smoke_test.is_some(),
+    );
     let mut pipeline = Pipeline::new(total_steps);
 
+    // The launcher verifies with `openssl pkeyutl -rawin` (OpenSSL 3); macOS
+    // ships LibreSSL, so users need OpenSSL 3 installed and first on PATH
+    if config.require_signature && config.target.os == TargetOs::MacOs {
+        tracing::warn!(
+            "--require-signature: {} binaries only run where OpenSSL 3 is on PATH; \
+             the LibreSSL openssl that ships with macOS can't verify Ed25519 signatures",
+            config.target.name()
+        );
+    }
+
+    // Tracing runs the app on the downloaded JDK, which has to run here
+    if config.trace_modules.is_some() && !is_host_target {
+        return Err(PackError::TraceFailed(format!(
+            "--trace-modules runs the app on this machine, but the target is {} (host: {})",
+            config.target.name(),
+            Target::current().name()
+        ))
+        .into());
+    }
+
     eprintln!();
 
     // Step: Detect build system (only for project directories)
-    let (jar_path, detected_modules) = if is_jar_input {
+    // Also remembers how the project was built, for classpath resolution in thin mode
+    let (jar_path, detected_modules, build_source) = if is_jar_input {
         let step = pipeline.start_step("Using pre-built JAR");
         let jar = config.input.clone();
         Pipeline::finish_step(&step, &format!("JAR: {}", jar.display()));
-        (jar, Vec::new())
+        (jar, Vec::new(), None)
     } else {
         let step = pipeline.start_step("Detecting build system");
         let detected = detect::detect_build_system_enhanced(&config.input)?;
@@ -376,12 +857,26 @@ async fn run_build(config: BuildConfig) -> Result<()> {
 
                 let build_desc = build::build_command_description(system);
                 let step = pipeline.start_step(&format!("Building uberjar ({})", build_desc));
-                let jar = build::build_uberjar(&config.input, system)?;
+                let target = match system {
+                    BuildSystem::Bazel => config.bazel_target.clone(),
+                    BuildSystem::Buck2 => config.buck_target.clone(),
+                    BuildSystem::Mill => config.mill_module.clone(),
+                    BuildSystem::Sbt => config.sbt_project.clone(),
+                    _ => None,
+                };
+                let jar = build::build_uberjar(&config.input, system, target.as_deref())?;
                 Pipeline::finish_step(
                     &step,
                     &format!("{}", jar.file_name().unwrap_or_default().to_string_lossy()),
                 );
-                (jar, Vec::new())
+                // The Mill module / sbt project also selects the classpath in thin mode
+                let subproject =
+                    target.filter(|_| matches!(system, BuildSystem::Mill | BuildSystem::Sbt));
+                (
+                    jar,
+                    Vec::new(),
+                    Some((config.input.clone(), system, subproject)),
+                )
             }
             detect::DetectedBuild::GradleMultiProject {
                 project,
@@ -403,11 +898,51 @@ async fn run_build(config: BuildConfig) -> Result<()> {
                     &step,
                     &format!("{}", jar.file_name().unwrap_or_default().to_string_lossy()),
                 );
-                (jar, gradle_modules)
+                (
+                    jar,
+                    gradle_modules,
+                    Some((
+                        project.root.clone(),
+                        BuildSystem::Gradle,
+                        Some(selected.name.clone()),
+                    )),
+                )
+            }
+            detect::DetectedBuild::MavenMultiModule {
+                project,
+                app_modules,
+            } => {
+                let selected = select_maven_module(&app_modules, config.maven_module.as_deref())?;
+
+                Pipeline::finish_step(&step, &format!("Maven multi-module ({})", selected.name));
+
+                let build_desc = build::maven_module_command_description(&selected.name);
+                let step = pipeline.start_step(&format!("Building uberjar ({})", build_desc));
+                let jar = build::build_maven_module(&project.root, &selected.name)?;
+                Pipeline::finish_step(
+                    &step,
+                    &format!("{}", jar.file_name().unwrap_or_default().to_string_lossy()),
+                );
+                (
+                    jar,
+                    Vec::new(),
+                    Some((
+                        project.root.clone(),
+                        BuildSystem::Maven,
+                        Some(selected.name.clone()),
+                    )),
+                )
             }
         }
     };
 
+    // Read Maven coordinates for the SBOM now; shrinking strips pom.properties
+    let sbom_libraries = if config.sbom.is_some() {
+        analyze::maven::read_coordinates(&jar_path)?
+    } else {
+        Vec::new()
+    };
+
     // Step: Shrink JAR (optional)
     let jar_path = if config.shrink {
         let step = pipeline.start_step("Shrinking JAR");
@@ -462,6 +997,19 @@ async fn run_build(config: BuildConfig) -> Result<()> {
 
     let temp_dir = tempfile::tempdir()?;
 
+    // Resolve the project's runtime classpath once; jdeps and thin mode share it.
+    // Build tools without a classpath query are skipped: jdeps works on the
+    // deploy JAR alone and there is nothing to warn about.
+    let mut classpath = match build_source {
+        Some((ref root, system, ref subproject))
+            if thin::resolves_classpath(system)
+                && (config.thin || config.modules_override.is_none()) =>
+        {
+            Some(thin::resolve_classpath(root, system, subproject.as_deref()))
+        }
+        _ => None,
+    };
+
     // Step: Detect modules (jdeps) - skip if using manual override or existing runtime
     let modules = if let Some(ref override_modules) = config.modules_override {
         // Use manual module override
@@ -472,22 +1020,91 @@ async fn run_build(config: BuildConfig) -> Result<()> {
     } else {
         // Detect modules with jdeps, combining with Gradle-detected modules
         let step = pipeline.start_step("Analyzing module dependencies");
-        let mut modules = jlink::detect_modules(&jdk_path, &jar_path)?;
-
-        // Append Gradle-detected modules if any
-        if !detected_modules.is_empty() {
-            let extra = detected_modules.join(",");
-            if !modules.is_empty() {
-                modules.push(',');
+        let deps = match classpath {
+            Some(Ok(ref deps)) => deps.as_slice(),
+            Some(Err(ref e)) => {
+                tracing::warn!("running jdeps without the project classpath: {e}");
+                &[]
             }
-            modules.push_str(&extra);
-            // Deduplicate
-            let mut module_set: std::collections::HashSet<&str> = modules.split(',').collect();
-            modules = module_set.drain().collect::<Vec<_>>().join(",");
+            None => &[],
+        };
+        let jdeps_modules = jlink::detect_modules(&jdk_path, &jar_path, java_version, deps)?;
+
+        // Combine with Gradle-detected modules, deduplicated in a stable order
+        let mut module_set: std::collections::BTreeSet<String> = jdeps_modules
+            .split(',')
+            .map(str::to_string)
+            .chain(detected_modules.iter().cloned())
+            .collect();
+
+        // Add modules that are only looked up at runtime (providers, charsets, ...)
+        let hints = if config.module_hints {
+            jlink::available_hints(&jdk_path, jlink::detect_hints(&jar_path)?)
+        } else {
+            Vec::new()
+        };
+        let hinted: Vec<_> = hints
+            .into_iter()
+            .filter(|hint| module_set.insert(hint.module.to_string()))
+            .collect();
+
+        let module_count = module_set.len();
+        let result = if hinted.is_empty() {
+            format!("{} modules", module_count)
+        } else {
+            format!(
+                "{} modules ({} from runtime lookups)",
+                module_count,
+                hinted.len()
+            )
+        };
+        Pipeline::finish_step(&step, &result);
+        for hint in &hinted {
+            eprintln!("  \x1b[33mℹ\x1b[0m Added {}: {}", hint.module, hint.reason);
         }
+        module_set.into_iter().collect::<Vec<_>>().join(",")
+    };
+    eprintln!("  \x1b[33mℹ\x1b[0m Modules: {modules}");
+
+    // Step: Trace modules loaded at runtime (optional)
+    let modules = if let Some(ref app_args) = config.trace_modules {
+        let step = pipeline.start_step("Tracing modules at runtime");
+        let traced = jlink::trace_modules(
+            &jdk_path,
+            &jar_path,
+            &config.jvm_args,
+            app_args,
+            temp_dir.path(),
+        )?;
+        let missing = jlink::missing_modules(&traced, &modules);
+        if missing.is_empty() {
+            Pipeline::finish_step(&step, "no missing modules");
+            modules
+        } else {
+            Pipeline::finish_step(&step, &format!("{} missing modules", missing.len()));
+            eprintln!(
+                "  \x1b[33mℹ\x1b[0m Loaded at runtime but not detected: {}",
+                missing.join(",")
+            );
 
-        let module_count = modules.split(',').count();
-        Pipeline::finish_step(&step, &format!("{} modules", module_count));
+            let merged: Vec<String> = modules
+                .split(',')
+                .map(str::to_string)
+                .chain(missing.into_iter().map(str::to_string))
+                .collect::<std::collections::BTreeSet<_>>()
+                .into_iter()
+                .collect();
+            // jbundle.toml is read from the project dir, or the working dir for JAR input
+            let config_dir = if is_jar_input {
+                std::env::current_dir()?
+            } else {
+                config.input.clone()
+            };
+            let written = project_config::set_modules(&config_dir, &merged)?;
+            eprintln!("  \x1b[33mℹ\x1b[0m Wrote modules to {}", written.display());
+            merged.join(",")
+        }
+    } else {
         modules
     };
 
@@ -498,8 +1115,8 @@ async fn run_build(config: BuildConfig) -> Result<()> {
         existing
     } else {
         let step = pipeline.start_step("Creating minimal runtime (jlink)");
-        let runtime = jlink::create_runtime(&jdk_path, &modules, temp_dir.path())?;
-        Pipeline::finish_step(&step, "done");
+        let (runtime, cached) = jlink::cached_runtime(&jdk_path, &modules, &config.jlink_options)?;
+        Pipeline::finish_step(&step, if cached { "cached" } else { "done" });
         runtime
     };
 
@@ -521,32 +1138,261 @@ async fn run_build(config: BuildConfig) -> Result<()> {
         None
     };
 
-    let compact_banner = config.compact_banner;
+    // Step: Split dependencies into their own layer (optional)
+    let thin_jar = if config.thin {
+        let step = pipeline.start_step("Splitting dependencies (thin)");
+        // Spring Boot JARs carry their dependencies; let Boot split itself
+        let result = if boot::is_boot_jar(&jar_path)? {
+            boot::jarmode_extract(&jdk_path, &jar_path, temp_dir.path())
+        } else {
+            // Not resolved up front for Bazel/Buck2; asking now reports why
+            let resolved = classpath.take().or_else(|| {
+                build_source.as_ref().map(|(root, system, subproject)| {
+                    thin::resolve_classpath(root, *system, subproject.as_deref())
+                })
+            });
+            match resolved {
+                Some(resolved) => {
+                    resolved.and_then(|deps| thin::split_uberjar(&jar_path, &deps, temp_dir.path()))
+                }
+                None => Err(PackError::ThinFailed(
+                    "requires a project directory as input".to_string(),
+                )),
+            }
+        };
+        match result {
+            Ok(thin_jar) => {
+                Pipeline::finish_step(
+                    &step,
+                    &format!(
+                        "{} dependency jars, app {}",
+                        thin_jar.deps.len(),
+                        HumanBytes(std::fs::metadata(&thin_jar.app_jar)?.len())
+                    ),
+                );
+                Some(thin_jar)
+            }
+            Err(e) => {
+                Pipeline::finish_step(&step, &format!("skipped ({})", e));
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Step: Generate SBOM (optional)
+    let sbom_json = if let Some(format) = config.sbom {
+        let step = pipeline.start_step(&format!("Generating SBOM ({})", format.name()));
+        let runtime_info = sbom::read_runtime_info(&runtime_path, &jdk_path);
+        let name = config
+            .output
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "app".to_string());
+        let input = sbom::SbomInput {
+            name: &name,
+            libraries: &sbom_libraries,
+            runtime: &runtime_info,
+        };
+        let json = sbom::generate(format, &input)?;
+        let sbom_path = config
+            .output
+            .with_file_name(format!("{name}.{}", format.extension()));
+        if let Some(parent) = sbom_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&sbom_path, &json)?;
+        Pipeline::finish_step(
+            &step,
+            &format!(
+                "{} components -> {}{}",
+                input.component_count(),
+                sbom_path.display(),
+                if config.embed_sbom { " (embedded)" } else { "" }
+            ),
+        );
+        Some(json)
+    } else {
+        None
+    };
+
+    // Load the signing key up front so a bad key fails before packing
+    let signing_key = config
+        .sign_key
+        .as_deref()
+        .map(sign::load_signing_key)
+        .transpose()?;
+    let signature_pubkey = match signing_key {
+        Some(ref key) if config.require_signature => {
+            Some(sign::public_key_pem(&key.verifying_key())?)
+        }
+        _ => None,
+    };
 
     // Step: Pack binary
     let step = pipeline.start_step("Packing binary");
-    pack::create_binary(&pack::PackOptions {
+    let packed = pack::create_binary(&pack::PackOptions {
         runtime_dir: &runtime_path,
-        jar_path: &jar_path,
+        jar_path: thin_jar.as_ref().map_or(&jar_path, |t| &t.app_jar),
         crac_path: crac_path.as_deref(),
+        resources: &config.resources,
+        deps: thin_jar.as_ref().map_or(&[], |t| &t.deps),
+        main_class: thin_jar.as_ref().map(|t| t.main_class.as_str()),
         output: &config.output,
         jvm_args: &config.jvm_args,
         profile: &config.profile,
         appcds: config.appcds,
         java_version,
-        compact_banner,
+        banner: &config.banner,
+        banner_first_run_only: config.banner_first_run_only,
+        modules: &modules,
+        flag_prefix: &config.flag_prefix,
+        target: &config.target.name(),
+        update_url: config.update_url.as_deref(),
+        signature_pubkey: signature_pubkey.as_deref(),
+        sbom: sbom_json.as_deref().filter(|_| config.embed_sbom),
+        env: &config.env,
+        workdir: config.workdir,
     })?;
+    let signed = match signing_key {
+        Some(ref key) => {
+            sign::sign_binary(&config.output, key)?;
+            format!(
+                ", signed with key {}",
+                sign::key_fingerprint(&key.verifying_key())
+            )
+        }
+        None => String::new(),
+    };
     let size = std::fs::metadata(&config.output)?.len();
     Pipeline::finish_step(
         &step,
-        &format!("{} ({})", config.output.display(), HumanBytes(size)),
+        &format!("{} ({}{signed})", config.output.display(), HumanBytes(size)),
     );
+    config.budget.check(size, &packed)?;
+
+    // Step: Smoke test the binary (optional)
+    if let Some(test) = smoke_test {
+        let step = pipeline.start_step("Smoke testing binary");
+        test.run(&config.output)?;
+        let expected = match test.expect_stdout {
+            Some(ref re) => format!("exit {}, stdout /{}/", test.expect_exit, re.as_str()),
+            None => format!("exit {}", test.expect_exit),
+        };
+        Pipeline::finish_step(&step, &format!("passed ({expected})"));
+    }
 
     pipeline.finish(&config.output.display().to_string());
 
     Ok(())
 }
 
+fn run_licenses(
+    input: &Path,
+    policy: &licenses::LicensePolicy,
+    notices: Option<&Path>,
+    runtime: Option<&Path>,
+) -> Result<()> {
+    let jar_path = analyze::resolve_input_jar(input)?;
+    let report = licenses::scan_jar(&jar_path)?;
+    let findings = licenses::evaluate(&report, policy);
+    licenses::render_report(&report, &findings);
+
+    if let Some(path) = notices {
+        let runtime_legal = match runtime {
+            Some(r) => licenses::read_runtime_legal(r)?,
+            None => Vec::new(),
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, licenses::render_notices(&report, &runtime_legal))?;
+        eprintln!("Notices written to {}", path.display());
+    }
+
+    let violations = findings.iter().filter(|(_, f)| f.is_violation()).count();
+    if violations > 0 {
+        return Err(PackError::LicenseViolation(violations).into());
+    }
+    Ok(())
+}
+
+fn run_audit(
+    input: &Path,
+    advisories: &Path,
+    jdk_advisories: Option<&Path>,
+    runtime: Option<&Path>,
+    fail_on: Option<audit::Severity>,
+) -> Result<()> {
+    // A jbundle binary is audited through its app layer
+    let temp_jar;
+    let jar_path = if input.is_file() && pack::layout::read_layout(input).is_ok() {
+        temp_jar = tempfile::Builder::new().suffix(".jar").tempfile()?;
+        std::fs::write(temp_jar.path(), pack::layout::read_app_jar(input)?)?;
+        temp_jar.path().to_path_buf()
+    } else {
+        analyze::resolve_input_jar(input)?
+    };
+    let coordinates = analyze::maven::read_coordinates(&jar_path)?;
+    let db = audit::AdvisoryDb::load(advisories)?;
+    let mut findings = db.check(&coordinates);
+
+    match (runtime, jdk_advisories) {
+        (Some(runtime), jdk_advisories) => {
+            let java_version = audit::runtime_java_version(runtime)?;
+            eprintln!("Runtime: Java {java_version} ({})", runtime.display());
+            match jdk_advisories {
+                Some(path) => {
+                    let advisories = audit::load_jdk_advisories(path)?;
+                    findings.extend(audit::check_jdk(&java_version, &advisories));
+                }
+                None => eprintln!(
+                    "  not checked: no JDK advisory list (--jdk-advisories or audit_jdk_advisories)"
+                ),
+            }
+        }
+        (None, Some(_)) => eprintln!(
+            "Runtime: unknown, JDK advisories not checked (pass --runtime, audit a binary, \
+             or build the project once so its JDK is downloaded)"
+        ),
+        (None, None) => {}
+    }
+    audit::render_report(&findings, coordinates.len(), db.len());
+
+    if let Some(threshold) = fail_on {
+        let count = findings.iter().filter(|v| v.severity >= threshold).count();
+        if count > 0 {
+            return Err(PackError::VulnerabilitiesFound {
+                count,
+                threshold: threshold.name().to_string(),
+            }
+            .into());
+        }
+    }
+    Ok(())
+}
+
+fn run_diff(old: &Path, new: &Path, output: &Path) -> Result<()> {
+    let summary = patch::create_patch(old, new, output)?;
+    for (name, source) in &summary.layers {
+        let how = match source {
+            patch::LayerSource::Base => "unchanged",
+            patch::LayerSource::Full => "included",
+            patch::LayerSource::Delta => "delta",
+        };
+        eprintln!("  {name}: {how}");
+    }
+    let pct = summary.patch_size as f64 / summary.new_size as f64 * 100.0;
+    eprintln!(
+        "Patch written to {} ({}, {:.0}% of the new binary)",
+        output.display(),
+        HumanBytes(summary.patch_size),
+        pct
+    );
+    Ok(())
+}
+
 fn run_clean() -> Result<()> {
     let cache_dir = BuildConfig::cache_dir()?;
     if cache_dir.exists() {
@@ -0,0 +1,240 @@
+//! Parser for Maven multi-module (reactor) builds.
+//!
+//! Walks `<modules>` from the root POM and finds the modules that produce a
+//! runnable fat JAR (shade, assembly or Spring Boot plugin) and their mainClass.
+
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+/// Plugins whose output is a self-contained JAR.
+const PACKAGING_PLUGINS: &[&str] = &[
+    "maven-shade-plugin",
+    "maven-assembly-plugin",
+    "spring-boot-maven-plugin",
+];
+
+/// A parsed Maven reactor build.
+#[derive(Debug, Clone)]
+pub struct MavenProject {
+    pub root: PathBuf,
+    pub modules: Vec<Module>,
+}
+
+/// A reactor module with its packaging configuration.
+#[derive(Debug, Clone)]
+pub struct Module {
+    /// Path relative to the root, as accepted by `mvn -pl` (e.g. `app` or `services/api`)
+    pub name: String,
+    /// Fat-JAR plugin configured in the module's `<build><plugins>`
+    pub packaging_plugin: Option<&'static str>,
+    pub main_class: Option<String>,
+}
+
+impl MavenProject {
+    /// Parse the reactor rooted at `root`.
+    /// Returns None if there is no pom.xml.
+    pub fn parse(root: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(root.join("pom.xml")).ok()?;
+        let mut modules = Vec::new();
+        collect_modules(root, "", &content, &mut modules, 0);
+        Some(MavenProject {
+            root: root.to_path_buf(),
+            modules,
+        })
+    }
+
+    /// Returns modules that build a runnable fat JAR.
+    pub fn application_modules(&self) -> Vec<&Module> {
+        self.modules
+            .iter()
+            .filter(|m| m.packaging_plugin.is_some())
+            .collect()
+    }
+
+    /// Check if this is a multi-module build.
+    pub fn is_multi_module(&self) -> bool {
+        !self.modules.is_empty()
+    }
+}
+
+/// Adds the modules declared by `pom` (and, recursively, by nested aggregators).
+fn collect_modules(root: &Path, prefix: &str, pom: &str, modules: &mut Vec<Module>, depth: usize) {
+    // Guards against cyclic <module>../</module> declarations
+    if depth > 8 {
+        return;
+    }
+    for name in parse_module_names(pom) {
+        let name = if prefix.is_empty() {
+            name
+        } else {
+            format!("{prefix}/{name}")
+        };
+        let Ok(content) = std::fs::read_to_string(root.join(&name).join("pom.xml")) else {
+            tracing::debug!("module {name} has no pom.xml, skipping");
+            continue;
+        };
+        let content = strip_comments(&content);
+        modules.push(Module {
+            name: name.clone(),
+            packaging_plugin: find_packaging_plugin(&content),
+            main_class: extract_main_class(&content),
+        });
+        collect_modules(root, &name, &content, modules, depth + 1);
+    }
+}
+
+fn strip_comments(xml: &str) -> String {
+    Regex::new(r"(?s)<!--.*?-->")
+        .unwrap()
+        .replace_all(xml, "")
+        .into_owned()
+}
+
+/// Extract `<module>` entries of the top-level `<modules>` block.
+/// Modules only declared inside `<profiles>` are not part of the default build.
+fn parse_module_names(pom: &str) -> Vec<String> {
+    let pom = strip_comments(pom);
+    let profiles = Regex::new(r"(?s)<profiles>.*?</profiles>").unwrap();
+    let pom = profiles.replace_all(&pom, "");
+
+    let block = Regex::new(r"(?s)<modules>(.*?)</modules>").unwrap();
+    let module = Regex::new(r"<module>\s*([^<]+?)\s*</module>").unwrap();
+    let Some(body) = block.captures(&pom) else {
+        return Vec::new();
+    };
+    module
+        .captures_iter(&body[1])
+        .map(|c| c[1].trim_end_matches('/').to_string())
+        .collect()
+}
+
+/// Packaging plugin applied by the module; `<pluginManagement>` only
+/// configures plugins, it doesn't run them.
+fn find_packaging_plugin(pom: &str) -> Option<&'static str> {
+    let management = Regex::new(r"(?s)<pluginManagement>.*?</pluginManagement>").unwrap();
+    let pom = management.replace_all(pom, "");
+    let plugins = Regex::new(r"(?s)<plugins>(.*?)</plugins>").unwrap();
+    let found = plugins.captures_iter(&pom).find_map(|c| {
+        PACKAGING_PLUGINS
+            .iter()
+            .find(|p| c[1].contains(&format!("<artifactId>{p}</artifactId>")))
+            .copied()
+    });
+    found
+}
+
+/// Extract the main class from plugin configuration or the usual properties.
+fn extract_main_class(pom: &str) -> Option<String> {
+    // Shade ManifestResourceTransformer, assembly <archive><manifest>, spring-boot <configuration>
+    let tags = ["mainClass", "start-class", "exec.mainClass"];
+    tags.iter().find_map(|tag| {
+        let re = Regex::new(&format!(r"<{tag}>\s*([\w.$]+)\s*</{tag}>")).unwrap();
+        re.captures(pom).map(|c| c[1].to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    const SHADE_POM: &str = r#"<project>
+  <artifactId>app</artifactId>
+  <build>
+    <plugins>
+      <plugin>
+        <groupId>org.apache.maven.plugins</groupId>
+        <artifactId>maven-shade-plugin</artifactId>
+        <configuration>
+          <transformers>
+            <transformer implementation="org.apache.maven.plugins.shade.resource.ManifestResourceTransformer">
+              <mainClass>com.example.app.Main</mainClass>
+            </transformer>
+          </transformers>
+        </configuration>
+      </plugin>
+    </plugins>
+  </build>
+</project>"#;
+
+    #[test]
+    fn parse_module_names_ignores_profiles_and_comments() {
+        let pom = r#"<project>
+  <modules>
+    <module>core</module>
+    <!-- <module>legacy</module> -->
+    <module> app/ </module>
+  </modules>
+  <profiles>
+    <profile><modules><module>bench</module></modules></profile>
+  </profiles>
+</project>"#;
+        assert_eq!(parse_module_names(pom), vec!["core", "app"]);
+    }
+
+    #[test]
+    fn find_packaging_plugin_skips_plugin_management() {
+        let managed = r#"<build>
+  <pluginManagement><plugins><plugin>
+    <artifactId>maven-shade-plugin</artifactId>
+  </plugin></plugins></pluginManagement>
+</build>"#;
+        assert_eq!(find_packaging_plugin(managed), None);
+        assert_eq!(find_packaging_plugin(SHADE_POM), Some("maven-shade-plugin"));
+    }
+
+    #[test]
+    fn extract_main_class_from_spring_boot_property() {
+        let pom = r#"<properties><start-class>com.example.Application</start-class></properties>
+<build><plugins><plugin><artifactId>spring-boot-maven-plugin</artifactId></plugin></plugins></build>"#;
+        assert_eq!(
+            extract_main_class(pom).as_deref(),
+            Some("com.example.Application")
+        );
+        assert_eq!(
+            extract_main_class(SHADE_POM).as_deref(),
+            Some("com.example.app.Main")
+        );
+    }
+
+    #[test]
+    fn parse_maven_project_multi_module() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("pom.xml"),
+            "<project><packaging>pom</packaging><modules><module>core</module><module>services</module></modules></project>",
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("core")).unwrap();
+        fs::write(root.join("core/pom.xml"), "<project/>").unwrap();
+        fs::create_dir_all(root.join("services/api")).unwrap();
+        fs::write(
+            root.join("services/pom.xml"),
+            "<project><modules><module>api</module></modules></project>",
+        )
+        .unwrap();
+        fs::write(root.join("services/api/pom.xml"), SHADE_POM).unwrap();
+
+        let project = MavenProject::parse(root).unwrap();
+        assert!(project.is_multi_module());
+        let names: Vec<_> = project.modules.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["core", "services", "services/api"]);
+
+        let apps = project.application_modules();
+        assert_eq!(apps.len(), 1);
+        assert_eq!(apps[0].name, "services/api");
+        assert_eq!(apps[0].packaging_plugin, Some("maven-shade-plugin"));
+        assert_eq!(apps[0].main_class.as_deref(), Some("com.example.app.Main"));
+    }
+
+    #[test]
+    fn parse_maven_project_single_module() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("pom.xml"), SHADE_POM).unwrap();
+        let project = MavenProject::parse(dir.path()).unwrap();
+        assert!(!project.is_multi_module());
+    }
+}
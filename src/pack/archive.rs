@@ -1,4 +1,5 @@
-use std::path::{Path, PathBuf};
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf};
 
 use flate2::write::GzEncoder;
 use flate2::Compression;
@@ -22,6 +23,106 @@ pub fn create_runtime_archive(runtime_dir: &Path, work_dir: &Path) -> Result<Pat
     Ok(archive_path)
 }
 
+/// Packs resource files and directories into a tar.gz layer. Each entry is
+/// stored under its own file name, so `config/` ends up as `config/...`.
+pub fn create_resources_archive(
+    resources: &[PathBuf],
+    work_dir: &Path,
+) -> Result<PathBuf, PackError> {
+    let archive_path = work_dir.join("resources.tar.gz");
+    let file = std::fs::File::create(&archive_path)?;
+    let encoder = GzEncoder::new(file, Compression::default());
+    let mut tar = tar::Builder::new(encoder);
+
+    tracing::info!("creating resources archive");
+
+    for (name, resource) in resource_names(resources)? {
+        if resource.is_dir() {
+            tar.append_dir_all(name, resource)?;
+        } else if resource.is_file() {
+            tar.append_path_with_name(resource, name)?;
+        } else {
+            return Err(PackError::ResourceNotFound(resource.clone()));
+        }
+    }
+
+    let encoder = tar.into_inner()?;
+    encoder.finish()?;
+
+    Ok(archive_path)
+}
+
+/// Name each resource is stored under in the resources layer. Paths without a
+/// name of their own (`.`, `..`, `config/..`) and two paths with the same
+/// name (`a/config`, `b/config`) are rejected, since they would land on top
+/// of each other.
+pub fn resource_names(resources: &[PathBuf]) -> Result<Vec<(&OsStr, &PathBuf)>, PackError> {
+    let mut names: Vec<(&OsStr, &PathBuf)> = Vec::new();
+    for resource in resources {
+        let unnamed = || PackError::UnnamedResource(resource.clone());
+        if matches!(
+            resource.components().next_back(),
+            None | Some(Component::CurDir | Component::ParentDir | Component::RootDir)
+        ) {
+            return Err(unnamed());
+        }
+        let name = resource.file_name().ok_or_else(unnamed)?;
+        if let Some((_, first)) = names.iter().find(|(n, _)| *n == name) {
+            return Err(PackError::DuplicateResourceName(
+                (*first).clone(),
+                resource.clone(),
+                name.to_string_lossy().to_string(),
+            ));
+        }
+        names.push((name, resource));
+    }
+    Ok(names)
+}
+
+/// Packs dependency JARs into a tar.gz layer under `lib/`. Entries are sorted
+/// and stripped of timestamps so the same dependency set always produces the
+/// same bytes, and therefore the same cache key.
+pub fn create_deps_archive(jars: &[PathBuf], work_dir: &Path) -> Result<PathBuf, PackError> {
+    let archive_path = work_dir.join("deps.tar.gz");
+    let file = std::fs::File::create(&archive_path)?;
+    let encoder = GzEncoder::new(file, Compression::default());
+    let mut tar = tar::Builder::new(encoder);
+
+    tracing::info!("creating dependency archive ({} jars)", jars.len());
+
+    let mut names: Vec<(String, &PathBuf)> = Vec::new();
+    for jar in jars {
+        let base = jar
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        // Different artifacts can share a file name (e.g. core-1.0.jar)
+        let mut name = base.clone();
+        let mut n = 1;
+        while names.iter().any(|(existing, _)| *existing == name) {
+            name = format!("{n}-{base}");
+            n += 1;
+        }
+        names.push((name, jar));
+    }
+    names.sort_by(|a, b| a.0.cmp(&b.0));
+
+    for (name, jar) in names {
+        let data = std::fs::read(jar)?;
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(0);
+        header.set_cksum();
+        tar.append_data(&mut header, format!("lib/{name}"), data.as_slice())?;
+    }
+
+    let encoder = tar.into_inner()?;
+    encoder.finish()?;
+
+    Ok(archive_path)
+}
+
 pub fn hash_file(path: &Path) -> Result<String, PackError> {
     use std::io::Read;
     let file = std::fs::File::open(path)?;
@@ -39,6 +140,16 @@ pub fn hash_file(path: &Path) -> Result<String, PackError> {
     Ok(hash[..16].to_string())
 }
 
+/// Same digest as [`hash_file`], for data already in memory.
+pub fn hash_bytes(data: &[u8]) -> String {
+    sha256_hex(data)[..16].to_string()
+}
+
+/// Full hex sha256, for when the 16-char cache key is too short (signatures).
+pub fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -55,6 +166,14 @@ mod tests {
         assert_eq!(h1, h2);
     }
 
+    #[test]
+    fn hash_bytes_matches_hash_file() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("test.bin");
+        std::fs::write(&file, b"hello world").unwrap();
+        assert_eq!(hash_bytes(b"hello world"), hash_file(&file).unwrap());
+    }
+
     #[test]
     fn hash_file_is_16_chars() {
         let dir = tempdir().unwrap();
@@ -115,4 +234,81 @@ mod tests {
 
         assert!(entries.iter().any(|e| e.contains("bin/java")));
     }
+
+    #[test]
+    fn create_resources_archive_keeps_top_level_names() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("config");
+        std::fs::create_dir_all(&config).unwrap();
+        std::fs::write(config.join("app.conf"), b"port = 8080").unwrap();
+        let readme = dir.path().join("README.txt");
+        std::fs::write(&readme, b"hello").unwrap();
+
+        let work = tempdir().unwrap();
+        let archive = create_resources_archive(&[config, readme], work.path()).unwrap();
+
+        let file = std::fs::File::open(&archive).unwrap();
+        let decoder = flate2::read::GzDecoder::new(file);
+        let mut tar_archive = tar::Archive::new(decoder);
+        let entries: Vec<String> = tar_archive
+            .entries()
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .map(|e| e.path().unwrap().to_string_lossy().to_string())
+            .collect();
+
+        assert!(entries.contains(&"config/app.conf".to_string()));
+        assert!(entries.contains(&"README.txt".to_string()));
+    }
+
+    #[test]
+    fn resource_names_reject_collisions_and_unnamed_paths() {
+        let dir = tempdir().unwrap();
+        let a = dir.path().join("a").join("config");
+        let b = dir.path().join("b").join("config");
+        std::fs::create_dir_all(&a).unwrap();
+        std::fs::create_dir_all(&b).unwrap();
+
+        let err = create_resources_archive(&[a.clone(), b.clone()], dir.path()).unwrap_err();
+        match err {
+            PackError::DuplicateResourceName(first, second, name) => {
+                assert_eq!((first, second), (a.clone(), b));
+                assert_eq!(name, "config");
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+
+        for unnamed in [".", "..", "config/..", "/"] {
+            let err = resource_names(&[PathBuf::from(unnamed)]).unwrap_err();
+            assert!(
+                matches!(err, PackError::UnnamedResource(_)),
+                "{unnamed}: {err}"
+            );
+        }
+        assert_eq!(resource_names(&[a]).unwrap()[0].0, "config");
+    }
+
+    #[test]
+    fn create_deps_archive_is_deterministic() {
+        let dir = tempdir().unwrap();
+        let a = dir.path().join("a.jar");
+        let b = dir.path().join("b.jar");
+        std::fs::write(&a, b"jar a").unwrap();
+        std::fs::write(&b, b"jar b").unwrap();
+
+        let work1 = tempdir().unwrap();
+        let work2 = tempdir().unwrap();
+        let first = create_deps_archive(&[a.clone(), b.clone()], work1.path()).unwrap();
+        let second = create_deps_archive(&[b, a], work2.path()).unwrap();
+
+        assert_eq!(hash_file(&first).unwrap(), hash_file(&second).unwrap());
+    }
+
+    #[test]
+    fn create_resources_archive_missing_path_errors() {
+        let dir = tempdir().unwrap();
+        let work = tempdir().unwrap();
+        let result = create_resources_archive(&[dir.path().join("missing")], work.path());
+        assert!(matches!(result, Err(PackError::ResourceNotFound(_))));
+    }
 }
@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::error::PackError;
+
+/// Payload layers in the order they follow the stub: (name, stub variable prefix).
+pub const LAYERS: &[(&str, &str)] = &[
+    ("runtime", "RT"),
+    ("app", "APP"),
+    ("crac", "CRAC"),
+    ("resources", "RES"),
+    ("deps", "DEPS"),
+    ("sbom", "SBOM"),
+];
+
+/// `STUB_SIZE` is defined right after the layer variables, well within this.
+const HEADER_PROBE: usize = 8192;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layer {
+    pub name: &'static str,
+    pub hash: String,
+    /// Absolute offset of the layer in the binary
+    pub offset: u64,
+    pub size: u64,
+}
+
+/// Where the stub and each payload layer live inside a built binary.
+#[derive(Debug)]
+pub struct BinaryLayout {
+    pub stub_size: u64,
+    /// Present layers only (size > 0), in payload order
+    pub layers: Vec<Layer>,
+    /// End of the last layer; anything after it is a trailer (e.g. a signature)
+    pub payload_end: u64,
+}
+
+impl BinaryLayout {
+    pub fn layer(&self, name: &str) -> Option<&Layer> {
+        self.layers.iter().find(|l| l.name == name)
+    }
+}
+
+/// Reads the stub variables of a jbundle binary to locate its layers.
+pub fn read_layout(path: &Path) -> Result<BinaryLayout, PackError> {
+    let mut file = std::fs::File::open(path)?;
+    let mut head = Vec::with_capacity(HEADER_PROBE);
+    file.by_ref()
+        .take(HEADER_PROBE as u64)
+        .read_to_end(&mut head)?;
+    let head = String::from_utf8_lossy(&head);
+
+    let vars = parse_stub_vars(&head);
+    let stub_size = vars
+        .get("STUB_SIZE")
+        .and_then(|v| v.parse::<u64>().ok())
+        .ok_or_else(|| PackError::InvalidBinary(path.display().to_string()))?;
+
+    let mut offset = stub_size;
+    let mut layers = Vec::new();
+    for (name, prefix) in LAYERS {
+        let size = vars
+            .get(&format!("{prefix}_SIZE"))
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(0);
+        if size > 0 {
+            layers.push(Layer {
+                name,
+                hash: vars
+                    .get(&format!("{prefix}_HASH"))
+                    .cloned()
+                    .unwrap_or_default(),
+                offset,
+                size,
+            });
+        }
+        offset += size;
+    }
+
+    let file_size = file.seek(SeekFrom::End(0))?;
+    if offset > file_size {
+        return Err(PackError::InvalidBinary(format!(
+            "{} is truncated ({} bytes, layers end at {})",
+            path.display(),
+            file_size,
+            offset
+        )));
+    }
+
+    Ok(BinaryLayout {
+        stub_size,
+        layers,
+        payload_end: offset,
+    })
+}
+
+/// Reads `len` bytes at `offset` of a binary.
+pub fn read_range(path: &Path, offset: u64, len: u64) -> Result<Vec<u8>, PackError> {
+    let mut file = std::fs::File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut buf = Vec::with_capacity(len as usize);
+    file.take(len).read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+/// The uncompressed `app.jar` of a jbundle binary.
+pub fn read_app_jar(path: &Path) -> Result<Vec<u8>, PackError> {
+    let layout = read_layout(path)?;
+    let app = layout
+        .layer("app")
+        .ok_or_else(|| PackError::InvalidBinary(format!("{} has no app layer", path.display())))?;
+    let data = read_range(path, app.offset, app.size)?;
+    let mut jar = Vec::new();
+    flate2::read::GzDecoder::new(&data[..]).read_to_end(&mut jar)?;
+    Ok(jar)
+}
+
+/// Reads the file or directory `name` of a Java runtime, given either a
+/// runtime directory or a jbundle binary (from its runtime layer). Returns
+/// `(relative path, contents)` pairs sorted by path.
+pub fn read_runtime_files(path: &Path, name: &str) -> Result<Vec<(String, Vec<u8>)>, PackError> {
+    let mut files = Vec::new();
+    if path.is_dir() {
+        collect_files(&path.join(name), name, &mut files)?;
+    } else {
+        let layout = read_layout(path)?;
+        let runtime = layout.layer("runtime").ok_or_else(|| {
+            PackError::InvalidBinary(format!("{} has no runtime layer", path.display()))
+        })?;
+        let data = read_range(path, runtime.offset, runtime.size)?;
+        let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(&data[..]));
+        for entry in tar.entries()? {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let entry_name = entry.path()?.to_string_lossy().to_string();
+            let entry_name = entry_name.trim_start_matches("./");
+            if entry_name == name || entry_name.starts_with(&format!("{name}/")) {
+                let entry_name = entry_name.to_string();
+                let mut bytes = Vec::new();
+                entry.read_to_end(&mut bytes)?;
+                files.push((entry_name, bytes));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn collect_files(
+    path: &Path,
+    name: &str,
+    out: &mut Vec<(String, Vec<u8>)>,
+) -> Result<(), PackError> {
+    if path.is_dir() {
+        for entry in std::fs::read_dir(path)? {
+            let entry = entry?;
+            let child = format!("{name}/{}", entry.file_name().to_string_lossy());
+            collect_files(&entry.path(), &child, out)?;
+        }
+    } else if path.is_file() {
+        out.push((name.to_string(), std::fs::read(path)?));
+    }
+    Ok(())
+}
+
+/// Collects `NAME=value` / `NAME="value"` assignments from the stub header.
+fn parse_stub_vars(stub: &str) -> HashMap<String, String> {
+    let re = regex::Regex::new(r#"\b([A-Z_]+_(?:HASH|SIZE))="?([^"\s]*)"?"#).unwrap();
+    let mut vars = HashMap::new();
+    for line in stub.lines() {
+        // Only top-level assignments; later references are inside commands
+        if line.starts_with(' ') || line.starts_with('#') {
+            continue;
+        }
+        for cap in re.captures_iter(line) {
+            vars.entry(cap[1].to_string())
+                .or_insert_with(|| cap[2].to_string());
+        }
+    }
+    vars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn parse_stub_vars_reads_layer_definitions() {
+        let stub = "#!/bin/sh\nRT_HASH=\"abc\"    RT_SIZE=10\nAPP_HASH=\"def\"   APP_SIZE=5\n\
+                    CRAC_SIZE=0       CRAC_HASH=\"\"\nSTUB_SIZE=123\n";
+        let vars = parse_stub_vars(stub);
+        assert_eq!(vars["RT_HASH"], "abc");
+        assert_eq!(vars["RT_SIZE"], "10");
+        assert_eq!(vars["APP_SIZE"], "5");
+        assert_eq!(vars["CRAC_HASH"], "");
+        assert_eq!(vars["STUB_SIZE"], "123");
+    }
+
+    #[test]
+    fn read_layout_locates_layers() {
+        let dir = tempdir().unwrap();
+        let stub = "#!/bin/sh\nRT_HASH=\"aaa\"    RT_SIZE=3\nAPP_HASH=\"bbb\"   APP_SIZE=2\n\
+                    CRAC_SIZE=0       CRAC_HASH=\"\"\nSTUB_SIZE=";
+        let stub_size = stub.len() + 3 + 1;
+        let content = format!("{stub}{stub_size}\nRTTAP");
+        let binary = dir.path().join("app");
+        std::fs::write(&binary, &content).unwrap();
+
+        let layout = read_layout(&binary).unwrap();
+        assert_eq!(layout.stub_size, stub_size as u64);
+        assert_eq!(layout.layers.len(), 2);
+        let app = layout.layer("app").unwrap();
+        assert_eq!(app.hash, "bbb");
+        assert_eq!(app.offset, stub_size as u64 + 3);
+        assert_eq!(
+            read_range(&binary, app.offset, app.size).unwrap(),
+            b"AP".to_vec()
+        );
+    }
+
+    #[test]
+    fn read_layout_of_built_binary() {
+        use crate::config::{Banner, JvmProfile, WorkDir};
+
+        let dir = tempdir().unwrap();
+        let runtime = dir.path().join("runtime");
+        std::fs::create_dir_all(runtime.join("bin")).unwrap();
+        std::fs::write(runtime.join("bin").join("java"), b"#!/bin/sh\n").unwrap();
+        let jar = dir.path().join("app.jar");
+        std::fs::write(&jar, b"fake jar").unwrap();
+        let output = dir.path().join("app");
+
+        crate::pack::create_binary(&crate::pack::PackOptions {
+            runtime_dir: &runtime,
+            jar_path: &jar,
+            crac_path: None,
+            resources: &[],
+            deps: &[],
+            main_class: None,
+            output: &output,
+            jvm_args: &[],
+            profile: &JvmProfile::Server,
+            appcds: false,
+            java_version: 21,
+            banner: &Banner::Full,
+            banner_first_run_only: false,
+            modules: "java.base",
+            flag_prefix: "jbundle",
+            target: "linux-x64",
+            update_url: None,
+            signature_pubkey: None,
+            sbom: None,
+            env: &Default::default(),
+            workdir: WorkDir::Cwd,
+        })
+        .unwrap();
+
+        let layout = read_layout(&output).unwrap();
+        let names: Vec<&str> = layout.layers.iter().map(|l| l.name).collect();
+        assert_eq!(names, vec!["runtime", "app"]);
+        let app = layout.layer("app").unwrap();
+        assert_eq!(app.hash, crate::pack::archive::hash_file(&jar).unwrap());
+        let file_size = std::fs::metadata(&output).unwrap().len();
+        assert_eq!(app.offset + app.size, file_size);
+    }
+
+    #[test]
+    fn read_layout_rejects_non_jbundle_files() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("plain");
+        std::fs::write(&file, b"just some bytes").unwrap();
+        assert!(matches!(
+            read_layout(&file),
+            Err(PackError::InvalidBinary(_))
+        ));
+    }
+}
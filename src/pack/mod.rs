@@ -1,28 +1,60 @@
 pub mod archive;
+pub mod layout;
 pub mod stub;
 
+use std::collections::BTreeMap;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use flate2::write::GzEncoder;
 use flate2::Compression;
 
-use crate::config::JvmProfile;
+use crate::config::{Banner, JvmProfile, WorkDir};
 use crate::error::PackError;
 
 pub struct PackOptions<'a> {
     pub runtime_dir: &'a Path,
     pub jar_path: &'a Path,
     pub crac_path: Option<&'a Path>,
+    /// Files and directories packed into the resources layer
+    pub resources: &'a [PathBuf],
+    /// Dependency JARs packed into the deps layer (thin mode)
+    pub deps: &'a [PathBuf],
+    /// Main class used with `-cp` when the app is split from its deps
+    pub main_class: Option<&'a str>,
     pub output: &'a Path,
     pub jvm_args: &'a [String],
     pub profile: &'a JvmProfile,
     pub appcds: bool,
     pub java_version: u8,
-    pub compact_banner: bool,
+    pub banner: &'a Banner,
+    pub banner_first_run_only: bool,
+    pub modules: &'a str,
+    pub flag_prefix: &'a str,
+    /// Target platform name (e.g. `linux-x64`)
+    pub target: &'a str,
+    /// Update manifest URL enabling the launcher's `--self-update`
+    pub update_url: Option<&'a str>,
+    /// Public key (PEM) the launcher checks the payload signature against
+    pub signature_pubkey: Option<&'a str>,
+    /// SBOM document embedded as the last layer
+    pub sbom: Option<&'a str>,
+    pub env: &'a BTreeMap<String, String>,
+    pub workdir: WorkDir,
 }
 
-pub fn create_binary(opts: &PackOptions) -> Result<(), PackError> {
+/// Compressed size of each payload layer of a built binary (0 when absent).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PackedSizes {
+    pub runtime: u64,
+    pub app: u64,
+    pub crac: u64,
+    pub resources: u64,
+    pub deps: u64,
+    pub sbom: u64,
+}
+
+pub fn create_binary(opts: &PackOptions) -> Result<PackedSizes, PackError> {
     // Validate output path is not a directory
     if opts.output.is_dir() {
         return Err(PackError::BuildFailed(format!(
@@ -53,6 +85,50 @@ pub fn create_binary(opts: &PackOptions) -> Result<(), PackError> {
         (0, None)
     };
 
+    // Resources (tar.gz, optional)
+    let resources_archive = if opts.resources.is_empty() {
+        None
+    } else {
+        Some(archive::create_resources_archive(
+            opts.resources,
+            temp.path(),
+        )?)
+    };
+    let (res_size, res_hash) = if let Some(ref ra) = resources_archive {
+        (std::fs::metadata(ra)?.len(), Some(archive::hash_file(ra)?))
+    } else {
+        (0, None)
+    };
+
+    // Dependencies (tar.gz, thin mode only)
+    let deps_archive = if opts.deps.is_empty() {
+        None
+    } else {
+        Some(archive::create_deps_archive(opts.deps, temp.path())?)
+    };
+    let (deps_size, deps_hash) = if let Some(ref da) = deps_archive {
+        (std::fs::metadata(da)?.len(), Some(archive::hash_file(da)?))
+    } else {
+        (0, None)
+    };
+
+    // SBOM (gzipped JSON, optional)
+    let sbom_gz = match opts.sbom {
+        Some(sbom) => {
+            let path = temp.path().join("sbom.json.gz");
+            let mut encoder = GzEncoder::new(std::fs::File::create(&path)?, Compression::best());
+            encoder.write_all(sbom.as_bytes())?;
+            encoder.finish()?;
+            Some(path)
+        }
+        None => None,
+    };
+    let (sbom_size, sbom_hash) = if let Some(ref sg) = sbom_gz {
+        (std::fs::metadata(sg)?.len(), Some(archive::hash_file(sg)?))
+    } else {
+        (0, None)
+    };
+
     // Generate stub
     let stub_script = stub::generate(&stub::StubParams {
         runtime_hash: &runtime_hash,
@@ -61,11 +137,26 @@ pub fn create_binary(opts: &PackOptions) -> Result<(), PackError> {
         app_size,
         crac_hash: crac_hash.as_deref(),
         crac_size,
+        res_hash: res_hash.as_deref(),
+        res_size,
+        deps_hash: deps_hash.as_deref(),
+        deps_size,
+        sbom_hash: sbom_hash.as_deref(),
+        sbom_size,
+        main_class: opts.main_class,
         profile: opts.profile,
         jvm_args: opts.jvm_args,
         appcds: opts.appcds,
         java_version: opts.java_version,
-        compact_banner: opts.compact_banner,
+        banner: opts.banner,
+        banner_first_run_only: opts.banner_first_run_only,
+        modules: opts.modules,
+        flag_prefix: opts.flag_prefix,
+        target: opts.target,
+        update_url: opts.update_url,
+        signature_pubkey: opts.signature_pubkey,
+        env: opts.env,
+        workdir: opts.workdir,
     });
     let stub_script = stub::finalize_stub(&stub_script);
 
@@ -92,6 +183,24 @@ pub fn create_binary(opts: &PackOptions) -> Result<(), PackError> {
         std::io::copy(&mut crac_file, &mut out_file)?;
     }
 
+    // Write resources tar.gz (streaming, if present)
+    if let Some(ref ra) = resources_archive {
+        let mut res_file = std::fs::File::open(ra)?;
+        std::io::copy(&mut res_file, &mut out_file)?;
+    }
+
+    // Write deps tar.gz (streaming, if present)
+    if let Some(ref da) = deps_archive {
+        let mut deps_file = std::fs::File::open(da)?;
+        std::io::copy(&mut deps_file, &mut out_file)?;
+    }
+
+    // Write SBOM (if embedded)
+    if let Some(ref sg) = sbom_gz {
+        let mut sbom_file = std::fs::File::open(sg)?;
+        std::io::copy(&mut sbom_file, &mut out_file)?;
+    }
+
     drop(out_file);
 
     #[cfg(unix)]
@@ -101,14 +210,27 @@ pub fn create_binary(opts: &PackOptions) -> Result<(), PackError> {
     }
 
     tracing::info!("binary created at {}", opts.output.display());
-    Ok(())
+    Ok(PackedSizes {
+        runtime: runtime_size,
+        app: app_size,
+        crac: crac_size,
+        resources: res_size,
+        deps: deps_size,
+        sbom: sbom_size,
+    })
 }
 
 fn compress_file(input: &Path, output: &Path) -> Result<(), PackError> {
     let mut src = std::fs::File::open(input)?;
     let dst = std::fs::File::create(output)?;
-    let mut encoder = GzEncoder::new(dst, Compression::default());
-    std::io::copy(&mut src, &mut encoder)?;
-    encoder.finish()?;
+    gzip_stream(&mut src, dst)?;
     Ok(())
 }
+
+/// Gzips `input` into `output`. `jbundle patch` rebuilds the app layer with
+/// this too, so the layer comes out byte for byte as the packer wrote it.
+pub fn gzip_stream<R: std::io::Read, W: Write>(input: &mut R, output: W) -> Result<W, PackError> {
+    let mut encoder = GzEncoder::new(output, Compression::default());
+    std::io::copy(input, &mut encoder)?;
+    Ok(encoder.finish()?)
+}
@@ -1,4 +1,6 @@
-use crate::config::JvmProfile;
+use std::collections::BTreeMap;
+
+use crate::config::{Banner, JvmProfile, WorkDir};
 
 pub struct StubParams<'a> {
     pub runtime_hash: &'a str,
@@ -7,11 +9,83 @@ pub struct StubParams<'a> {
     pub app_size: u64,
     pub crac_hash: Option<&'a str>,
     pub crac_size: u64,
+    pub res_hash: Option<&'a str>,
+    pub res_size: u64,
+    pub deps_hash: Option<&'a str>,
+    pub deps_size: u64,
+    /// Embedded SBOM (gzipped JSON), printed by `--<prefix>-sbom`
+    pub sbom_hash: Option<&'a str>,
+    pub sbom_size: u64,
+    /// Launch with `-cp` and this main class instead of `-jar` (thin mode)
+    pub main_class: Option<&'a str>,
     pub profile: &'a JvmProfile,
     pub jvm_args: &'a [String],
     pub appcds: bool,
     pub java_version: u8,
-    pub compact_banner: bool,
+    pub banner: &'a Banner,
+    pub banner_first_run_only: bool,
+    /// Comma-separated jlink modules, reported by the info flag
+    pub modules: &'a str,
+    /// Prefix for the launcher's reserved flags (`--<prefix>-info`, ...)
+    pub flag_prefix: &'a str,
+    /// Target platform name (e.g. `linux-x64`), used to pick self-updates
+    pub target: &'a str,
+    /// Update manifest URL enabling `--self-update`
+    pub update_url: Option<&'a str>,
+    /// Ed25519 public key (PEM); when set the launcher refuses to extract
+    /// a payload whose signature doesn't verify
+    pub signature_pubkey: Option<&'a str>,
+    /// Environment variables exported before starting the JVM
+    pub env: &'a BTreeMap<String, String>,
+    pub workdir: WorkDir,
+}
+
+/// Launcher variables that may appear as `${NAME}` placeholders in build-time
+/// values (e.g. jvm_args). They are expanded by the stub at runtime.
+pub const RUNTIME_VARS: &[&str] = &["APP_DIR", "BINARY_DIR", "DEPS_DIR", "RES_DIR", "RT_DIR"];
+
+/// Quotes a string for POSIX sh. Strings made only of safe characters are
+/// left as-is to keep the generated stub readable.
+pub fn shell_quote(s: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_=.,/:+@%".contains(c);
+    if !s.is_empty() && s.chars().all(is_safe) {
+        return s.to_string();
+    }
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Renders a build-time value as a single shell word. Literal text is quoted,
+/// while `${NAME}` placeholders for [`RUNTIME_VARS`] become double-quoted
+/// variable references expanded by the launcher.
+pub fn shell_word(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+
+    while !rest.is_empty() {
+        let next = RUNTIME_VARS
+            .iter()
+            .filter_map(|var| rest.find(&format!("${{{var}}}")).map(|pos| (pos, *var)))
+            .min_by_key(|(pos, _)| *pos);
+
+        match next {
+            Some((pos, var)) => {
+                if pos > 0 {
+                    out.push_str(&shell_quote(&rest[..pos]));
+                }
+                out.push_str(&format!("\"${var}\""));
+                rest = &rest[pos + var.len() + 3..];
+            }
+            None => {
+                out.push_str(&shell_quote(rest));
+                rest = "";
+            }
+        }
+    }
+
+    if out.is_empty() {
+        out.push_str("''");
+    }
+    out
 }
 
 pub fn generate(params: &StubParams) -> String {
@@ -19,7 +93,8 @@ pub fn generate(params: &StubParams) -> String {
     let jvm_args_str = if params.jvm_args.is_empty() {
         String::new()
     } else {
-        format!(" {}", params.jvm_args.join(" "))
+        let words: Vec<String> = params.jvm_args.iter().map(|a| shell_word(a)).collect();
+        format!(" {}", words.join(" "))
     };
 
     let profile_and_args = if profile_flags.is_empty() {
@@ -31,24 +106,50 @@ pub fn generate(params: &StubParams) -> String {
     };
 
     let crac_hash_val = params.crac_hash.unwrap_or("");
+    let res_hash_val = params.res_hash.unwrap_or("");
+    let deps_hash_val = params.deps_hash.unwrap_or("");
+    let sbom_hash_val = params.sbom_hash.unwrap_or("");
 
     let runtime_hash = params.runtime_hash;
     let runtime_size = params.runtime_size;
     let app_hash = params.app_hash;
     let app_size = params.app_size;
     let crac_size = params.crac_size;
-    let jbundle_banner = if params.compact_banner {
-        r#"echo "binary created with jbundle.avelino.run" >&2"#
+    let res_size = params.res_size;
+    let deps_size = params.deps_size;
+    let sbom_size = params.sbom_size;
+    let res_flag = if params.res_hash.is_some() {
+        r#" "-Djbundle.resources=$RES_DIR""#
     } else {
-        r#"cat >&2 <<'BANNER'
-   _ _                    _ _
-  (_) |__  _   _ _ __   __| | | ___
-  | | '_ \| | | | '_ \ / _` | |/ _ \
-  | | |_) | |_| | | | | (_| | |  __/
- _/ |_.__/ \__,_|_| |_|\__,_|_|\___|
-|__/
-BANNER"#
+        ""
     };
+    let launch_target = match params.main_class {
+        Some(main_class) => format!(
+            r#"-cp "$APP_DIR/app.jar:$DEPS_DIR/lib/*" {}"#,
+            shell_quote(main_class)
+        ),
+        None => r#"-jar "$APP_DIR/app.jar""#.to_string(),
+    };
+    let jbundle_banner = banner_script(params.banner, params.banner_first_run_only);
+
+    let launcher_flags = launcher_flags(params);
+    let signature_check = if params.signature_pubkey.is_some() {
+        r#"
+# Refuse to extract a payload that isn't signed by the baked-in key
+NEEDS_EXTRACT=0
+[ -d "$RT_DIR/bin" ] || NEEDS_EXTRACT=1
+[ -f "$APP_DIR/app.jar" ] || NEEDS_EXTRACT=1
+if [ "$CRAC_SIZE" -gt 0 ] && [ "$(uname)" = "Linux" ] && [ ! -d "$CRAC_DIR/cr" ]; then NEEDS_EXTRACT=1; fi
+if [ "$RES_SIZE" -gt 0 ] && [ ! -f "$RES_DIR/.jbundle-extracted" ]; then NEEDS_EXTRACT=1; fi
+if [ "$DEPS_SIZE" -gt 0 ] && [ ! -f "$DEPS_DIR/.jbundle-extracted" ]; then NEEDS_EXTRACT=1; fi
+if [ "$NEEDS_EXTRACT" = "1" ] && ! jbundle_verify_signature; then
+    echo "error: signature verification failed, refusing to run" >&2
+    exit 1
+fi"#
+    } else {
+        ""
+    };
+    let environment = environment_script(params.env, params.workdir);
 
     // AppCDS via AutoCreateSharedArchive (JDK 19+)
     let cds_flags = if params.appcds && params.java_version >= 19 {
@@ -64,16 +165,24 @@ CDS_FLAG="-XX:+AutoCreateSharedArchive -XX:SharedArchiveFile=$CDS_FILE""#
         r#"#!/bin/sh
 set -e
 CACHE="${{HOME}}/.jbundle/cache"
+BINARY_DIR="$(cd "$(dirname "$0")" && pwd)"
 RT_HASH="{runtime_hash}"    RT_SIZE={runtime_size}
 APP_HASH="{app_hash}"   APP_SIZE={app_size}
 CRAC_SIZE={crac_size}       CRAC_HASH="{crac_hash_val}"
-
-{jbundle_banner}
+RES_SIZE={res_size}        RES_HASH="{res_hash_val}"
+DEPS_SIZE={deps_size}       DEPS_HASH="{deps_hash_val}"
+SBOM_SIZE={sbom_size}       SBOM_HASH="{sbom_hash_val}"
+RT_DIR="$CACHE/rt-$RT_HASH"
+APP_DIR="$CACHE/app-$APP_HASH"
+CRAC_DIR="$CACHE/crac-$CRAC_HASH"
+RES_DIR="$CACHE/res-$RES_HASH"
+DEPS_DIR="$CACHE/deps-$DEPS_HASH"
 
 STUB_SIZE=__STUB_SIZE__
+{launcher_flags}{signature_check}
+{jbundle_banner}
 
 # Extract runtime (only if not cached)
-RT_DIR="$CACHE/rt-$RT_HASH"
 if [ ! -d "$RT_DIR/bin" ]; then
     mkdir -p "$RT_DIR"
     echo "Extracting runtime (first run)..." >&2
@@ -81,32 +190,394 @@ if [ ! -d "$RT_DIR/bin" ]; then
 fi
 
 # Extract app.jar (decompress gzip, only if not cached)
-APP_DIR="$CACHE/app-$APP_HASH"
 if [ ! -f "$APP_DIR/app.jar" ]; then
     mkdir -p "$APP_DIR"
     tail -c +$((STUB_SIZE + RT_SIZE + 1)) "$0" | head -c "$APP_SIZE" | gzip -d > "$APP_DIR/app.jar"
 fi
 {cds_flags}
 
+# Extract CRaC checkpoint (Linux only)
+if [ "$CRAC_SIZE" -gt 0 ] 2>/dev/null && [ "$(uname)" = "Linux" ] && [ ! -d "$CRAC_DIR/cr" ]; then
+    mkdir -p "$CRAC_DIR"
+    tail -c +$((STUB_SIZE + RT_SIZE + APP_SIZE + 1)) "$0" | head -c "$CRAC_SIZE" | tar xzf - -C "$CRAC_DIR"
+fi
+
+# Extract resources (only if not cached)
+if [ "$RES_SIZE" -gt 0 ]; then
+    if [ ! -f "$RES_DIR/.jbundle-extracted" ]; then
+        mkdir -p "$RES_DIR"
+        tail -c +$((STUB_SIZE + RT_SIZE + APP_SIZE + CRAC_SIZE + 1)) "$0" | head -c "$RES_SIZE" | tar xzf - -C "$RES_DIR"
+        touch "$RES_DIR/.jbundle-extracted"
+    fi
+    export JBUNDLE_RESOURCES="$RES_DIR"
+fi
+
+# Extract dependency jars (thin mode, only if not cached)
+if [ "$DEPS_SIZE" -gt 0 ] && [ ! -f "$DEPS_DIR/.jbundle-extracted" ]; then
+    mkdir -p "$DEPS_DIR"
+    tail -c +$((STUB_SIZE + RT_SIZE + APP_SIZE + CRAC_SIZE + RES_SIZE + 1)) "$0" | head -c "$DEPS_SIZE" | tar xzf - -C "$DEPS_DIR"
+    touch "$DEPS_DIR/.jbundle-extracted"
+fi
+
+if [ "$EXTRACT_ONLY" = "1" ]; then
+    echo "Layers extracted to $CACHE" >&2
+    exit 0
+fi
+{environment}
 # CRaC restore (Linux only)
 if [ "$CRAC_SIZE" -gt 0 ] 2>/dev/null && [ "$(uname)" = "Linux" ]; then
-    CRAC_DIR="$CACHE/crac-$CRAC_HASH"
-    if [ ! -d "$CRAC_DIR/cr" ]; then
-        mkdir -p "$CRAC_DIR"
-        tail -c +$((STUB_SIZE + RT_SIZE + APP_SIZE + 1)) "$0" | head -c "$CRAC_SIZE" | tar xzf - -C "$CRAC_DIR"
-    fi
     "$RT_DIR/bin/java" -XX:CRaCRestoreFrom="$CRAC_DIR/cr" "$@" && exit 0
     echo "warn: CRaC restore failed, falling back to standard startup" >&2
 fi
 
 # Launch with profile flags + AppCDS + user args
-exec "$RT_DIR/bin/java"{profile_and_args} $CDS_FLAG -jar "$APP_DIR/app.jar" "$@"
+exec "$RT_DIR/bin/java"{profile_and_args}{res_flag} $CDS_FLAG {launch_target} "$@"
 exit 0
 # --- PAYLOAD BELOW ---
 "#
     )
 }
 
+/// Generates the exports and `cd` applied right before launching the JVM.
+/// Values may reference the [`RUNTIME_VARS`] placeholders.
+fn environment_script(env: &BTreeMap<String, String>, workdir: WorkDir) -> String {
+    if env.is_empty() && workdir == WorkDir::Cwd {
+        return String::new();
+    }
+
+    let mut script = String::from("\n# Environment and working directory\n");
+    for (name, value) in env {
+        script.push_str(&format!("export {name}={}\n", shell_word(value)));
+    }
+    if workdir == WorkDir::Binary {
+        script.push_str("cd \"$BINARY_DIR\"\n");
+    }
+    script
+}
+
+/// Generates the shell snippet printing the banner to stderr. With
+/// `first_run_only`, it is only shown while the app layer is not yet cached.
+fn banner_script(banner: &Banner, first_run_only: bool) -> String {
+    let script = match banner {
+        Banner::Full => r#"cat >&2 <<'BANNER'
+   _ _                    _ _
+  (_) |__  _   _ _ __   __| | | ___
+  | | '_ \| | | | '_ \ / _` | |/ _ \
+  | | |_) | |_| | | | | (_| | |  __/
+ _/ |_.__/ \__,_|_| |_|\__,_|_|\___|
+|__/
+BANNER"#
+            .to_string(),
+        Banner::Compact => r#"echo "binary created with jbundle.avelino.run" >&2"#.to_string(),
+        Banner::Custom(text) => {
+            format!("printf '%s\\n' {} >&2", shell_quote(text.trim_end()))
+        }
+        Banner::None => return String::new(),
+    };
+
+    if first_run_only {
+        format!("if [ ! -f \"$APP_DIR/app.jar\" ]; then\n{script}\nfi")
+    } else {
+        script
+    }
+}
+
+/// Generates the `case` block handling the launcher's reserved flags.
+/// These are only recognized as the first argument, so apps keep full
+/// control over everything else.
+fn launcher_flags(params: &StubParams) -> String {
+    let prefix = params.flag_prefix;
+    let mut info_lines = vec![
+        format!("java_version: {}", params.java_version),
+        format!("profile: {}", params.profile.name()),
+        format!("modules: {}", params.modules),
+        format!("target: {}", params.target),
+    ];
+    if let Some(url) = params.update_url {
+        info_lines.push(format!("update_url: {url}"));
+    }
+    let info_lines = info_lines
+        .iter()
+        .map(|line| format!("        echo {}", shell_quote(line)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let self_update = params
+        .update_url
+        .map(|url| self_update_arm(url, params.target, params.signature_pubkey.is_some()))
+        .unwrap_or_default();
+    let signature_functions = params
+        .signature_pubkey
+        .map(signature_functions)
+        .unwrap_or_default();
+    let signature_verify = if params.signature_pubkey.is_some() {
+        r#"
+        if jbundle_verify_signature; then echo "signature: ok"; else echo "signature: INVALID"; STATUS=1; fi"#
+    } else {
+        ""
+    };
+
+    format!(
+        r#"
+# Launcher self-management flags
+EXTRACT_ONLY=0
+sha256_hex() {{
+    if command -v sha256sum >/dev/null 2>&1; then
+        sha256sum | cut -d' ' -f1
+    else
+        shasum -a 256 | cut -d' ' -f1
+    fi
+}}{signature_functions}
+case "${{1:-}}" in
+    --{prefix}-info)
+{info_lines}
+        echo "runtime: rt-$RT_HASH ($RT_SIZE bytes)"
+        echo "app: app-$APP_HASH ($APP_SIZE bytes)"
+        if [ "$CRAC_SIZE" -gt 0 ]; then
+            echo "crac: crac-$CRAC_HASH ($CRAC_SIZE bytes)"
+        else
+            echo "crac: none"
+        fi
+        if [ "$RES_SIZE" -gt 0 ]; then
+            echo "resources: res-$RES_HASH ($RES_SIZE bytes)"
+        else
+            echo "resources: none"
+        fi
+        if [ "$DEPS_SIZE" -gt 0 ]; then
+            echo "deps: deps-$DEPS_HASH ($DEPS_SIZE bytes)"
+        else
+            echo "deps: none"
+        fi
+        if [ "$SBOM_SIZE" -gt 0 ]; then
+            echo "sbom: sbom-$SBOM_HASH ($SBOM_SIZE bytes)"
+        else
+            echo "sbom: none"
+        fi
+        echo "cache: $CACHE"
+        exit 0
+        ;;
+    --{prefix}-extract-only)
+        EXTRACT_ONLY=1
+        ;;
+    --{prefix}-clear-cache)
+        rm -rf "$RT_DIR" "$APP_DIR"
+        if [ "$CRAC_SIZE" -gt 0 ]; then
+            rm -rf "$CRAC_DIR"
+        fi
+        if [ "$RES_SIZE" -gt 0 ]; then
+            rm -rf "$RES_DIR"
+        fi
+        if [ "$DEPS_SIZE" -gt 0 ]; then
+            rm -rf "$DEPS_DIR"
+        fi
+        echo "Removed cached layers from $CACHE" >&2
+        exit 0
+        ;;
+    --{prefix}-verify)
+        sha256_prefix() {{
+            sha256_hex | cut -c1-16
+        }}
+        STATUS=0
+        ACTUAL=$(tail -c +$((STUB_SIZE + 1)) "$0" | head -c "$RT_SIZE" | sha256_prefix)
+        if [ "$ACTUAL" = "$RT_HASH" ]; then echo "runtime: ok"; else echo "runtime: MISMATCH"; STATUS=1; fi
+        ACTUAL=$(tail -c +$((STUB_SIZE + RT_SIZE + 1)) "$0" | head -c "$APP_SIZE" | gzip -d | sha256_prefix)
+        if [ "$ACTUAL" = "$APP_HASH" ]; then echo "app: ok"; else echo "app: MISMATCH"; STATUS=1; fi
+        if [ "$CRAC_SIZE" -gt 0 ]; then
+            ACTUAL=$(tail -c +$((STUB_SIZE + RT_SIZE + APP_SIZE + 1)) "$0" | head -c "$CRAC_SIZE" | sha256_prefix)
+            if [ "$ACTUAL" = "$CRAC_HASH" ]; then echo "crac: ok"; else echo "crac: MISMATCH"; STATUS=1; fi
+        fi
+        if [ "$RES_SIZE" -gt 0 ]; then
+            ACTUAL=$(tail -c +$((STUB_SIZE + RT_SIZE + APP_SIZE + CRAC_SIZE + 1)) "$0" | head -c "$RES_SIZE" | sha256_prefix)
+            if [ "$ACTUAL" = "$RES_HASH" ]; then echo "resources: ok"; else echo "resources: MISMATCH"; STATUS=1; fi
+        fi
+        if [ "$DEPS_SIZE" -gt 0 ]; then
+            ACTUAL=$(tail -c +$((STUB_SIZE + RT_SIZE + APP_SIZE + CRAC_SIZE + RES_SIZE + 1)) "$0" | head -c "$DEPS_SIZE" | sha256_prefix)
+            if [ "$ACTUAL" = "$DEPS_HASH" ]; then echo "deps: ok"; else echo "deps: MISMATCH"; STATUS=1; fi
+        fi
+        if [ "$SBOM_SIZE" -gt 0 ]; then
+            ACTUAL=$(tail -c +$((STUB_SIZE + RT_SIZE + APP_SIZE + CRAC_SIZE + RES_SIZE + DEPS_SIZE + 1)) "$0" | head -c "$SBOM_SIZE" | sha256_prefix)
+            if [ "$ACTUAL" = "$SBOM_HASH" ]; then echo "sbom: ok"; else echo "sbom: MISMATCH"; STATUS=1; fi
+        fi{signature_verify}
+        exit $STATUS
+        ;;
+    --{prefix}-sbom)
+        if [ "$SBOM_SIZE" -eq 0 ]; then
+            echo "error: this binary has no embedded SBOM" >&2
+            exit 1
+        fi
+        tail -c +$((STUB_SIZE + RT_SIZE + APP_SIZE + CRAC_SIZE + RES_SIZE + DEPS_SIZE + 1)) "$0" | head -c "$SBOM_SIZE" | gzip -d
+        exit 0
+        ;;{self_update}
+esac
+"#
+    )
+}
+
+/// Generates the shell functions checking the signature trailer written by
+/// `sign::sign_binary`: they rebuild the payload manifest (sha256 of the stub
+/// and each layer) and verify it with `openssl pkeyutl` against `pubkey_pem`.
+/// Both take the binary to check (default `$0`) and read its layer sizes from
+/// that binary's own header, so `--self-update` can check a download too.
+fn signature_functions(pubkey_pem: &str) -> String {
+    let prefix = crate::sign::SIGNATURE_PREFIX;
+    let trailer_len = crate::sign::SIGNATURE_TRAILER_LEN;
+    let pubkey_pem = pubkey_pem.trim_end();
+    format!(
+        r#"
+jbundle_header_value() {{
+    head -n 20 "$1" | tr ' ' '\n' | sed -n "s/^$2=\([0-9][0-9]*\)\$/\1/p" | head -n 1
+}}
+jbundle_manifest() {{
+    M_FILE=${{1:-$0}}
+    echo "jbundle-manifest v1"
+    OFF=$(jbundle_header_value "$M_FILE" STUB_SIZE)
+    OFF=${{OFF:-0}}
+    echo "stub $(head -c "$OFF" "$M_FILE" | sha256_hex)"
+    for L in runtime:RT_SIZE app:APP_SIZE crac:CRAC_SIZE resources:RES_SIZE deps:DEPS_SIZE sbom:SBOM_SIZE; do
+        SIZE=$(jbundle_header_value "$M_FILE" "${{L#*:}}")
+        SIZE=${{SIZE:-0}}
+        if [ "$SIZE" -gt 0 ]; then
+            echo "${{L%%:*}} $(tail -c +$((OFF + 1)) "$M_FILE" | head -c "$SIZE" | sha256_hex)"
+        fi
+        OFF=$((OFF + SIZE))
+    done
+    PAYLOAD_END=$OFF
+}}
+jbundle_unhex() {{
+    HEX=$1
+    while [ -n "$HEX" ]; do
+        REST=${{HEX#??}}
+        printf "\\$(printf '%03o' "0x${{HEX%"$REST"}}")"
+        HEX=$REST
+    done
+}}
+jbundle_write_pubkey() {{
+    cat > "$1" <<'JBUNDLE_PUBKEY'
+{pubkey_pem}
+JBUNDLE_PUBKEY
+}}
+jbundle_check_openssl() {{
+    # Ed25519 over raw input needs OpenSSL 3; LibreSSL (macOS) has no -rawin
+    if ! command -v openssl >/dev/null 2>&1 ||
+        ! openssl pkeyutl -help 2>&1 | grep -q -- '-rawin'; then
+        echo "error: checking this binary's signature requires OpenSSL 3 (openssl pkeyutl -rawin), found: $(openssl version 2>/dev/null || echo 'no openssl')" >&2
+        return 1
+    fi
+}}
+jbundle_verify_signature() {{
+    V_FILE=${{1:-$0}}
+    jbundle_check_openssl || return 1
+    TRAILER=$(tail -c {trailer_len} "$V_FILE")
+    case "$TRAILER" in
+        "{prefix}"*) SIG_HEX=${{TRAILER#"{prefix}"}} ;;
+        *) echo "error: binary is not signed" >&2; return 1 ;;
+    esac
+    SIG_TMP=$(mktemp -d) || return 1
+    jbundle_write_pubkey "$SIG_TMP/key.pem"
+    # Sets PAYLOAD_END; a redirected function still runs in this shell
+    jbundle_manifest "$V_FILE" > "$SIG_TMP/manifest"
+    jbundle_unhex "$SIG_HEX" > "$SIG_TMP/sig"
+    # Nothing but the trailer may follow the last layer
+    if [ $(($(wc -c < "$V_FILE"))) -eq $((PAYLOAD_END + {trailer_len})) ] &&
+        openssl pkeyutl -verify -pubin -inkey "$SIG_TMP/key.pem" -rawin \
+            -in "$SIG_TMP/manifest" -sigfile "$SIG_TMP/sig" >/dev/null 2>&1; then
+        SIG_STATUS=0
+    else
+        SIG_STATUS=1
+    fi
+    rm -rf "$SIG_TMP"
+    return $SIG_STATUS
+}}
+jbundle_verify_file_signature() {{
+    jbundle_check_openssl || return 1
+    SIG_TMP=$(mktemp -d) || return 1
+    jbundle_write_pubkey "$SIG_TMP/key.pem"
+    jbundle_unhex "$2" > "$SIG_TMP/sig"
+    if openssl pkeyutl -verify -pubin -inkey "$SIG_TMP/key.pem" -rawin \
+        -in "$1" -sigfile "$SIG_TMP/sig" >/dev/null 2>&1; then
+        SIG_STATUS=0
+    else
+        SIG_STATUS=1
+    fi
+    rm -rf "$SIG_TMP"
+    return $SIG_STATUS
+}}"#
+    )
+}
+
+/// Generates the `--self-update` case arm. It reads the update manifest,
+/// picks the entry for this binary's target, downloads it next to the binary,
+/// checks its sha256 and renames it over the binary. The rename keeps the
+/// running script's inode intact, so the current process is unaffected.
+///
+/// The sha256 comes from the same host as the download, so `signed` binaries
+/// also require the download's signature trailer, and the manifest's optional
+/// `signature` over the file, to verify against their baked-in key.
+fn self_update_arm(update_url: &str, target: &str, signed: bool) -> String {
+    let url = shell_quote(update_url);
+    let signature_check = if signed {
+        r#"
+        if ! jbundle_verify_signature "$TMP"; then
+            echo "error: $NEW_URL is not signed by this binary's key, keeping the current binary" >&2
+            exit 1
+        fi
+        if [ -n "$NEW_SIG" ] && ! jbundle_verify_file_signature "$TMP" "$NEW_SIG"; then
+            echo "error: manifest signature doesn't match $NEW_URL, keeping the current binary" >&2
+            exit 1
+        fi"#
+    } else {
+        r#"
+        if [ -n "$NEW_SIG" ]; then
+            echo "warning: this binary has no public key, the manifest signature can't be checked" >&2
+        fi"#
+    };
+    format!(
+        r#"
+    --self-update)
+        fetch() {{
+            if command -v curl >/dev/null 2>&1; then
+                curl -fsSL "$1"
+            elif command -v wget >/dev/null 2>&1; then
+                wget -qO- "$1"
+            else
+                echo "error: self-update requires curl or wget" >&2
+                return 1
+            fi
+        }}
+        json_field() {{
+            sed -n 's/.*"'"$1"'"[[:space:]]*:[[:space:]]*"\([^"]*\)".*/\1/p'
+        }}
+        MANIFEST=$(fetch {url}) || {{ echo "error: could not fetch "{url} >&2; exit 1; }}
+        MANIFEST=$(printf '%s' "$MANIFEST" | tr -d '\r\n')
+        ENTRY=$(printf '%s' "$MANIFEST" | sed -n 's/.*"{target}"[[:space:]]*:[[:space:]]*{{\([^}}]*\)}}.*/\1/p')
+        NEW_URL=$(printf '%s' "$ENTRY" | json_field url)
+        NEW_SHA=$(printf '%s' "$ENTRY" | json_field sha256)
+        NEW_SIG=$(printf '%s' "$ENTRY" | json_field signature)
+        NEW_VERSION=$(printf '%s' "$MANIFEST" | json_field version)
+        if [ -z "$NEW_URL" ] || [ -z "$NEW_SHA" ]; then
+            echo "error: update manifest has no url/sha256 for {target}" >&2
+            exit 1
+        fi
+        if [ "$(sha256_hex < "$0")" = "$NEW_SHA" ]; then
+            echo "Already up to date (${{NEW_VERSION:-unknown version}})" >&2
+            exit 0
+        fi
+        SELF="$BINARY_DIR/$(basename "$0")"
+        TMP="$SELF.update.$$"
+        trap 'rm -f "$TMP"' EXIT
+        echo "Downloading ${{NEW_VERSION:-update}}..." >&2
+        fetch "$NEW_URL" > "$TMP" || {{ echo "error: download failed: $NEW_URL" >&2; exit 1; }}
+        if [ "$(sha256_hex < "$TMP")" != "$NEW_SHA" ]; then
+            echo "error: checksum mismatch for $NEW_URL, keeping the current binary" >&2
+            exit 1
+        fi{signature_check}
+        chmod 755 "$TMP"
+        mv -f "$TMP" "$SELF"
+        echo "Updated to ${{NEW_VERSION:-latest}}" >&2
+        exit 0
+        ;;"#
+    )
+}
+
 /// Replace the __STUB_SIZE__ placeholder with the actual byte size of the stub
 pub fn finalize_stub(stub: &str) -> String {
     let placeholder = "__STUB_SIZE__";
@@ -131,6 +602,8 @@ pub fn finalize_stub(stub: &str) -> String {
 mod tests {
     use super::*;
 
+    static EMPTY_ENV: BTreeMap<String, String> = BTreeMap::new();
+
     fn params_default<'a>() -> StubParams<'a> {
         StubParams {
             runtime_hash: "rt1",
@@ -139,11 +612,26 @@ mod tests {
             app_size: 200,
             crac_hash: None,
             crac_size: 0,
+            res_hash: None,
+            res_size: 0,
+            deps_hash: None,
+            deps_size: 0,
+            sbom_hash: None,
+            sbom_size: 0,
+            main_class: None,
             profile: &JvmProfile::Server,
             jvm_args: &[],
             appcds: true,
             java_version: 21,
-            compact_banner: false,
+            banner: &Banner::Full,
+            banner_first_run_only: false,
+            modules: "java.base",
+            flag_prefix: "jbundle",
+            target: "linux-x64",
+            update_url: None,
+            signature_pubkey: None,
+            env: &EMPTY_ENV,
+            workdir: WorkDir::Cwd,
         }
     }
 
@@ -290,6 +778,50 @@ mod tests {
         assert!(stub.contains("(_) |__"));
     }
 
+    #[test]
+    fn stub_compact_banner() {
+        let p = StubParams {
+            banner: &Banner::Compact,
+            ..params_default()
+        };
+        let stub = generate(&p);
+        assert!(stub.contains("binary created with jbundle.avelino.run"));
+        assert!(!stub.contains("(_) |__"));
+    }
+
+    #[test]
+    fn stub_without_banner() {
+        let p = StubParams {
+            banner: &Banner::None,
+            ..params_default()
+        };
+        let stub = generate(&p);
+        assert!(!stub.contains("BANNER"));
+        assert!(!stub.contains("jbundle.avelino.run"));
+    }
+
+    #[test]
+    fn stub_custom_banner_is_quoted() {
+        let banner = Banner::Custom("My Tool's CLI\n$VERSION\n".to_string());
+        let p = StubParams {
+            banner: &banner,
+            ..params_default()
+        };
+        let stub = generate(&p);
+        assert!(stub.contains(r"printf '%s\n' 'My Tool'\''s CLI"));
+        assert!(stub.contains("$VERSION' >&2"));
+    }
+
+    #[test]
+    fn stub_banner_first_run_only() {
+        let p = StubParams {
+            banner_first_run_only: true,
+            ..params_default()
+        };
+        let stub = generate(&p);
+        assert!(stub.contains("if [ ! -f \"$APP_DIR/app.jar\" ]; then\ncat >&2 <<'BANNER'"));
+    }
+
     #[test]
     fn stub_contains_layered_cache_dirs() {
         let stub = generate(&params_default());
@@ -310,4 +842,824 @@ mod tests {
         assert!(!finalized.contains("__STUB_SIZE__"));
         assert!(finalized.contains("STUB_SIZE="));
     }
+
+    #[test]
+    fn shell_quote_leaves_safe_args_bare() {
+        assert_eq!(shell_quote("-Xmx512m"), "-Xmx512m");
+        assert_eq!(shell_quote("-Dapp.env=prod"), "-Dapp.env=prod");
+    }
+
+    #[test]
+    fn shell_quote_escapes_special_chars() {
+        assert_eq!(shell_quote("hello world"), "'hello world'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        assert_eq!(shell_quote("$HOME"), "'$HOME'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn shell_word_expands_placeholders() {
+        assert_eq!(
+            shell_word("-Dconf=${APP_DIR}/app.conf"),
+            r#"-Dconf="$APP_DIR"/app.conf"#
+        );
+        assert_eq!(shell_word("${BINARY_DIR}"), r#""$BINARY_DIR""#);
+        assert_eq!(shell_word("${HOME}"), "'${HOME}'");
+    }
+
+    #[test]
+    fn stub_quotes_jvm_args() {
+        let args = vec!["-Dgreeting=hello world".to_string()];
+        let p = StubParams {
+            jvm_args: &args,
+            ..params_default()
+        };
+        let stub = generate(&p);
+        assert!(stub.contains("'-Dgreeting=hello world'"));
+    }
+
+    #[test]
+    fn stub_defines_binary_dir() {
+        let stub = generate(&params_default());
+        assert!(stub.contains("BINARY_DIR="));
+    }
+
+    /// Fake "java" printing each argument on its own line.
+    #[cfg(unix)]
+    const ECHO_JAVA: &str = "#!/bin/sh\nfor a in \"$@\"; do printf '%s\\n' \"$a\"; done\n";
+
+    /// Options for [`build_test_binary`]; defaults to an echo runtime.
+    #[cfg(unix)]
+    struct TestBinary<'a> {
+        java_script: &'a str,
+        jvm_args: &'a [String],
+        env: &'a BTreeMap<String, String>,
+        workdir: WorkDir,
+        resources: &'a [std::path::PathBuf],
+        deps: &'a [std::path::PathBuf],
+        update_url: Option<&'a str>,
+        signature_pubkey: Option<&'a str>,
+        sbom: Option<&'a str>,
+    }
+
+    #[cfg(unix)]
+    impl Default for TestBinary<'_> {
+        fn default() -> Self {
+            TestBinary {
+                java_script: ECHO_JAVA,
+                jvm_args: &[],
+                env: &EMPTY_ENV,
+                workdir: WorkDir::Cwd,
+                resources: &[],
+                deps: &[],
+                update_url: None,
+                signature_pubkey: None,
+                sbom: None,
+            }
+        }
+    }
+
+    /// Builds a binary whose "java" prints each argument on its own line.
+    #[cfg(unix)]
+    fn build_echo_binary(dir: &std::path::Path, jvm_args: &[String]) -> std::path::PathBuf {
+        build_test_binary(
+            dir,
+            &TestBinary {
+                jvm_args,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Builds a binary with a fake runtime whose `bin/java` is `java_script`.
+    #[cfg(unix)]
+    fn build_test_binary(dir: &std::path::Path, opts: &TestBinary) -> std::path::PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let runtime = dir.join("runtime");
+        std::fs::create_dir_all(runtime.join("bin")).unwrap();
+        let java = runtime.join("bin").join("java");
+        std::fs::write(&java, opts.java_script).unwrap();
+        std::fs::set_permissions(&java, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let jar = dir.join("app.jar");
+        std::fs::write(&jar, b"fake jar").unwrap();
+
+        let output = dir.join("dist").join("app");
+        crate::pack::create_binary(&crate::pack::PackOptions {
+            runtime_dir: &runtime,
+            jar_path: &jar,
+            crac_path: None,
+            resources: opts.resources,
+            deps: opts.deps,
+            main_class: (!opts.deps.is_empty()).then_some("com.example.Main"),
+            output: &output,
+            jvm_args: opts.jvm_args,
+            profile: &JvmProfile::Server,
+            appcds: false,
+            java_version: 21,
+            banner: &Banner::Compact,
+            banner_first_run_only: false,
+            modules: "java.base,java.sql",
+            flag_prefix: "jbundle",
+            target: "linux-x64",
+            update_url: opts.update_url,
+            signature_pubkey: opts.signature_pubkey,
+            sbom: opts.sbom,
+            env: opts.env,
+            workdir: opts.workdir,
+        })
+        .unwrap();
+        output
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stub_passes_tricky_jvm_args_verbatim() {
+        let dir = tempfile::tempdir().unwrap();
+        let tricky = vec![
+            "-Dgreeting=\"hello world\"".to_string(),
+            "-Dprice=$100".to_string(),
+            "-Dcmd=`id`".to_string(),
+            "-Dquote=it's".to_string(),
+            "-Dglob=*".to_string(),
+            "-Dres=${BINARY_DIR}/res".to_string(),
+        ];
+        let binary = build_echo_binary(dir.path(), &tricky);
+
+        let output = std::process::Command::new("sh")
+            .arg(&binary)
+            .arg("app arg")
+            .env("HOME", dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let lines: Vec<&str> = stdout.lines().collect();
+        let binary_dir = binary.parent().unwrap().canonicalize().unwrap();
+
+        assert_eq!(lines[0], "-Dgreeting=\"hello world\"");
+        assert_eq!(lines[1], "-Dprice=$100");
+        assert_eq!(lines[2], "-Dcmd=`id`");
+        assert_eq!(lines[3], "-Dquote=it's");
+        assert_eq!(lines[4], "-Dglob=*");
+        assert_eq!(lines[5], format!("-Dres={}/res", binary_dir.display()));
+        assert_eq!(lines[6], "-jar");
+        assert_eq!(lines.last(), Some(&"app arg"));
+    }
+
+    #[cfg(unix)]
+    fn run_binary(
+        binary: &std::path::Path,
+        home: &std::path::Path,
+        args: &[&str],
+    ) -> std::process::Output {
+        std::process::Command::new("sh")
+            .arg(binary)
+            .args(args)
+            .env("HOME", home)
+            .output()
+            .unwrap()
+    }
+
+    #[test]
+    fn stub_without_env_has_no_exports() {
+        let stub = generate(&params_default());
+        assert!(!stub.contains("# Environment and working directory"));
+        assert!(!stub.lines().any(|l| l.starts_with("export ")));
+        assert!(!stub.contains("cd \"$BINARY_DIR\""));
+    }
+
+    #[test]
+    fn stub_exports_env_and_changes_workdir() {
+        let mut env = BTreeMap::new();
+        env.insert("LANG".to_string(), "C.UTF-8".to_string());
+        env.insert("APP_CONF".to_string(), "${APP_DIR}/conf dir".to_string());
+        let p = StubParams {
+            env: &env,
+            workdir: WorkDir::Binary,
+            ..params_default()
+        };
+        let stub = generate(&p);
+        assert!(stub.contains("export LANG=C.UTF-8\n"));
+        assert!(stub.contains("export APP_CONF=\"$APP_DIR\"'/conf dir'\n"));
+        assert!(stub.contains("cd \"$BINARY_DIR\"\n"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn env_and_workdir_applied_before_launch() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut env = BTreeMap::new();
+        env.insert("TZ".to_string(), "UTC".to_string());
+        env.insert("CONF".to_string(), "${BINARY_DIR}/conf $x".to_string());
+        let binary = build_test_binary(
+            dir.path(),
+            &TestBinary {
+                java_script:
+                    "#!/bin/sh\necho \"TZ=$TZ\"\necho \"CONF=$CONF\"\necho \"PWD=$(pwd)\"\n",
+                env: &env,
+                workdir: WorkDir::Binary,
+                ..Default::default()
+            },
+        );
+
+        let output = run_binary(&binary, dir.path(), &[]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let binary_dir = binary.parent().unwrap().canonicalize().unwrap();
+        assert!(stdout.contains("TZ=UTC\n"));
+        assert!(stdout.contains(&format!("CONF={}/conf $x\n", binary_dir.display())));
+        assert!(stdout.contains(&format!("PWD={}\n", binary_dir.display())));
+    }
+
+    #[test]
+    fn stub_without_resources_has_no_property() {
+        let stub = generate(&params_default());
+        assert!(stub.contains("RES_SIZE=0"));
+        assert!(!stub.contains("-Djbundle.resources"));
+    }
+
+    #[test]
+    fn stub_with_resources() {
+        let p = StubParams {
+            res_hash: Some("res123"),
+            res_size: 4096,
+            ..params_default()
+        };
+        let stub = generate(&p);
+        assert!(stub.contains("RES_SIZE=4096"));
+        assert!(stub.contains(r#"RES_HASH="res123""#));
+        assert!(stub.contains(r#""-Djbundle.resources=$RES_DIR""#));
+        assert!(stub.contains(r#"export JBUNDLE_RESOURCES="$RES_DIR""#));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resources_extracted_and_exposed() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config");
+        std::fs::create_dir_all(&config).unwrap();
+        std::fs::write(config.join("app.conf"), "port = 8080\n").unwrap();
+        let java_script = format!("{ECHO_JAVA}cat \"$JBUNDLE_RESOURCES/config/app.conf\"\n");
+        let binary = build_test_binary(
+            dir.path(),
+            &TestBinary {
+                java_script: &java_script,
+                resources: &[config],
+                ..Default::default()
+            },
+        );
+
+        let output = run_binary(&binary, dir.path(), &[]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("-Djbundle.resources="));
+        assert!(stdout.contains("port = 8080\n"));
+
+        let verify = run_binary(&binary, dir.path(), &["--jbundle-verify"]);
+        assert!(verify.status.success());
+        assert!(String::from_utf8(verify.stdout)
+            .unwrap()
+            .contains("resources: ok"));
+    }
+
+    #[test]
+    fn stub_thin_mode_uses_classpath() {
+        let p = StubParams {
+            deps_hash: Some("deps123"),
+            deps_size: 2048,
+            main_class: Some("com.example.Main"),
+            ..params_default()
+        };
+        let stub = generate(&p);
+        assert!(stub.contains("DEPS_SIZE=2048"));
+        assert!(stub.contains(r#"DEPS_DIR="$CACHE/deps-$DEPS_HASH""#));
+        assert!(stub.contains(r#"-cp "$APP_DIR/app.jar:$DEPS_DIR/lib/*" com.example.Main "$@""#));
+        assert!(!stub.contains(r#"-jar "$APP_DIR/app.jar""#));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn thin_binary_extracts_deps_after_other_layers() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config");
+        std::fs::create_dir_all(&config).unwrap();
+        std::fs::write(config.join("app.conf"), "x").unwrap();
+        let dep = dir.path().join("lib-1.0.jar");
+        std::fs::write(&dep, b"dependency jar").unwrap();
+        let binary = build_test_binary(
+            dir.path(),
+            &TestBinary {
+                resources: &[config],
+                deps: &[dep],
+                ..Default::default()
+            },
+        );
+
+        let output = run_binary(&binary, dir.path(), &["arg1"]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let lines: Vec<&str> = stdout.lines().collect();
+        let cp_pos = lines.iter().position(|l| *l == "-cp").unwrap();
+        let deps_lib = lines[cp_pos + 1].split(':').nth(1).unwrap();
+        assert_eq!(lines[cp_pos + 2], "com.example.Main");
+        assert_eq!(lines[cp_pos + 3], "arg1");
+        let extracted = std::path::Path::new(deps_lib.trim_end_matches('*')).join("lib-1.0.jar");
+        assert_eq!(std::fs::read(extracted).unwrap(), b"dependency jar");
+
+        let verify = run_binary(&binary, dir.path(), &["--jbundle-verify"]);
+        assert!(verify.status.success());
+        let report = String::from_utf8(verify.stdout).unwrap();
+        assert!(report.contains("resources: ok"));
+        assert!(report.contains("deps: ok"));
+    }
+
+    #[test]
+    fn stub_fat_mode_uses_jar() {
+        let stub = generate(&params_default());
+        assert!(stub.contains(r#"-jar "$APP_DIR/app.jar" "$@""#));
+        assert!(stub.contains("DEPS_SIZE=0"));
+    }
+
+    #[test]
+    fn stub_self_update_only_with_update_url() {
+        let stub = generate(&params_default());
+        assert!(!stub.contains("--self-update)"));
+
+        let p = StubParams {
+            update_url: Some("https://example.com/latest.json"),
+            target: "macos-aarch64",
+            ..params_default()
+        };
+        let stub = generate(&p);
+        assert!(stub.contains("--self-update)"));
+        assert!(stub.contains("fetch https://example.com/latest.json"));
+        assert!(stub.contains(r#""macos-aarch64""#));
+    }
+
+    /// Serves `files` over HTTP on an already bound listener, forever.
+    #[cfg(unix)]
+    fn serve_http(listener: std::net::TcpListener, files: Vec<(String, Vec<u8>)>) {
+        use std::io::{BufRead, BufReader, Write};
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request_line = String::new();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                reader.read_line(&mut request_line).unwrap();
+                let mut header = String::new();
+                while reader.read_line(&mut header).unwrap() > 2 {
+                    header.clear();
+                }
+                let path = request_line.split_whitespace().nth(1).unwrap_or("");
+                let response = match files.iter().find(|(p, _)| p == path) {
+                    Some((_, body)) => {
+                        let mut r = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            body.len()
+                        )
+                        .into_bytes();
+                        r.extend_from_slice(body);
+                        r
+                    }
+                    None => {
+                        b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_vec()
+                    }
+                };
+                let _ = stream.write_all(&response);
+            }
+        });
+    }
+
+    /// Signing setup for a self-update test.
+    #[cfg(unix)]
+    #[derive(Default)]
+    struct UpdateSigning<'a> {
+        /// Public key baked into the current binary
+        pubkey_pem: Option<&'a str>,
+        /// Signs the current binary's trailer
+        current_key: Option<&'a ed25519_dalek::SigningKey>,
+        /// Signs the next binary's trailer
+        next_key: Option<&'a ed25519_dalek::SigningKey>,
+        /// Signs the next binary's bytes for the manifest's `signature`
+        manifest_key: Option<&'a ed25519_dalek::SigningKey>,
+    }
+
+    /// Builds the current and the next version of a binary, serves an update
+    /// manifest for the next one, and returns (current binary, next bytes).
+    #[cfg(unix)]
+    fn setup_self_update(
+        dir: &std::path::Path,
+        manifest_sha: Option<&str>,
+        signing: &UpdateSigning,
+    ) -> (std::path::PathBuf, Vec<u8>) {
+        use ed25519_dalek::Signer;
+        use sha2::{Digest, Sha256};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let manifest_url = format!("{base}/latest.json");
+
+        let current = build_test_binary(
+            &dir.join("v1"),
+            &TestBinary {
+                update_url: Some(&manifest_url),
+                signature_pubkey: signing.pubkey_pem,
+                ..Default::default()
+            },
+        );
+        if let Some(key) = signing.current_key {
+            crate::sign::sign_binary(&current, key).unwrap();
+        }
+        let next_args = vec!["-Dversion=2".to_string()];
+        let next = build_test_binary(
+            &dir.join("v2"),
+            &TestBinary {
+                jvm_args: &next_args,
+                update_url: Some(&manifest_url),
+                signature_pubkey: signing.pubkey_pem,
+                ..Default::default()
+            },
+        );
+        if let Some(key) = signing.next_key {
+            crate::sign::sign_binary(&next, key).unwrap();
+        }
+        let next_bytes = std::fs::read(next).unwrap();
+        let sha = manifest_sha
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{:x}", Sha256::digest(&next_bytes)));
+        let signature = signing
+            .manifest_key
+            .map(|key| {
+                let sig: String = key
+                    .sign(&next_bytes)
+                    .to_bytes()
+                    .iter()
+                    .map(|b| format!("{b:02x}"))
+                    .collect();
+                format!(r#", "signature": "{sig}""#)
+            })
+            .unwrap_or_default();
+
+        let manifest = format!(
+            r#"{{
+  "version": "2.0.0",
+  "targets": {{
+    "macos-aarch64": {{ "url": "{base}/wrong", "sha256": "0000" }},
+    "linux-x64": {{ "url": "{base}/app-2.0.0", "sha256": "{sha}"{signature} }}
+  }}
+}}"#
+        );
+        serve_http(
+            listener,
+            vec![
+                ("/latest.json".to_string(), manifest.into_bytes()),
+                ("/app-2.0.0".to_string(), next_bytes.clone()),
+            ],
+        );
+        (current, next_bytes)
+    }
+
+    /// Whether `dir` still holds a half-downloaded update.
+    #[cfg(unix)]
+    fn has_update_leftovers(dir: &std::path::Path) -> bool {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .any(|e| e.file_name().to_string_lossy().contains(".update."))
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn self_update_replaces_binary() {
+        if which::which("curl").is_err() && which::which("wget").is_err() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let (binary, next_bytes) = setup_self_update(dir.path(), None, &UpdateSigning::default());
+
+        let output = run_binary(&binary, dir.path(), &["--self-update"]);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "{stderr}");
+        assert!(stderr.contains("Updated to 2.0.0"));
+        assert_eq!(std::fs::read(&binary).unwrap(), next_bytes);
+
+        let again = run_binary(&binary, dir.path(), &["--self-update"]);
+        assert!(again.status.success());
+        assert!(String::from_utf8_lossy(&again.stderr).contains("Already up to date (2.0.0)"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn self_update_rejects_checksum_mismatch() {
+        if which::which("curl").is_err() && which::which("wget").is_err() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let (binary, _) = setup_self_update(
+            dir.path(),
+            Some(&"ab".repeat(32)),
+            &UpdateSigning::default(),
+        );
+        let before = std::fs::read(&binary).unwrap();
+
+        let output = run_binary(&binary, dir.path(), &["--self-update"]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("checksum mismatch"));
+        assert_eq!(std::fs::read(&binary).unwrap(), before);
+        assert!(!has_update_leftovers(binary.parent().unwrap()));
+    }
+
+    /// Runs `--self-update` on a signed binary; returns (status ok, stderr,
+    /// binary replaced by the served one).
+    #[cfg(unix)]
+    fn run_signed_self_update(
+        next_seed: u8,
+        manifest_seed: Option<u8>,
+    ) -> Option<(bool, String, bool)> {
+        if which::which("openssl").is_err()
+            || (which::which("curl").is_err() && which::which("wget").is_err())
+        {
+            eprintln!("skipping: openssl and curl or wget are required");
+            return None;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let (_, public) = crate::sign::tests::write_key_pair(dir.path(), 5);
+        let pem = std::fs::read_to_string(public).unwrap();
+        let key = ed25519_dalek::SigningKey::from_bytes(&[5; 32]);
+        let next_key = ed25519_dalek::SigningKey::from_bytes(&[next_seed; 32]);
+        let manifest_key = manifest_seed.map(|s| ed25519_dalek::SigningKey::from_bytes(&[s; 32]));
+        let (binary, next_bytes) = setup_self_update(
+            dir.path(),
+            None,
+            &UpdateSigning {
+                pubkey_pem: Some(&pem),
+                current_key: Some(&key),
+                next_key: Some(&next_key),
+                manifest_key: manifest_key.as_ref(),
+            },
+        );
+        let before = std::fs::read(&binary).unwrap();
+
+        let output = run_binary(&binary, dir.path(), &["--self-update"]);
+        let after = std::fs::read(&binary).unwrap();
+        if !output.status.success() {
+            assert_eq!(after, before);
+            assert!(!has_update_leftovers(binary.parent().unwrap()));
+        }
+        Some((
+            output.status.success(),
+            String::from_utf8_lossy(&output.stderr).to_string(),
+            after == next_bytes,
+        ))
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn signed_self_update_accepts_binary_signed_by_same_key() {
+        let Some((ok, stderr, replaced)) = run_signed_self_update(5, Some(5)) else {
+            return;
+        };
+        assert!(ok, "{stderr}");
+        assert!(replaced);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn signed_self_update_rejects_bad_trailer_signature() {
+        // Correct sha256 in the manifest, but signed by another key
+        let Some((ok, stderr, replaced)) = run_signed_self_update(6, None) else {
+            return;
+        };
+        assert!(!ok);
+        assert!(!replaced);
+        assert!(
+            stderr.contains("is not signed by this binary's key"),
+            "{stderr}"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn signed_self_update_rejects_bad_manifest_signature() {
+        let Some((ok, stderr, replaced)) = run_signed_self_update(5, Some(6)) else {
+            return;
+        };
+        assert!(!ok);
+        assert!(!replaced);
+        assert!(
+            stderr.contains("manifest signature doesn't match"),
+            "{stderr}"
+        );
+    }
+
+    #[test]
+    fn stub_uses_flag_prefix() {
+        let p = StubParams {
+            flag_prefix: "mytool",
+            ..params_default()
+        };
+        let stub = generate(&p);
+        assert!(stub.contains("--mytool-info)"));
+        assert!(stub.contains("--mytool-verify)"));
+        assert!(!stub.contains("--jbundle-info)"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn info_flag_prints_metadata_without_launching() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = build_echo_binary(dir.path(), &[]);
+
+        let output = run_binary(&binary, dir.path(), &["--jbundle-info"]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("java_version: 21"));
+        assert!(stdout.contains("profile: server"));
+        assert!(stdout.contains("modules: java.base,java.sql"));
+        assert!(stdout.contains("crac: none"));
+        assert!(!stdout.contains("-jar"));
+        assert!(!dir.path().join(".jbundle").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn verify_flag_checks_payload() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = build_echo_binary(dir.path(), &[]);
+
+        let output = run_binary(&binary, dir.path(), &["--jbundle-verify"]);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(output.status.success(), "{stdout}");
+        assert!(stdout.contains("runtime: ok"));
+        assert!(stdout.contains("app: ok"));
+
+        // Corrupt the first byte of the runtime layer
+        let mut data = std::fs::read(&binary).unwrap();
+        let marker = b"# --- PAYLOAD BELOW ---\n";
+        let payload_start = data
+            .windows(marker.len())
+            .position(|w| w == marker)
+            .unwrap()
+            + marker.len();
+        data[payload_start + 10] ^= 0xFF;
+        std::fs::write(&binary, data).unwrap();
+
+        let output = run_binary(&binary, dir.path(), &["--jbundle-verify"]);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(!output.status.success());
+        assert!(stdout.contains("runtime: MISMATCH"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn signed_binary_refuses_tampered_payload() {
+        if which::which("openssl").is_err() {
+            eprintln!("skipping: openssl not installed");
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let (private, public) = crate::sign::tests::write_key_pair(dir.path(), 5);
+        let key = crate::sign::load_signing_key(&private).unwrap();
+        let pem = std::fs::read_to_string(&public).unwrap();
+        let binary = build_test_binary(
+            dir.path(),
+            &TestBinary {
+                signature_pubkey: Some(&pem),
+                ..Default::default()
+            },
+        );
+        crate::sign::sign_binary(&binary, &key).unwrap();
+
+        let output = run_binary(&binary, dir.path(), &["hello"]);
+        assert!(output.status.success(), "{output:?}");
+        let output = run_binary(&binary, dir.path(), &["--jbundle-verify"]);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(output.status.success(), "{stdout}");
+        assert!(stdout.contains("signature: ok"));
+
+        // Flip a byte inside the app layer and run with an empty cache
+        let layout = crate::pack::layout::read_layout(&binary).unwrap();
+        let app = layout.layer("app").unwrap();
+        let mut data = std::fs::read(&binary).unwrap();
+        data[(app.offset + app.size / 2) as usize] ^= 0xFF;
+        std::fs::write(&binary, data).unwrap();
+        let home = dir.path().join("fresh-home");
+        std::fs::create_dir_all(&home).unwrap();
+
+        let output = run_binary(&binary, &home, &["hello"]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("signature verification failed"), "{stderr}");
+        assert!(!String::from_utf8(output.stdout).unwrap().contains("hello"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn signed_binary_names_openssl_3_when_only_libressl_is_found() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (private, public) = crate::sign::tests::write_key_pair(dir.path(), 6);
+        let key = crate::sign::load_signing_key(&private).unwrap();
+        let pem = std::fs::read_to_string(&public).unwrap();
+        let binary = build_test_binary(
+            dir.path(),
+            &TestBinary {
+                signature_pubkey: Some(&pem),
+                ..Default::default()
+            },
+        );
+        crate::sign::sign_binary(&binary, &key).unwrap();
+
+        // Mimics macOS' /usr/bin/openssl: no -rawin in pkeyutl
+        let bin = dir.path().join("libressl");
+        std::fs::create_dir_all(&bin).unwrap();
+        let openssl = bin.join("openssl");
+        std::fs::write(
+            &openssl,
+            "#!/bin/sh\n\
+             case \"$1\" in\n\
+             version) echo 'LibreSSL 3.3.6' ;;\n\
+             *) echo 'Usage: pkeyutl [-verify] [-pubin] [-inkey file]' >&2 ;;\n\
+             esac\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&openssl, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+
+        let output = std::process::Command::new("sh")
+            .arg(&binary)
+            .arg("hello")
+            .env("HOME", dir.path())
+            .env("PATH", path)
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("requires OpenSSL 3"), "{stderr}");
+        assert!(stderr.contains("LibreSSL 3.3.6"), "{stderr}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sbom_flag_prints_embedded_sbom() {
+        let dir = tempfile::tempdir().unwrap();
+        let sbom = r#"{"bomFormat": "CycloneDX"}"#;
+        let binary = build_test_binary(
+            dir.path(),
+            &TestBinary {
+                sbom: Some(sbom),
+                ..Default::default()
+            },
+        );
+
+        let output = run_binary(&binary, dir.path(), &["--jbundle-sbom"]);
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), sbom);
+
+        let output = run_binary(&binary, dir.path(), &["--jbundle-verify"]);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(output.status.success(), "{stdout}");
+        assert!(stdout.contains("sbom: ok"));
+
+        // The app still starts normally with an SBOM layer after it
+        let output = run_binary(&binary, dir.path(), &["hello"]);
+        assert!(String::from_utf8(output.stdout).unwrap().contains("hello"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sbom_flag_without_sbom_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = build_echo_binary(dir.path(), &[]);
+        let output = run_binary(&binary, dir.path(), &["--jbundle-sbom"]);
+        assert!(!output.status.success());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn extract_only_and_clear_cache_flags() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = build_echo_binary(dir.path(), &[]);
+        let cache = dir.path().join(".jbundle").join("cache");
+
+        let output = run_binary(&binary, dir.path(), &["--jbundle-extract-only"]);
+        assert!(output.status.success());
+        assert!(output.stdout.is_empty());
+        let layers: Vec<String> = std::fs::read_dir(&cache)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert!(layers.iter().any(|l| l.starts_with("rt-")));
+        assert!(layers.iter().any(|l| l.starts_with("app-")));
+
+        let output = run_binary(&binary, dir.path(), &["--jbundle-clear-cache"]);
+        assert!(output.status.success());
+        assert_eq!(std::fs::read_dir(&cache).unwrap().count(), 0);
+    }
 }
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use crate::error::PackError;
+
+/// Match granularity. JAR entries are compressed independently, so unchanged
+/// entries show up as identical byte runs at shifted offsets.
+const BLOCK: usize = 1024;
+
+const OP_COPY: u8 = 0;
+const OP_INSERT: u8 = 1;
+
+/// Adler-style rolling checksum over a `BLOCK`-sized window.
+struct Rolling {
+    a: u32,
+    b: u32,
+}
+
+impl Rolling {
+    fn new(window: &[u8]) -> Self {
+        let mut a: u32 = 0;
+        let mut b: u32 = 0;
+        for (i, &byte) in window.iter().enumerate() {
+            a = a.wrapping_add(byte as u32);
+            b = b.wrapping_add((window.len() - i) as u32 * byte as u32);
+        }
+        Rolling { a, b }
+    }
+
+    fn roll(&mut self, out: u8, inp: u8) {
+        self.a = self.a.wrapping_sub(out as u32).wrapping_add(inp as u32);
+        self.b = self
+            .b
+            .wrapping_sub(BLOCK as u32 * out as u32)
+            .wrapping_add(self.a);
+    }
+
+    fn digest(&self) -> u32 {
+        (self.a & 0xffff) | (self.b << 16)
+    }
+}
+
+/// Encodes `new` as copy/insert operations against `old`.
+pub fn diff(old: &[u8], new: &[u8]) -> Vec<u8> {
+    let mut index: HashMap<u32, Vec<usize>> = HashMap::new();
+    for start in (0..old.len().saturating_sub(BLOCK - 1)).step_by(BLOCK) {
+        let digest = Rolling::new(&old[start..start + BLOCK]).digest();
+        index.entry(digest).or_default().push(start);
+    }
+
+    let mut ops = Vec::new();
+    let mut literal_start = 0;
+    let mut pos = 0;
+    let mut rolling = (new.len() >= BLOCK).then(|| Rolling::new(&new[..BLOCK]));
+
+    while let Some(ref mut window) = rolling {
+        let found = index.get(&window.digest()).and_then(|starts| {
+            starts
+                .iter()
+                .copied()
+                .find(|&s| old[s..s + BLOCK] == new[pos..pos + BLOCK])
+        });
+
+        if let Some(start) = found {
+            let mut len = BLOCK;
+            while start + len < old.len()
+                && pos + len < new.len()
+                && old[start + len] == new[pos + len]
+            {
+                len += 1;
+            }
+            push_insert(&mut ops, &new[literal_start..pos]);
+            push_copy(&mut ops, start as u64, len as u64);
+            pos += len;
+            literal_start = pos;
+            rolling = (pos + BLOCK <= new.len()).then(|| Rolling::new(&new[pos..pos + BLOCK]));
+        } else if pos + BLOCK < new.len() {
+            window.roll(new[pos], new[pos + BLOCK]);
+            pos += 1;
+        } else {
+            rolling = None;
+        }
+    }
+    push_insert(&mut ops, &new[literal_start..]);
+    ops
+}
+
+/// Rebuilds the new data from `old` and a delta produced by [`diff`].
+pub fn apply(old: &[u8], delta: &[u8]) -> Result<Vec<u8>, PackError> {
+    let corrupt = || PackError::PatchFailed("corrupt delta".to_string());
+    let read_u64 = |at: usize| -> Result<u64, PackError> {
+        let bytes = delta.get(at..at + 8).ok_or_else(corrupt)?;
+        Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+    };
+
+    let mut out = Vec::new();
+    let mut pos = 0;
+    while pos < delta.len() {
+        match delta[pos] {
+            OP_COPY => {
+                let start = read_u64(pos + 1)? as usize;
+                let len = read_u64(pos + 9)? as usize;
+                let chunk = start
+                    .checked_add(len)
+                    .and_then(|end| old.get(start..end))
+                    .ok_or_else(corrupt)?;
+                out.extend_from_slice(chunk);
+                pos += 17;
+            }
+            OP_INSERT => {
+                let len = read_u64(pos + 1)? as usize;
+                let end = pos
+                    .checked_add(9)
+                    .and_then(|p| p.checked_add(len))
+                    .ok_or_else(corrupt)?;
+                let chunk = delta.get(pos + 9..end).ok_or_else(corrupt)?;
+                out.extend_from_slice(chunk);
+                pos = end;
+            }
+            _ => return Err(corrupt()),
+        }
+    }
+    Ok(out)
+}
+
+fn push_copy(ops: &mut Vec<u8>, start: u64, len: u64) {
+    ops.push(OP_COPY);
+    ops.extend_from_slice(&start.to_le_bytes());
+    ops.extend_from_slice(&len.to_le_bytes());
+}
+
+fn push_insert(ops: &mut Vec<u8>, data: &[u8]) {
+    if data.is_empty() {
+        return;
+    }
+    ops.push(OP_INSERT);
+    ops.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    ops.extend_from_slice(data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pseudo_random(len: usize, seed: u32) -> Vec<u8> {
+        let mut x = seed;
+        (0..len)
+            .map(|_| {
+                x = x.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                (x >> 16) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn roundtrip_with_shifted_content() {
+        let old = pseudo_random(64 * 1024, 1);
+        let mut new = b"inserted header".to_vec();
+        new.extend_from_slice(&old[..20_000]);
+        new.extend_from_slice(&pseudo_random(3000, 2));
+        new.extend_from_slice(&old[30_000..]);
+
+        let delta = diff(&old, &new);
+        assert_eq!(apply(&old, &delta).unwrap(), new);
+        assert!(delta.len() < 4 * 1024, "delta is {} bytes", delta.len());
+    }
+
+    #[test]
+    fn roundtrip_small_and_empty_inputs() {
+        for (old, new) in [
+            (&b""[..], &b"new"[..]),
+            (&b"old"[..], &b""[..]),
+            (&b"abc"[..], &b"abc"[..]),
+        ] {
+            assert_eq!(apply(old, &diff(old, new)).unwrap(), new);
+        }
+    }
+
+    #[test]
+    fn apply_rejects_out_of_range_copy() {
+        let mut delta = Vec::new();
+        push_copy(&mut delta, 10, 10);
+        assert!(matches!(
+            apply(b"short", &delta),
+            Err(PackError::PatchFailed(_))
+        ));
+    }
+
+    #[test]
+    fn apply_rejects_insert_length_overflow() {
+        let mut delta = vec![OP_INSERT];
+        delta.extend_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(
+            apply(b"old", &delta),
+            Err(PackError::PatchFailed(_))
+        ));
+    }
+}
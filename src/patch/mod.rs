@@ -0,0 +1,487 @@
+mod delta;
+
+use std::io::{Read, Write};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+
+use crate::error::PackError;
+use crate::pack::archive::sha256_hex;
+use crate::pack::layout::{read_layout, read_range, BinaryLayout, Layer};
+
+const PATCH_MAGIC: &[u8] = b"JBUNDLE-PATCH 2\n";
+/// Any version of the patch format, for telling old patches from other files
+const PATCH_MAGIC_PREFIX: &[u8] = b"JBUNDLE-PATCH ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LayerSource {
+    /// Unchanged, copied from the base binary
+    Base,
+    /// Stored whole in the patch
+    Full,
+    /// Stored as a binary delta of the base app JAR
+    Delta,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PatchHeader {
+    stub_size: u64,
+    layers: Vec<PatchLayer>,
+    /// Bytes after the last layer (a signature), stored after the blobs
+    trailer_size: u64,
+    /// Full sha256 of the new binary, checked before anything is written
+    binary_sha256: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PatchLayer {
+    name: String,
+    /// Layer hash in the new binary
+    hash: String,
+    source: LayerSource,
+    /// Hash of the base layer a `base`/`delta` entry depends on
+    base_hash: Option<String>,
+    /// Bytes stored in the patch for this layer
+    size: u64,
+    /// Full sha256 of the layer's bytes in the new binary
+    sha256: String,
+}
+
+pub struct DiffSummary {
+    pub layers: Vec<(String, LayerSource)>,
+    pub patch_size: u64,
+    pub new_size: u64,
+}
+
+/// Writes a patch that turns `old` into `new`, holding only changed layers.
+pub fn create_patch(old: &Path, new: &Path, output: &Path) -> Result<DiffSummary, PackError> {
+    let old_layout = read_layout(old)?;
+    let new_layout = read_layout(new)?;
+
+    let stub = read_range(new, 0, new_layout.stub_size)?;
+    let new_size = std::fs::metadata(new)?.len();
+    let trailer = read_range(
+        new,
+        new_layout.payload_end,
+        new_size - new_layout.payload_end,
+    )?;
+    let mut header = PatchHeader {
+        stub_size: new_layout.stub_size,
+        layers: Vec::new(),
+        trailer_size: trailer.len() as u64,
+        binary_sha256: sha256_hex(&std::fs::read(new)?),
+    };
+    let mut blobs = Vec::new();
+
+    for layer in &new_layout.layers {
+        let full = read_range(new, layer.offset, layer.size)?;
+        let sha256 = sha256_hex(&full);
+        let base = old_layout.layer(layer.name);
+        let (source, base_hash, blob) = match base {
+            Some(b) if b.hash == layer.hash => (LayerSource::Base, Some(b.hash.clone()), None),
+            Some(b) if layer.name == "app" => {
+                let old_jar = gunzip(&read_range(old, b.offset, b.size)?)?;
+                let new_jar = gunzip(&full)?;
+                // apply_patch regzips the JAR, so a delta only works when that
+                // reproduces the layer (another jbundle or flate2 version may not)
+                let reproducible = crate::pack::gzip_stream(&mut &new_jar[..], Vec::new())? == full;
+                let delta = delta::diff(&old_jar, &new_jar);
+                let delta = crate::pack::gzip_stream(&mut &delta[..], Vec::new())?;
+                if reproducible && delta.len() < full.len() {
+                    (LayerSource::Delta, Some(b.hash.clone()), Some(delta))
+                } else {
+                    (LayerSource::Full, None, Some(full))
+                }
+            }
+            _ => (LayerSource::Full, None, Some(full)),
+        };
+        header.layers.push(PatchLayer {
+            name: layer.name.to_string(),
+            hash: layer.hash.clone(),
+            source,
+            base_hash,
+            size: blob.as_ref().map_or(0, |b| b.len() as u64),
+            sha256,
+        });
+        blobs.extend(blob);
+    }
+
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let header_json = serde_json::to_vec(&header)?;
+    let mut out = std::fs::File::create(output)?;
+    out.write_all(PATCH_MAGIC)?;
+    out.write_all(format!("{}\n", header_json.len()).as_bytes())?;
+    out.write_all(&header_json)?;
+    out.write_all(&stub)?;
+    for blob in &blobs {
+        out.write_all(blob)?;
+    }
+    out.write_all(&trailer)?;
+    drop(out);
+
+    Ok(DiffSummary {
+        layers: header
+            .layers
+            .iter()
+            .map(|l| (l.name.clone(), l.source))
+            .collect(),
+        patch_size: std::fs::metadata(output)?.len(),
+        new_size,
+    })
+}
+
+/// Rebuilds the new binary from `base` and a patch, verifying every layer.
+pub fn apply_patch(base: &Path, patch: &Path, output: &Path) -> Result<(), PackError> {
+    let data = std::fs::read(patch)?;
+    let (header, mut rest) = parse_patch(&data)?;
+    let base_layout = read_layout(base)?;
+
+    let stub = take(&mut rest, header.stub_size)?;
+    let mut binary = stub.to_vec();
+
+    for layer in &header.layers {
+        let bytes = match layer.source {
+            LayerSource::Base => read_base_layer(base, &base_layout, layer)?.1,
+            LayerSource::Full => take(&mut rest, layer.size)?.to_vec(),
+            LayerSource::Delta => {
+                let (base_layer, base_bytes) = read_base_layer(base, &base_layout, layer)?;
+                let old_jar = gunzip(&base_bytes)?;
+                let delta = gunzip(take(&mut rest, layer.size)?)?;
+                tracing::debug!("applying app delta against app-{}", base_layer.hash);
+                crate::pack::gzip_stream(&mut &delta::apply(&old_jar, &delta)?[..], Vec::new())?
+            }
+        };
+
+        // Compare the exact bytes: the stub's offsets and the signature
+        // depend on them, not only on the uncompressed content
+        if sha256_hex(&bytes) != layer.sha256 {
+            return Err(PackError::PatchFailed(format!(
+                "{} layer does not match the new binary after patching",
+                layer.name
+            )));
+        }
+        binary.extend_from_slice(&bytes);
+    }
+    binary.extend_from_slice(take(&mut rest, header.trailer_size)?);
+    if sha256_hex(&binary) != header.binary_sha256 {
+        return Err(PackError::PatchFailed(
+            "patched binary does not match the new binary".to_string(),
+        ));
+    }
+
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(output, &binary)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(output, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(())
+}
+
+fn parse_patch(data: &[u8]) -> Result<(PatchHeader, &[u8]), PackError> {
+    let invalid = || PackError::PatchFailed("not a jbundle patch".to_string());
+    let Some(rest) = data.strip_prefix(PATCH_MAGIC) else {
+        if data.starts_with(PATCH_MAGIC_PREFIX) {
+            return Err(PackError::PatchFailed(
+                "patch was made by an older jbundle; recreate it with `jbundle diff`".to_string(),
+            ));
+        }
+        return Err(invalid());
+    };
+    let newline = rest.iter().position(|&b| b == b'\n').ok_or_else(invalid)?;
+    let header_len: usize = std::str::from_utf8(&rest[..newline])
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(invalid)?;
+    let mut rest = &rest[newline + 1..];
+    let header: PatchHeader = serde_json::from_slice(take(&mut rest, header_len as u64)?)?;
+    Ok((header, rest))
+}
+
+fn read_base_layer<'a>(
+    base: &Path,
+    layout: &'a BinaryLayout,
+    layer: &PatchLayer,
+) -> Result<(&'a Layer, Vec<u8>), PackError> {
+    let base_layer = layout
+        .layer(&layer.name)
+        .filter(|b| Some(&b.hash) == layer.base_hash.as_ref())
+        .ok_or_else(|| {
+            PackError::PatchFailed(format!(
+                "base binary has no {} layer with hash {} (was the patch made for a different version?)",
+                layer.name,
+                layer.base_hash.as_deref().unwrap_or("?")
+            ))
+        })?;
+    let bytes = read_range(base, base_layer.offset, base_layer.size)?;
+    Ok((base_layer, bytes))
+}
+
+fn take<'a>(data: &mut &'a [u8], len: u64) -> Result<&'a [u8], PackError> {
+    let len = len as usize;
+    if data.len() < len {
+        return Err(PackError::PatchFailed("patch is truncated".to_string()));
+    }
+    let (head, tail) = data.split_at(len);
+    *data = tail;
+    Ok(head)
+}
+
+fn gunzip(data: &[u8]) -> Result<Vec<u8>, PackError> {
+    let mut out = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut out)?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    use crate::config::{Banner, JvmProfile, WorkDir};
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    /// Writes a runtime directory for `write_binary`. Old and new binaries
+    /// share one directory when the runtime should be unchanged, since the
+    /// tar headers carry file mtimes.
+    fn runtime_dir(dir: &Path, name: &str, java: &[u8]) -> PathBuf {
+        let runtime = dir.join(name);
+        std::fs::create_dir_all(runtime.join("bin")).unwrap();
+        std::fs::write(runtime.join("bin").join("java"), java).unwrap();
+        runtime
+    }
+
+    /// Packs a binary with the real packer, so patches cover its layer format.
+    fn write_binary(path: &Path, runtime: &Path, app_jar: &[u8]) {
+        let jar = path.with_extension("jar");
+        std::fs::write(&jar, app_jar).unwrap();
+        crate::pack::create_binary(&crate::pack::PackOptions {
+            runtime_dir: runtime,
+            jar_path: &jar,
+            crac_path: None,
+            resources: &[],
+            deps: &[],
+            main_class: None,
+            output: path,
+            jvm_args: &[],
+            profile: &JvmProfile::Server,
+            appcds: false,
+            java_version: 21,
+            banner: &Banner::Compact,
+            banner_first_run_only: false,
+            modules: "java.base",
+            flag_prefix: "jbundle",
+            target: "linux-x64",
+            update_url: None,
+            signature_pubkey: None,
+            sbom: None,
+            env: &BTreeMap::new(),
+            workdir: WorkDir::Cwd,
+        })
+        .unwrap();
+    }
+
+    fn jar_bytes(seed: u8, len: usize) -> Vec<u8> {
+        (0..len)
+            .map(|i| (i as u8).wrapping_mul(31) ^ seed)
+            .collect()
+    }
+
+    #[test]
+    fn patch_reuses_runtime_and_deltas_app() {
+        let dir = tempdir().unwrap();
+        let old = dir.path().join("old");
+        let new = dir.path().join("new");
+        let runtime = runtime_dir(dir.path(), "runtime", &jar_bytes(7, 50_000));
+        let old_jar = jar_bytes(1, 40_000);
+        let mut new_jar = old_jar.clone();
+        new_jar.extend_from_slice(b"a new class");
+        write_binary(&old, &runtime, &old_jar);
+        write_binary(&new, &runtime, &new_jar);
+
+        let patch = dir.path().join("update.patch");
+        let summary = create_patch(&old, &new, &patch).unwrap();
+        assert_eq!(
+            summary.layers,
+            vec![
+                ("runtime".to_string(), LayerSource::Base),
+                ("app".to_string(), LayerSource::Delta),
+            ]
+        );
+        assert!(summary.patch_size < summary.new_size);
+
+        let rebuilt = dir.path().join("rebuilt");
+        apply_patch(&old, &patch, &rebuilt).unwrap();
+        assert_eq!(
+            std::fs::read(&rebuilt).unwrap(),
+            std::fs::read(&new).unwrap()
+        );
+    }
+
+    #[test]
+    fn patch_stores_app_in_full_when_gzip_differs() {
+        let dir = tempdir().unwrap();
+        let old = dir.path().join("old");
+        let new = dir.path().join("new");
+        let runtime = runtime_dir(dir.path(), "runtime", b"runtime");
+        let old_jar = jar_bytes(1, 40_000);
+        let mut new_jar = old_jar.clone();
+        new_jar.extend_from_slice(b"a new class");
+        write_binary(&old, &runtime, &old_jar);
+        write_binary(&new, &runtime, &new_jar);
+
+        // Same JAR, stored by a packer with another compression level
+        let layout = read_layout(&new).unwrap();
+        let app = layout.layer("app").unwrap();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        encoder.write_all(&new_jar).unwrap();
+        let regzipped = encoder.finish().unwrap();
+        let data = std::fs::read(&new).unwrap();
+        let stub = String::from_utf8(data[..layout.stub_size as usize].to_vec()).unwrap();
+        let stub = stub
+            .replace(
+                &format!("APP_SIZE={}", app.size),
+                &format!("APP_SIZE={}", regzipped.len()),
+            )
+            .replace(
+                &format!("STUB_SIZE={}", layout.stub_size),
+                "STUB_SIZE=__STUB_SIZE__",
+            );
+        let mut binary = crate::pack::stub::finalize_stub(&stub).into_bytes();
+        binary.extend_from_slice(&data[layout.stub_size as usize..app.offset as usize]);
+        binary.extend_from_slice(&regzipped);
+        binary.extend_from_slice(&data[(app.offset + app.size) as usize..]);
+        std::fs::write(&new, binary).unwrap();
+
+        let patch = dir.path().join("update.patch");
+        let summary = create_patch(&old, &new, &patch).unwrap();
+        assert_eq!(summary.layers[1], ("app".to_string(), LayerSource::Full));
+
+        let rebuilt = dir.path().join("rebuilt");
+        apply_patch(&old, &patch, &rebuilt).unwrap();
+        assert_eq!(
+            std::fs::read(&rebuilt).unwrap(),
+            std::fs::read(&new).unwrap()
+        );
+    }
+
+    #[test]
+    fn patch_stores_changed_runtime_in_full() {
+        let dir = tempdir().unwrap();
+        let old = dir.path().join("old");
+        let new = dir.path().join("new");
+        let old_runtime = runtime_dir(dir.path(), "runtime-v1", b"runtime v1");
+        let new_runtime = runtime_dir(dir.path(), "runtime-v2", b"runtime v2");
+        write_binary(&old, &old_runtime, b"jar");
+        write_binary(&new, &new_runtime, b"jar");
+
+        let patch = dir.path().join("update.patch");
+        let summary = create_patch(&old, &new, &patch).unwrap();
+        assert_eq!(
+            summary.layers[0],
+            ("runtime".to_string(), LayerSource::Full)
+        );
+        assert_eq!(summary.layers[1], ("app".to_string(), LayerSource::Base));
+
+        let rebuilt = dir.path().join("rebuilt");
+        apply_patch(&old, &patch, &rebuilt).unwrap();
+        assert_eq!(
+            std::fs::read(&rebuilt).unwrap(),
+            std::fs::read(&new).unwrap()
+        );
+    }
+
+    #[test]
+    fn patch_keeps_signature_trailer() {
+        let dir = tempdir().unwrap();
+        let old = dir.path().join("old");
+        let new = dir.path().join("new");
+        let runtime = runtime_dir(dir.path(), "runtime", b"runtime");
+        write_binary(&old, &runtime, b"jar v1");
+        write_binary(&new, &runtime, b"jar v2");
+        let (private, public) = crate::sign::tests::write_key_pair(dir.path(), 3);
+        crate::sign::sign_binary(&new, &crate::sign::load_signing_key(&private).unwrap()).unwrap();
+
+        let patch = dir.path().join("update.patch");
+        create_patch(&old, &new, &patch).unwrap();
+        let rebuilt = dir.path().join("rebuilt");
+        apply_patch(&old, &patch, &rebuilt).unwrap();
+        assert_eq!(
+            std::fs::read(&rebuilt).unwrap(),
+            std::fs::read(&new).unwrap()
+        );
+        let key = crate::sign::load_verifying_key(&public).unwrap();
+        crate::sign::verify_binary(&rebuilt, &key).unwrap();
+    }
+
+    #[test]
+    fn apply_rejects_wrong_base() {
+        let dir = tempdir().unwrap();
+        let old = dir.path().join("old");
+        let new = dir.path().join("new");
+        let other = dir.path().join("other");
+        let runtime = runtime_dir(dir.path(), "runtime", b"runtime");
+        let other_runtime = runtime_dir(dir.path(), "other-runtime", b"other runtime");
+        write_binary(&old, &runtime, b"jar v1");
+        write_binary(&new, &runtime, b"jar v2");
+        write_binary(&other, &other_runtime, b"jar v1");
+
+        let patch = dir.path().join("update.patch");
+        create_patch(&old, &new, &patch).unwrap();
+
+        let result = apply_patch(&other, &patch, &dir.path().join("rebuilt"));
+        assert!(matches!(result, Err(PackError::PatchFailed(_))));
+    }
+
+    #[test]
+    fn apply_rejects_layer_that_rebuilds_differently() {
+        let dir = tempdir().unwrap();
+        let old = dir.path().join("old");
+        let new = dir.path().join("new");
+        let runtime = runtime_dir(dir.path(), "runtime", b"runtime");
+        write_binary(&old, &runtime, b"jar v1");
+        write_binary(&new, &runtime, b"jar v2");
+        let patch = dir.path().join("update.patch");
+        create_patch(&old, &new, &patch).unwrap();
+
+        // Same uncompressed JAR, different compressed bytes (e.g. another
+        // gzip level): the rebuilt layer must not pass
+        let data = std::fs::read(&patch).unwrap();
+        let (mut header, rest) = parse_patch(&data).unwrap();
+        header.layers[1].sha256 = sha256_hex(b"recompressed elsewhere");
+        let header_json = serde_json::to_vec(&header).unwrap();
+        let mut tampered = PATCH_MAGIC.to_vec();
+        tampered.extend_from_slice(format!("{}\n", header_json.len()).as_bytes());
+        tampered.extend_from_slice(&header_json);
+        tampered.extend_from_slice(rest);
+        std::fs::write(&patch, tampered).unwrap();
+
+        let rebuilt = dir.path().join("rebuilt");
+        let err = apply_patch(&old, &patch, &rebuilt).unwrap_err().to_string();
+        assert!(err.contains("app layer does not match"), "{err}");
+        assert!(!rebuilt.exists());
+    }
+
+    #[test]
+    fn apply_rejects_older_patch_format() {
+        let dir = tempdir().unwrap();
+        let patch = dir.path().join("update.patch");
+        std::fs::write(&patch, b"JBUNDLE-PATCH 1\n2\n{}").unwrap();
+        let err = apply_patch(&patch, &patch, &dir.path().join("rebuilt"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("older jbundle"), "{err}");
+    }
+}
@@ -1,4 +1,5 @@
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use serde::Deserialize;
@@ -16,12 +17,100 @@ pub struct ProjectConfig {
     pub appcds: Option<bool>,
     pub crac: Option<bool>,
     pub compact_banner: Option<bool>,
+    /// Ship dependency JARs as a separate layer and launch with -cp
+    pub thin: Option<bool>,
+    /// Startup banner: "full", "compact", "none" or "custom"
+    pub banner: Option<String>,
+    /// Text file used when banner = "custom"
+    pub banner_file: Option<String>,
+    /// Only show the banner on first run (when layers are extracted)
+    pub banner_first_run_only: Option<bool>,
     /// Gradle subproject to build (for multi-project builds)
     pub gradle_project: Option<String>,
+    /// Maven module to build (for multi-module reactor builds)
+    pub maven_module: Option<String>,
+    /// Bazel `java_binary` target whose deploy JAR is bundled
+    pub bazel_target: Option<String>,
+    /// Buck2 `java_binary` target
+    pub buck_target: Option<String>,
+    /// Mill module to assemble (default: first module in the build file)
+    pub mill_module: Option<String>,
+    /// sbt subproject to assemble (default: the root project)
+    pub sbt_project: Option<String>,
     /// Manual module override (bypasses jdeps detection)
     pub modules: Option<Vec<String>>,
     /// Path to existing jlink runtime to reuse
     pub jlink_runtime: Option<String>,
+    /// jlink tuning options
+    pub jlink: Option<JlinkConfig>,
+    /// Prefix for the launcher's reserved flags (default: "jbundle")
+    pub launcher_flag_prefix: Option<String>,
+    /// Working directory for the app: "binary" or "cwd" (default)
+    pub workdir: Option<String>,
+    /// Environment variables set by the launcher before starting the JVM
+    pub env: Option<BTreeMap<String, String>>,
+    /// Files and directories embedded as a separate resources layer
+    pub resources: Option<Vec<String>>,
+    /// Update manifest URL enabling the launcher's --self-update
+    pub update_url: Option<String>,
+    /// Ed25519 private key (PKCS#8 PEM) used to sign the binary
+    pub sign_key: Option<String>,
+    /// Make the launcher refuse to run when the signature doesn't verify
+    pub require_signature: Option<bool>,
+    /// SBOM format written next to the binary: "cyclonedx" or "spdx"
+    pub sbom: Option<String>,
+    /// Also embed the SBOM in the binary
+    pub embed_sbom: Option<bool>,
+    /// Licenses accepted by `jbundle licenses` (SPDX ids)
+    pub licenses_allow: Option<Vec<String>>,
+    /// Licenses rejected by `jbundle licenses` (SPDX ids)
+    pub licenses_deny: Option<Vec<String>>,
+    /// OSV advisory dump used by `jbundle audit`
+    pub audit_advisories: Option<String>,
+    /// Lowest severity that fails `jbundle audit`: low, medium, high, critical
+    pub audit_fail_on: Option<String>,
+    /// JDK advisory list checked against the bundled runtime by `jbundle audit`
+    pub audit_jdk_advisories: Option<String>,
+    /// Size limits checked after packing
+    pub budget: Option<BudgetConfig>,
+    /// Smoke test run against the packed binary
+    pub test: Option<TestConfig>,
+}
+
+/// `[budget]` table: sizes such as "80MB"
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BudgetConfig {
+    pub max_binary_size: Option<String>,
+    pub max_runtime_size: Option<String>,
+    pub max_app_size: Option<String>,
+}
+
+/// `[jlink]` table: options for the runtime image
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct JlinkConfig {
+    /// Add modules inferred from runtime lookups in the JAR (default: true)
+    pub heuristics: Option<bool>,
+    pub strip_native_commands: Option<bool>,
+    /// HotSpot VM: "server", "client" or "minimal"
+    pub vm: Option<String>,
+    pub include_locales: Option<Vec<String>>,
+    pub generate_cds_archive: Option<bool>,
+    pub dedup_legal_notices: Option<bool>,
+    pub bind_services: Option<bool>,
+    /// Passed to jlink as-is
+    pub extra_args: Option<Vec<String>>,
+}
+
+/// `[test]` table: how the packed binary is expected to behave
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TestConfig {
+    pub args: Option<Vec<String>>,
+    pub expect_exit: Option<i32>,
+    pub expect_stdout_regex: Option<String>,
+    pub timeout_secs: Option<u64>,
 }
 
 pub fn load_project_config(dir: &Path) -> Result<Option<ProjectConfig>> {
@@ -37,6 +126,58 @@ pub fn load_project_config(dir: &Path) -> Result<Option<ProjectConfig>> {
     Ok(Some(config))
 }
 
+/// Writes `modules = [...]` into the project's jbundle.toml, replacing an
+/// existing top-level `modules` key and leaving everything else untouched.
+/// Returns the path of the written file.
+pub fn set_modules(dir: &Path, modules: &[String]) -> Result<PathBuf> {
+    let config_path = dir.join(CONFIG_FILE);
+    let content = if config_path.exists() {
+        std::fs::read_to_string(&config_path)?
+    } else {
+        String::new()
+    };
+
+    let quoted: Vec<String> = modules.iter().map(|m| format!("\"{m}\"")).collect();
+    let entry = format!("modules = [{}]", quoted.join(", "));
+
+    let mut lines: Vec<&str> = content.lines().collect();
+    // Top-level keys end at the first table header
+    let top_level_end = lines
+        .iter()
+        .position(|l| l.trim_start().starts_with('['))
+        .unwrap_or(lines.len());
+    let existing = lines[..top_level_end].iter().position(|l| {
+        l.split_once('=')
+            .is_some_and(|(key, _)| key.trim() == "modules")
+    });
+
+    match existing {
+        Some(start) => {
+            // Multi-line arrays run until the line closing the bracket
+            let mut end = start;
+            while !lines[end].contains(']') && end + 1 < top_level_end {
+                end += 1;
+            }
+            lines.splice(start..=end, [entry.as_str()]);
+        }
+        None => {
+            let mut at = top_level_end;
+            // Keep a blank line between the keys and the first table
+            while at > 0 && lines[at - 1].trim().is_empty() {
+                at -= 1;
+            }
+            lines.insert(at, &entry);
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    toml::from_str::<ProjectConfig>(&updated)
+        .map_err(|e| anyhow::anyhow!("could not update {}: {}", CONFIG_FILE, e))?;
+    std::fs::write(&config_path, updated)?;
+    Ok(config_path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -56,10 +197,55 @@ jvm_args = ["-Xmx512m", "-XX:+UseZGC"]
 profile = "cli"
 appcds = false
 crac = true
+thin = true
 compact_banner = false
+banner = "custom"
+banner_file = "banner.txt"
+banner_first_run_only = true
 gradle_project = "jabkit"
+maven_module = "services/api"
+bazel_target = "//svc:app"
+mill_module = "app"
+sbt_project = "server"
 modules = ["java.base", "java.sql"]
 jlink_runtime = "./build/jlink"
+launcher_flag_prefix = "mytool"
+workdir = "binary"
+resources = ["config/", "static/"]
+update_url = "https://example.com/mytool/latest.json"
+sign_key = "keys/release.pem"
+require_signature = true
+sbom = "spdx"
+embed_sbom = true
+licenses_allow = ["Apache-2.0", "MIT", "EPL-1.0"]
+licenses_deny = ["AGPL-3.0"]
+audit_advisories = "vendor/osv/Maven.zip"
+audit_fail_on = "high"
+audit_jdk_advisories = "vendor/jdk-advisories.json"
+
+[env]
+TZ = "UTC"
+LANG = "C.UTF-8"
+
+[budget]
+max_binary_size = "80MB"
+max_app_size = "30MB"
+
+[jlink]
+heuristics = false
+strip_native_commands = true
+vm = "server"
+include_locales = ["en", "pt-BR"]
+generate_cds_archive = true
+dedup_legal_notices = true
+bind_services = false
+extra_args = ["--order-resources=**/module-info.class"]
+
+[test]
+args = ["--version"]
+expect_exit = 0
+expect_stdout_regex = "^app \\d+"
+timeout_secs = 30
 "#,
         )
         .unwrap();
@@ -75,13 +261,74 @@ jlink_runtime = "./build/jlink"
         assert_eq!(config.profile.as_deref(), Some("cli"));
         assert_eq!(config.appcds, Some(false));
         assert_eq!(config.crac, Some(true));
+        assert_eq!(config.thin, Some(true));
         assert_eq!(config.compact_banner, Some(false));
+        assert_eq!(config.banner.as_deref(), Some("custom"));
+        assert_eq!(config.banner_file.as_deref(), Some("banner.txt"));
+        assert_eq!(config.banner_first_run_only, Some(true));
         assert_eq!(config.gradle_project.as_deref(), Some("jabkit"));
+        assert_eq!(config.maven_module.as_deref(), Some("services/api"));
+        assert_eq!(config.bazel_target.as_deref(), Some("//svc:app"));
+        assert_eq!(config.buck_target, None);
+        assert_eq!(config.mill_module.as_deref(), Some("app"));
+        assert_eq!(config.sbt_project.as_deref(), Some("server"));
         assert_eq!(
             config.modules,
             Some(vec!["java.base".to_string(), "java.sql".to_string()])
         );
         assert_eq!(config.jlink_runtime.as_deref(), Some("./build/jlink"));
+        assert_eq!(config.launcher_flag_prefix.as_deref(), Some("mytool"));
+        assert_eq!(config.workdir.as_deref(), Some("binary"));
+        assert_eq!(
+            config.resources,
+            Some(vec!["config/".to_string(), "static/".to_string()])
+        );
+        assert_eq!(
+            config.update_url.as_deref(),
+            Some("https://example.com/mytool/latest.json")
+        );
+        assert_eq!(config.sign_key.as_deref(), Some("keys/release.pem"));
+        assert_eq!(config.require_signature, Some(true));
+        assert_eq!(config.sbom.as_deref(), Some("spdx"));
+        assert_eq!(config.embed_sbom, Some(true));
+        assert_eq!(config.licenses_allow.unwrap().len(), 3);
+        assert_eq!(config.licenses_deny.unwrap(), vec!["AGPL-3.0"]);
+        assert_eq!(
+            config.audit_advisories.as_deref(),
+            Some("vendor/osv/Maven.zip")
+        );
+        assert_eq!(config.audit_fail_on.as_deref(), Some("high"));
+        assert_eq!(
+            config.audit_jdk_advisories.as_deref(),
+            Some("vendor/jdk-advisories.json")
+        );
+        let budget = config.budget.unwrap();
+        assert_eq!(budget.max_binary_size.as_deref(), Some("80MB"));
+        assert_eq!(budget.max_runtime_size, None);
+        assert_eq!(budget.max_app_size.as_deref(), Some("30MB"));
+        let jlink = config.jlink.unwrap();
+        assert_eq!(jlink.heuristics, Some(false));
+        assert_eq!(jlink.strip_native_commands, Some(true));
+        assert_eq!(jlink.vm.as_deref(), Some("server"));
+        assert_eq!(
+            jlink.include_locales,
+            Some(vec!["en".to_string(), "pt-BR".to_string()])
+        );
+        assert_eq!(jlink.generate_cds_archive, Some(true));
+        assert_eq!(jlink.dedup_legal_notices, Some(true));
+        assert_eq!(jlink.bind_services, Some(false));
+        assert_eq!(
+            jlink.extra_args,
+            Some(vec!["--order-resources=**/module-info.class".to_string()])
+        );
+        let test = config.test.unwrap();
+        assert_eq!(test.args, Some(vec!["--version".to_string()]));
+        assert_eq!(test.expect_exit, Some(0));
+        assert_eq!(test.expect_stdout_regex.as_deref(), Some(r"^app \d+"));
+        assert_eq!(test.timeout_secs, Some(30));
+        let env = config.env.unwrap();
+        assert_eq!(env.get("TZ").map(String::as_str), Some("UTC"));
+        assert_eq!(env.get("LANG").map(String::as_str), Some("C.UTF-8"));
     }
 
     #[test]
@@ -130,4 +377,45 @@ jlink_runtime = "./build/jlink"
         let result = load_project_config(dir.path());
         assert!(result.is_err());
     }
+
+    #[test]
+    fn set_modules_replaces_existing_key() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join(CONFIG_FILE),
+            "java_version = 21\nmodules = [\n  \"java.base\",\n]\nshrink = true\n\n[env]\nTZ = \"UTC\"\n",
+        )
+        .unwrap();
+
+        let modules = vec!["java.base".to_string(), "java.sql".to_string()];
+        set_modules(dir.path(), &modules).unwrap();
+
+        let content = fs::read_to_string(dir.path().join(CONFIG_FILE)).unwrap();
+        assert_eq!(
+            content,
+            "java_version = 21\nmodules = [\"java.base\", \"java.sql\"]\nshrink = true\n\n[env]\nTZ = \"UTC\"\n"
+        );
+    }
+
+    #[test]
+    fn set_modules_inserts_before_tables_or_creates_file() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join(CONFIG_FILE),
+            "java_version = 21\n\n[budget]\nmax_binary_size = \"80MB\"\n",
+        )
+        .unwrap();
+        set_modules(dir.path(), &["java.base".to_string()]).unwrap();
+        let config = load_project_config(dir.path()).unwrap().unwrap();
+        assert_eq!(config.modules, Some(vec!["java.base".to_string()]));
+        assert_eq!(
+            config.budget.unwrap().max_binary_size.as_deref(),
+            Some("80MB")
+        );
+
+        let empty = tempdir().unwrap();
+        set_modules(empty.path(), &["java.base".to_string()]).unwrap();
+        let config = load_project_config(empty.path()).unwrap().unwrap();
+        assert_eq!(config.modules, Some(vec!["java.base".to_string()]));
+    }
 }
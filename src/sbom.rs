@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use crate::analyze::maven::MavenCoordinate;
+use crate::config::SbomFormat;
+use crate::error::PackError;
+use crate::jvm::adoptium::Package;
+use crate::pack::archive::sha256_hex;
+
+/// What jbundle knows about the bundled Java runtime.
+#[derive(Debug, Default)]
+pub struct RuntimeInfo {
+    /// `JAVA_RUNTIME_VERSION` (e.g. `21.0.5+11-LTS`), else `JAVA_VERSION`
+    pub version: Option<String>,
+    pub implementor: Option<String>,
+    /// Modules linked into the runtime by jlink
+    pub modules: Vec<String>,
+    /// Adoptium asset the JDK was downloaded from, when recorded
+    pub asset: Option<Package>,
+}
+
+/// Reads the `release` file of a jlinked runtime, plus the Adoptium asset
+/// recorded for the JDK it was linked from.
+pub fn read_runtime_info(runtime_dir: &Path, jdk_dir: &Path) -> RuntimeInfo {
+    let release = std::fs::read_to_string(runtime_dir.join("release")).unwrap_or_default();
+    let mut fields = parse_release(&release);
+    RuntimeInfo {
+        version: fields
+            .remove("JAVA_RUNTIME_VERSION")
+            .or_else(|| fields.remove("JAVA_VERSION")),
+        implementor: fields.remove("IMPLEMENTOR"),
+        modules: fields
+            .remove("MODULES")
+            .map(|m| m.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default(),
+        asset: crate::jvm::cache::read_release_info(jdk_dir),
+    }
+}
+
+/// Parses `KEY="value"` lines of a JDK `release` file.
+pub fn parse_release(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            Some((
+                key.trim().to_string(),
+                value.trim().trim_matches('"').to_string(),
+            ))
+        })
+        .collect()
+}
+
+pub struct SbomInput<'a> {
+    /// Application name (the output binary's file name)
+    pub name: &'a str,
+    /// Maven artifacts found in the uberjar before shrinking
+    pub libraries: &'a [MavenCoordinate],
+    pub runtime: &'a RuntimeInfo,
+}
+
+impl SbomInput<'_> {
+    pub fn component_count(&self) -> usize {
+        self.libraries.len() + 1
+    }
+}
+
+/// Renders the SBOM as pretty-printed JSON.
+pub fn generate(format: SbomFormat, input: &SbomInput) -> Result<String, PackError> {
+    let created = timestamp();
+    let doc = match format {
+        SbomFormat::CycloneDx => cyclonedx(input, &created),
+        SbomFormat::Spdx => spdx(input, &created),
+    };
+    Ok(serde_json::to_string_pretty(&doc)?)
+}
+
+fn cyclonedx(input: &SbomInput, created: &str) -> Value {
+    let runtime = input.runtime;
+    let mut jdk = json!({
+        "type": "platform",
+        "bom-ref": "java-runtime",
+        "name": "java-runtime",
+        "version": runtime.version.as_deref().unwrap_or("unknown"),
+        "properties": [
+            { "name": "jbundle:jlink:modules", "value": runtime.modules.join(",") }
+        ],
+    });
+    if let Some(ref implementor) = runtime.implementor {
+        jdk["supplier"] = json!({ "name": implementor });
+    }
+    if let Some(ref asset) = runtime.asset {
+        jdk["hashes"] = json!([{ "alg": "SHA-256", "content": asset.checksum }]);
+        jdk["externalReferences"] = json!([{ "type": "distribution", "url": asset.link }]);
+        jdk["properties"]
+            .as_array_mut()
+            .expect("properties is an array")
+            .push(json!({ "name": "jbundle:jdk:asset", "value": asset.name }));
+    }
+
+    let mut components: Vec<Value> = input
+        .libraries
+        .iter()
+        .map(|c| {
+            json!({
+                "type": "library",
+                "bom-ref": c.purl(),
+                "group": c.group_id,
+                "name": c.artifact_id,
+                "version": c.version,
+                "purl": c.purl(),
+            })
+        })
+        .collect();
+    components.push(jdk);
+
+    let mut depends_on: Vec<String> = input.libraries.iter().map(|c| c.purl()).collect();
+    depends_on.push("java-runtime".to_string());
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "serialNumber": format!("urn:uuid:{}", document_uuid(input)),
+        "version": 1,
+        "metadata": {
+            "timestamp": created,
+            "tools": {
+                "components": [{
+                    "type": "application",
+                    "name": "jbundle",
+                    "version": env!("CARGO_PKG_VERSION"),
+                }]
+            },
+            "component": {
+                "type": "application",
+                "bom-ref": input.name,
+                "name": input.name,
+            },
+        },
+        "components": components,
+        "dependencies": [{ "ref": input.name, "dependsOn": depends_on }],
+    })
+}
+
+fn spdx(input: &SbomInput, created: &str) -> Value {
+    let runtime = input.runtime;
+    let mut packages = vec![json!({
+        "SPDXID": "SPDXRef-Application",
+        "name": input.name,
+        "downloadLocation": "NOASSERTION",
+        "filesAnalyzed": false,
+    })];
+    let mut relationships = vec![json!({
+        "spdxElementId": "SPDXRef-DOCUMENT",
+        "relationshipType": "DESCRIBES",
+        "relatedSpdxElement": "SPDXRef-Application",
+    })];
+
+    for (i, c) in input.libraries.iter().enumerate() {
+        let id = format!("SPDXRef-Package-{}", i + 1);
+        packages.push(json!({
+            "SPDXID": id,
+            "name": format!("{}:{}", c.group_id, c.artifact_id),
+            "versionInfo": c.version,
+            "downloadLocation": "NOASSERTION",
+            "filesAnalyzed": false,
+            "externalRefs": [{
+                "referenceCategory": "PACKAGE-MANAGER",
+                "referenceType": "purl",
+                "referenceLocator": c.purl(),
+            }],
+        }));
+        relationships.push(json!({
+            "spdxElementId": "SPDXRef-Application",
+            "relationshipType": "DEPENDS_ON",
+            "relatedSpdxElement": id,
+        }));
+    }
+
+    let mut jdk = json!({
+        "SPDXID": "SPDXRef-Runtime",
+        "name": "java-runtime",
+        "versionInfo": runtime.version.as_deref().unwrap_or("unknown"),
+        "downloadLocation": runtime.asset.as_ref().map_or("NOASSERTION", |a| a.link.as_str()),
+        "filesAnalyzed": false,
+        "comment": format!("jlink modules: {}", runtime.modules.join(",")),
+    });
+    if let Some(ref implementor) = runtime.implementor {
+        jdk["supplier"] = json!(format!("Organization: {implementor}"));
+    }
+    if let Some(ref asset) = runtime.asset {
+        jdk["packageFileName"] = json!(asset.name);
+        jdk["checksums"] = json!([{ "algorithm": "SHA256", "checksumValue": asset.checksum }]);
+    }
+    packages.push(jdk);
+    relationships.push(json!({
+        "spdxElementId": "SPDXRef-Application",
+        "relationshipType": "DEPENDS_ON",
+        "relatedSpdxElement": "SPDXRef-Runtime",
+    }));
+
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": input.name,
+        "documentNamespace": format!(
+            "https://jbundle.avelino.run/spdx/{}-{}",
+            input.name,
+            document_uuid(input)
+        ),
+        "creationInfo": {
+            "created": created,
+            "creators": [format!("Tool: jbundle-{}", env!("CARGO_PKG_VERSION"))],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
+
+/// Name-based UUID derived from the SBOM contents, so identical inputs give
+/// identical documents.
+fn document_uuid(input: &SbomInput) -> String {
+    let mut seed = format!("{}\n{:?}\n", input.name, input.runtime.version);
+    for c in input.libraries {
+        seed.push_str(&c.purl());
+        seed.push('\n');
+    }
+    let h = sha256_hex(seed.as_bytes());
+    format!(
+        "{}-{}-5{}-8{}-{}",
+        &h[0..8],
+        &h[8..12],
+        &h[13..16],
+        &h[17..20],
+        &h[20..32]
+    )
+}
+
+/// Creation time, honoring `SOURCE_DATE_EPOCH` for reproducible builds.
+fn timestamp() -> String {
+    let secs = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        });
+    format_rfc3339(secs)
+}
+
+/// Formats Unix seconds as `YYYY-MM-DDTHH:MM:SSZ`.
+fn format_rfc3339(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn libraries() -> Vec<MavenCoordinate> {
+        vec![MavenCoordinate {
+            group_id: "org.clojure".to_string(),
+            artifact_id: "clojure".to_string(),
+            version: "1.12.0".to_string(),
+        }]
+    }
+
+    fn runtime() -> RuntimeInfo {
+        RuntimeInfo {
+            version: Some("21.0.5+11-LTS".to_string()),
+            implementor: Some("Eclipse Adoptium".to_string()),
+            modules: vec!["java.base".to_string(), "java.sql".to_string()],
+            asset: Some(Package {
+                link: "https://example.com/jdk.tar.gz".to_string(),
+                checksum: "abc123".to_string(),
+                size: 1,
+                name: "OpenJDK21U-jdk_x64_linux_hotspot_21.0.5_11.tar.gz".to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn parse_release_file() {
+        let content = "IMPLEMENTOR=\"Eclipse Adoptium\"\nJAVA_VERSION=\"21.0.5\"\n\
+                       MODULES=\"java.base java.logging\"\n";
+        let fields = parse_release(content);
+        assert_eq!(fields["IMPLEMENTOR"], "Eclipse Adoptium");
+        assert_eq!(fields["MODULES"], "java.base java.logging");
+    }
+
+    #[test]
+    fn read_runtime_info_from_release() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("release"),
+            "JAVA_VERSION=\"21.0.5\"\nJAVA_RUNTIME_VERSION=\"21.0.5+11-LTS\"\n\
+             MODULES=\"java.base java.sql\"\n",
+        )
+        .unwrap();
+        let info = read_runtime_info(dir.path(), &dir.path().join("jdk"));
+        assert_eq!(info.version.as_deref(), Some("21.0.5+11-LTS"));
+        assert_eq!(info.modules, vec!["java.base", "java.sql"]);
+        assert!(info.asset.is_none());
+    }
+
+    #[test]
+    fn cyclonedx_lists_libraries_and_runtime() {
+        let (libraries, runtime) = (libraries(), runtime());
+        let input = SbomInput {
+            name: "app",
+            libraries: &libraries,
+            runtime: &runtime,
+        };
+        let doc: Value =
+            serde_json::from_str(&generate(SbomFormat::CycloneDx, &input).unwrap()).unwrap();
+        assert_eq!(doc["bomFormat"], "CycloneDX");
+        let components = doc["components"].as_array().unwrap();
+        assert_eq!(components.len(), input.component_count());
+        assert_eq!(
+            components[0]["purl"],
+            "pkg:maven/org.clojure/clojure@1.12.0"
+        );
+        assert_eq!(components[1]["hashes"][0]["content"], "abc123");
+        assert_eq!(
+            components[1]["properties"][0]["value"],
+            "java.base,java.sql"
+        );
+        assert_eq!(doc["dependencies"][0]["dependsOn"][1], "java-runtime");
+    }
+
+    #[test]
+    fn spdx_relates_packages_to_application() {
+        let (libraries, runtime) = (libraries(), runtime());
+        let input = SbomInput {
+            name: "app",
+            libraries: &libraries,
+            runtime: &runtime,
+        };
+        let doc: Value =
+            serde_json::from_str(&generate(SbomFormat::Spdx, &input).unwrap()).unwrap();
+        assert_eq!(doc["spdxVersion"], "SPDX-2.3");
+        assert_eq!(doc["packages"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            doc["packages"][1]["externalRefs"][0]["referenceLocator"],
+            "pkg:maven/org.clojure/clojure@1.12.0"
+        );
+        assert_eq!(
+            doc["packages"][2]["checksums"][0]["checksumValue"],
+            "abc123"
+        );
+        assert_eq!(doc["relationships"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn format_rfc3339_dates() {
+        assert_eq!(format_rfc3339(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_rfc3339(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_rfc3339(1_700_000_000), "2023-11-14T22:13:20Z");
+    }
+}
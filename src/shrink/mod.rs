@@ -33,6 +33,8 @@ pub fn shrink_jar(jar_path: &Path) -> Result<ShrinkResult, PackError> {
     let options = SimpleFileOptions::default()
         .compression_method(CompressionMethod::Deflated)
         .compression_level(Some(9));
+    // Spring Boot only opens nested JARs that are stored uncompressed
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
 
     let mut buf = Vec::new();
 
@@ -57,8 +59,13 @@ pub fn shrink_jar(jar_path: &Path) -> Result<ShrinkResult, PackError> {
                 .read_to_end(&mut buf)
                 .map_err(|e| PackError::ShrinkFailed(format!("zip read error: {e}")))?;
 
+            let entry_options = if crate::boot::is_nested_jar(&name) {
+                stored
+            } else {
+                options
+            };
             writer
-                .start_file(&name, options)
+                .start_file(&name, entry_options)
                 .map_err(|e| PackError::ShrinkFailed(format!("zip write error: {e}")))?;
             writer
                 .write_all(&buf)
@@ -133,6 +140,24 @@ mod tests {
     use tempfile::NamedTempFile;
     use zip::write::SimpleFileOptions as TestOptions;
 
+    #[test]
+    fn shrink_keeps_boot_nested_jars_stored() {
+        let jar = create_test_jar(&[
+            ("BOOT-INF/classes/com/example/App.class", b"app"),
+            ("BOOT-INF/lib/dep-1.0.jar", &[0u8; 512]),
+        ]);
+        let result = shrink_jar(jar.path()).unwrap();
+        let mut archive = ZipArchive::new(std::fs::File::open(&result.jar_path).unwrap()).unwrap();
+        let nested = archive.by_name("BOOT-INF/lib/dep-1.0.jar").unwrap();
+        assert_eq!(nested.compression(), CompressionMethod::Stored);
+        drop(nested);
+        let class = archive
+            .by_name("BOOT-INF/classes/com/example/App.class")
+            .unwrap();
+        assert_eq!(class.compression(), CompressionMethod::Deflated);
+        std::fs::remove_file(&result.jar_path).ok();
+    }
+
     fn create_test_jar(entries: &[(&str, &[u8])]) -> NamedTempFile {
         let file = NamedTempFile::new().unwrap();
         let mut zip = ZipWriter::new(file.reopen().unwrap());
@@ -0,0 +1,215 @@
+use std::io::Write;
+use std::path::Path;
+
+use ed25519_dalek::pkcs8::spki::der::pem::LineEnding;
+use ed25519_dalek::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePublicKey};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+
+use crate::error::PackError;
+use crate::pack::archive::sha256_hex;
+use crate::pack::layout::{read_layout, read_range};
+
+/// The signature is appended after the last layer as a shell comment line,
+/// so signed binaries still run as plain scripts.
+pub const SIGNATURE_PREFIX: &str = "# jbundle-signature: ";
+
+/// Prefix + 64 signature bytes in hex + newline.
+pub const SIGNATURE_TRAILER_LEN: usize = SIGNATURE_PREFIX.len() + 128 + 1;
+
+/// Reads an Ed25519 private key in PKCS#8 PEM form
+/// (`openssl genpkey -algorithm ed25519`).
+pub fn load_signing_key(path: &Path) -> Result<SigningKey, PackError> {
+    let pem = std::fs::read_to_string(path)?;
+    SigningKey::from_pkcs8_pem(&pem)
+        .map_err(|e| PackError::InvalidKey(format!("{}: {e}", path.display())))
+}
+
+/// Reads an Ed25519 public key in SPKI PEM form (`openssl pkey -pubout`).
+pub fn load_verifying_key(path: &Path) -> Result<VerifyingKey, PackError> {
+    let pem = std::fs::read_to_string(path)?;
+    VerifyingKey::from_public_key_pem(&pem)
+        .map_err(|e| PackError::InvalidKey(format!("{}: {e}", path.display())))
+}
+
+/// PEM text baked into the launcher for its own signature check.
+pub fn public_key_pem(key: &VerifyingKey) -> Result<String, PackError> {
+    key.to_public_key_pem(LineEnding::LF)
+        .map_err(|e| PackError::InvalidKey(e.to_string()))
+}
+
+/// Short identifier of a public key, shown in build and verify output.
+pub fn key_fingerprint(key: &VerifyingKey) -> String {
+    sha256_hex(key.as_bytes())[..16].to_string()
+}
+
+/// The signed message: full sha256 digests of the stub and every layer, one
+/// per line. The launcher rebuilds the exact same text to check it.
+pub fn payload_manifest(binary: &Path) -> Result<String, PackError> {
+    let layout = read_layout(binary)?;
+    let mut manifest = String::from("jbundle-manifest v1\n");
+    let stub = read_range(binary, 0, layout.stub_size)?;
+    manifest.push_str(&format!("stub {}\n", sha256_hex(&stub)));
+    for layer in &layout.layers {
+        let data = read_range(binary, layer.offset, layer.size)?;
+        manifest.push_str(&format!("{} {}\n", layer.name, sha256_hex(&data)));
+    }
+    Ok(manifest)
+}
+
+/// Signs the payload manifest and appends the signature trailer, replacing
+/// any previous signature.
+pub fn sign_binary(binary: &Path, key: &SigningKey) -> Result<(), PackError> {
+    let layout = read_layout(binary)?;
+    let signature = key.sign(payload_manifest(binary)?.as_bytes());
+
+    let file = std::fs::OpenOptions::new().write(true).open(binary)?;
+    file.set_len(layout.payload_end)?;
+    let mut file = std::io::BufWriter::new(file);
+    std::io::Seek::seek(&mut file, std::io::SeekFrom::End(0))?;
+    writeln!(file, "{SIGNATURE_PREFIX}{}", hex(&signature.to_bytes()))?;
+    file.flush()?;
+    Ok(())
+}
+
+/// Checks the signature trailer of a binary against `key`.
+pub fn verify_binary(binary: &Path, key: &VerifyingKey) -> Result<(), PackError> {
+    let layout = read_layout(binary)?;
+    let file_size = std::fs::metadata(binary)?.len();
+    if file_size != layout.payload_end + SIGNATURE_TRAILER_LEN as u64 {
+        return Err(PackError::SignatureInvalid(format!(
+            "{} is not signed",
+            binary.display()
+        )));
+    }
+
+    let trailer = read_range(binary, layout.payload_end, SIGNATURE_TRAILER_LEN as u64)?;
+    let signature = std::str::from_utf8(&trailer)
+        .ok()
+        .and_then(|t| t.strip_prefix(SIGNATURE_PREFIX))
+        .and_then(|t| unhex(t.trim_end()))
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+        .ok_or_else(|| {
+            PackError::SignatureInvalid(format!("{} has a malformed signature", binary.display()))
+        })?;
+
+    key.verify(payload_manifest(binary)?.as_bytes(), &signature)
+        .map_err(|_| {
+            PackError::SignatureInvalid(format!(
+                "{} was not signed by key {} or has been modified",
+                binary.display(),
+                key_fingerprint(key)
+            ))
+        })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn unhex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use ed25519_dalek::pkcs8::EncodePrivateKey;
+    use tempfile::tempdir;
+
+    /// Writes a fixed test key pair as PEM files, returning (private, public).
+    pub(crate) fn write_key_pair(dir: &Path, seed: u8) -> (std::path::PathBuf, std::path::PathBuf) {
+        let key = SigningKey::from_bytes(&[seed; 32]);
+        let private = dir.join(format!("key-{seed}.pem"));
+        let public = dir.join(format!("key-{seed}.pub.pem"));
+        std::fs::write(
+            &private,
+            key.to_pkcs8_pem(LineEnding::LF).unwrap().as_bytes(),
+        )
+        .unwrap();
+        std::fs::write(&public, public_key_pem(&key.verifying_key()).unwrap()).unwrap();
+        (private, public)
+    }
+
+    /// A minimal binary with one runtime and one app layer.
+    fn write_binary(path: &Path) {
+        let stub = crate::pack::stub::finalize_stub(
+            "#!/bin/sh\nRT_HASH=\"r\"    RT_SIZE=7\nAPP_HASH=\"a\"   APP_SIZE=3\nSTUB_SIZE=__STUB_SIZE__\n",
+        );
+        std::fs::write(path, format!("{stub}runtimeapp")).unwrap();
+    }
+
+    #[test]
+    fn sign_and_verify_roundtrip() {
+        let dir = tempdir().unwrap();
+        let (private, public) = write_key_pair(dir.path(), 1);
+        let binary = dir.path().join("app");
+        write_binary(&binary);
+
+        sign_binary(&binary, &load_signing_key(&private).unwrap()).unwrap();
+        verify_binary(&binary, &load_verifying_key(&public).unwrap()).unwrap();
+
+        // Re-signing replaces the trailer instead of stacking another one
+        sign_binary(&binary, &load_signing_key(&private).unwrap()).unwrap();
+        verify_binary(&binary, &load_verifying_key(&public).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn verify_rejects_other_key_and_tampering() {
+        let dir = tempdir().unwrap();
+        let (private, _) = write_key_pair(dir.path(), 1);
+        let (_, other_public) = write_key_pair(dir.path(), 2);
+        let binary = dir.path().join("app");
+        write_binary(&binary);
+        sign_binary(&binary, &load_signing_key(&private).unwrap()).unwrap();
+
+        let other = load_verifying_key(&other_public).unwrap();
+        assert!(matches!(
+            verify_binary(&binary, &other),
+            Err(PackError::SignatureInvalid(_))
+        ));
+
+        let public = load_signing_key(&private).unwrap().verifying_key();
+        let mut data = std::fs::read(&binary).unwrap();
+        let pos = data.windows(7).position(|w| w == b"runtime").unwrap();
+        data[pos] = b'R';
+        std::fs::write(&binary, data).unwrap();
+        assert!(matches!(
+            verify_binary(&binary, &public),
+            Err(PackError::SignatureInvalid(_))
+        ));
+    }
+
+    #[test]
+    fn verify_rejects_unsigned_binary() {
+        let dir = tempdir().unwrap();
+        let (private, _) = write_key_pair(dir.path(), 1);
+        let binary = dir.path().join("app");
+        write_binary(&binary);
+
+        let public = load_signing_key(&private).unwrap().verifying_key();
+        assert!(matches!(
+            verify_binary(&binary, &public),
+            Err(PackError::SignatureInvalid(_))
+        ));
+    }
+
+    #[test]
+    fn payload_manifest_lists_stub_and_layers() {
+        let dir = tempdir().unwrap();
+        let binary = dir.path().join("app");
+        write_binary(&binary);
+
+        let manifest = payload_manifest(&binary).unwrap();
+        let lines: Vec<&str> = manifest.lines().collect();
+        assert_eq!(lines[0], "jbundle-manifest v1");
+        assert!(lines[1].starts_with("stub "));
+        assert_eq!(lines[2], format!("runtime {}", sha256_hex(b"runtime")));
+        assert_eq!(lines[3], format!("app {}", sha256_hex(b"app")));
+    }
+}
@@ -0,0 +1,186 @@
+//! Smoke test of the packed binary, configured by `[test]` in jbundle.toml.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+
+use crate::error::PackError;
+
+/// Default time the binary gets, including first-run layer extraction.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Lines of output quoted when the test fails.
+const OUTPUT_TAIL: usize = 20;
+
+/// Runs the binary once with `args` and checks how it exited.
+#[derive(Debug, Clone)]
+pub struct SmokeTest {
+    pub args: Vec<String>,
+    pub expect_exit: i32,
+    pub expect_stdout: Option<Regex>,
+    pub timeout: Duration,
+}
+
+impl SmokeTest {
+    /// Runs the binary with an empty layer cache, so the extraction path is
+    /// exercised and stale layers from earlier builds can't hide a problem.
+    pub fn run(&self, binary: &Path) -> Result<(), PackError> {
+        let home = tempfile::tempdir()?;
+        let stdout_path = home.path().join("stdout");
+        let stderr_path = home.path().join("stderr");
+
+        tracing::info!("running: {} {}", binary.display(), self.args.join(" "));
+        let mut child = Command::new(binary)
+            .args(&self.args)
+            .env("HOME", home.path())
+            .stdin(Stdio::null())
+            .stdout(std::fs::File::create(&stdout_path)?)
+            .stderr(std::fs::File::create(&stderr_path)?)
+            .spawn()
+            .map_err(|e| {
+                PackError::SmokeTestFailed(format!("failed to run {}: {e}", binary.display()))
+            })?;
+
+        let started = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break Some(status);
+            }
+            if started.elapsed() >= self.timeout {
+                child.kill().ok();
+                child.wait().ok();
+                break None;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        };
+
+        let stdout = std::fs::read_to_string(&stdout_path).unwrap_or_default();
+        let stderr = std::fs::read_to_string(&stderr_path).unwrap_or_default();
+        let code = status.and_then(|s| s.code());
+
+        let problem = match (status, code) {
+            (None, _) => format!("timed out after {}s", self.timeout.as_secs()),
+            (Some(s), None) => format!("killed by a signal ({s})"),
+            (Some(_), Some(c)) if c != self.expect_exit => {
+                format!("exited with {c}, expected {}", self.expect_exit)
+            }
+            _ => match self.expect_stdout {
+                Some(ref re) if !re.is_match(&stdout) => {
+                    format!("stdout doesn't match /{}/", re.as_str())
+                }
+                _ => return Ok(()),
+            },
+        };
+
+        Err(PackError::SmokeTestFailed(failure_report(
+            &problem, &self.args, &stdout, &stderr,
+        )))
+    }
+}
+
+fn failure_report(problem: &str, args: &[String], stdout: &str, stderr: &str) -> String {
+    let mut report = format!("`app {}` {problem}", args.join(" "));
+    for (name, output) in [("stdout", stdout), ("stderr", stderr)] {
+        let lines: Vec<&str> = output.lines().collect();
+        if lines.is_empty() {
+            continue;
+        }
+        report.push_str(&format!("\n--- {name} ---\n"));
+        report.push_str(&lines[lines.len().saturating_sub(OUTPUT_TAIL)..].join("\n"));
+    }
+    if let Some(hint) = missing_module_hint(stderr) {
+        report.push_str(&format!("\nHint: {hint}"));
+    }
+    report
+}
+
+/// Recognizes failures caused by a module missing from the jlink runtime.
+fn missing_module_hint(stderr: &str) -> Option<String> {
+    let no_class = Regex::new(r"NoClassDefFoundError: ([\w/.$]+)").unwrap();
+    let no_module = Regex::new(r"[Mm]odule ([\w.]+) not found").unwrap();
+
+    let cause = if let Some(c) = no_module.captures(stderr) {
+        format!("module {} is not in the runtime", &c[1])
+    } else if let Some(c) = no_class.captures(stderr) {
+        format!("class {} could not be loaded", c[1].replace('/', "."))
+    } else {
+        return None;
+    };
+    Some(format!(
+        "{cause}. If it comes from the JDK, add its module to `modules` in jbundle.toml, \
+         or run `jbundle build --trace-modules` to record the modules the app loads"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hints_missing_class_and_module() {
+        let stderr =
+            "Exception in thread \"main\" java.lang.NoClassDefFoundError: java/sql/SQLException\n\
+                      \tat com.example.Main.main(Main.java:5)";
+        let hint = missing_module_hint(stderr).unwrap();
+        assert!(hint.starts_with("class java.sql.SQLException could not be loaded"));
+        assert!(hint.contains("`modules`"));
+
+        let stderr = "Error occurred during initialization of boot layer\n\
+                      java.lang.module.FindException: Module jdk.crypto.ec not found";
+        assert!(missing_module_hint(stderr)
+            .unwrap()
+            .starts_with("module jdk.crypto.ec is not in the runtime"));
+
+        assert!(missing_module_hint("Exception: connection refused").is_none());
+    }
+
+    #[test]
+    fn report_quotes_output_tail() {
+        let stderr: String = (1..=30).map(|i| format!("line {i}\n")).collect();
+        let report = failure_report(
+            "exited with 1, expected 0",
+            &["--version".to_string()],
+            "",
+            &stderr,
+        );
+        assert!(report.starts_with("`app --version` exited with 1, expected 0"));
+        assert!(!report.contains("--- stdout ---"));
+        assert!(report.contains("--- stderr ---\nline 11\n"));
+        assert!(report.ends_with("line 30"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_binary_and_checks_exit_and_stdout() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("app");
+        std::fs::write(&binary, "#!/bin/sh\necho \"app 1.2.3\"\nexit ${1:-0}\n").unwrap();
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let test = SmokeTest {
+            args: vec![],
+            expect_exit: 0,
+            expect_stdout: Some(Regex::new(r"^app \d+\.\d+\.\d+").unwrap()),
+            timeout: DEFAULT_TIMEOUT,
+        };
+        test.run(&binary).unwrap();
+
+        let failing = SmokeTest {
+            args: vec!["3".to_string()],
+            ..test.clone()
+        };
+        let err = failing.run(&binary).unwrap_err().to_string();
+        assert!(err.contains("exited with 3, expected 0"), "{err}");
+
+        let mismatch = SmokeTest {
+            expect_stdout: Some(Regex::new("^other").unwrap()),
+            ..test
+        };
+        let err = mismatch.run(&binary).unwrap_err().to_string();
+        assert!(err.contains("stdout doesn't match /^other/"), "{err}");
+    }
+}
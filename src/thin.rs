@@ -0,0 +1,418 @@
+use std::collections::HashSet;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::config::BuildSystem;
+use crate::error::PackError;
+
+/// Gradle init script adding a task that prints the main runtime classpath.
+const GRADLE_INIT_SCRIPT: &str = r#"allprojects {
+    tasks.register("jbundleClasspath") {
+        doLast {
+            def sourceSets = project.extensions.findByName("sourceSets")
+            def main = sourceSets?.findByName("main")
+            if (main != null) {
+                main.runtimeClasspath.files.each { println "JBUNDLE_CP=" + it }
+            }
+        }
+    }
+}
+"#;
+
+/// An uberjar split into project classes and its dependency JARs.
+pub struct ThinJar {
+    /// JAR with only the entries not provided by a dependency
+    pub app_jar: PathBuf,
+    pub main_class: String,
+    pub deps: Vec<PathBuf>,
+}
+
+/// Whether `resolve_classpath` can work for `system`. Bazel and Buck2 hand
+/// over a self-contained deploy JAR and have no classpath query to run.
+pub fn resolves_classpath(system: BuildSystem) -> bool {
+    !matches!(system, BuildSystem::Bazel | BuildSystem::Buck2)
+}
+
+/// Asks the build tool for the resolved runtime classpath and returns the
+/// dependency JARs on it (directories such as `target/classes` are dropped).
+/// `subproject` is the Gradle subproject, Maven module, Mill module or sbt
+/// subproject that was built.
+pub fn resolve_classpath(
+    project_dir: &Path,
+    system: BuildSystem,
+    subproject: Option<&str>,
+) -> Result<Vec<PathBuf>, PackError> {
+    let entries = match system {
+        BuildSystem::DepsEdn => {
+            let stdout = run_tool(project_dir, "clojure", &["-Spath"])?;
+            std::env::split_paths(stdout.trim()).collect::<Vec<_>>()
+        }
+        BuildSystem::Leiningen => {
+            let stdout = run_tool(
+                project_dir,
+                "lein",
+                &["with-profile", "production", "classpath"],
+            )?;
+            let line = stdout.lines().last().unwrap_or("");
+            std::env::split_paths(line.trim()).collect()
+        }
+        BuildSystem::Maven => {
+            let temp = tempfile::tempdir()?;
+            let out = temp.path().join("classpath.txt");
+            let output_file = format!("-Dmdep.outputFile={}", out.display());
+            let mut args = vec![
+                "-q",
+                "dependency:build-classpath",
+                "-Dmdep.includeScope=runtime",
+                &output_file,
+            ];
+            // With -am every module writes the file; the requested one comes last
+            if let Some(module) = subproject {
+                args.splice(1..1, ["-pl", module, "-am"]);
+            }
+            run_tool(project_dir, "mvn", &args)?;
+            let content = std::fs::read_to_string(&out)?;
+            std::env::split_paths(content.trim()).collect()
+        }
+        BuildSystem::Gradle => {
+            let temp = tempfile::tempdir()?;
+            let init = temp.path().join("jbundle-classpath.gradle");
+            std::fs::write(&init, GRADLE_INIT_SCRIPT)?;
+            let task = match subproject {
+                Some("(root)") | None => ":jbundleClasspath".to_string(),
+                Some(sub) => format!(":{sub}:jbundleClasspath"),
+            };
+            let cmd = if project_dir.join("gradlew").exists() {
+                "./gradlew"
+            } else {
+                "gradle"
+            };
+            let init_arg = init.display().to_string();
+            let stdout = run_tool(project_dir, cmd, &["-q", "--init-script", &init_arg, &task])?;
+            parse_gradle_classpath(&stdout)
+        }
+        BuildSystem::Sbt => {
+            let cmd = if project_dir.join("sbt").is_file() {
+                "./sbt"
+            } else {
+                "sbt"
+            };
+            let task = match subproject {
+                Some(p) => format!("export {p} / Runtime / fullClasspath"),
+                None => "export Runtime / fullClasspath".to_string(),
+            };
+            let stdout = run_tool(project_dir, cmd, &["--batch", "--error", &task])?;
+            let line = stdout.lines().rfind(|l| !l.trim().is_empty()).unwrap_or("");
+            std::env::split_paths(line.trim()).collect()
+        }
+        BuildSystem::Mill => {
+            let cmd = if project_dir.join("mill").is_file() {
+                "./mill"
+            } else {
+                "mill"
+            };
+            let module = subproject
+                .map(str::to_string)
+                .or_else(|| crate::build::detect_mill_module(project_dir));
+            let task = match module.as_deref() {
+                Some("") | None => "runClasspath".to_string(),
+                Some(m) => format!("{m}.runClasspath"),
+            };
+            let stdout = run_tool(project_dir, cmd, &["--disable-ticker", "show", &task])?;
+            parse_mill_classpath(&stdout)
+        }
+        BuildSystem::Bazel | BuildSystem::Buck2 => {
+            return Err(PackError::ThinFailed(format!(
+                "not supported for {system:?}; the deploy JAR is bundled whole"
+            )));
+        }
+    };
+
+    Ok(entries
+        .into_iter()
+        .filter(|p| p.extension().is_some_and(|e| e == "jar") && p.is_file())
+        .collect())
+}
+
+fn run_tool(project_dir: &Path, cmd: &str, args: &[&str]) -> Result<String, PackError> {
+    if !cmd.starts_with("./") {
+        which::which(cmd)
+            .map_err(|_| PackError::ThinFailed(format!("command '{cmd}' not found in PATH")))?;
+    }
+    tracing::info!("running: {cmd} {}", args.join(" "));
+
+    let output = Command::new(cmd)
+        .args(args)
+        .current_dir(project_dir)
+        .output()
+        .map_err(|e| PackError::ThinFailed(format!("failed to run {cmd}: {e}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(PackError::ThinFailed(format!(
+            "could not resolve classpath with {cmd}:\n{}",
+            stderr.trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn parse_gradle_classpath(stdout: &str) -> Vec<PathBuf> {
+    stdout
+        .lines()
+        .filter_map(|l| l.trim().strip_prefix("JBUNDLE_CP="))
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// `mill show <module>.runClasspath` prints a JSON array of path refs such as
+/// `"qref:v1:1a2b3c4d:/home/me/.cache/coursier/.../cats-core_3-2.10.0.jar"`.
+fn parse_mill_classpath(stdout: &str) -> Vec<PathBuf> {
+    let refs: Vec<String> = match serde_json::from_str(stdout.trim()) {
+        Ok(refs) => refs,
+        Err(_) => return Vec::new(),
+    };
+    refs.iter()
+        .map(|r| {
+            if r.starts_with("ref:") || r.starts_with("qref:") {
+                r.splitn(4, ':').nth(3).unwrap_or(r)
+            } else {
+                r.as_str()
+            }
+        })
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Writes a copy of `uberjar` without the entries already provided by `deps`.
+/// An entry only counts as provided when a dependency has it with the same
+/// CRC-32 and size; the project's own `logback.xml` or a patched class differ
+/// from the dependency's and are kept (the app JAR comes first on `-cp`).
+/// The manifest is always kept; its `Main-Class` is used to launch with `-cp`.
+pub fn split_uberjar(
+    uberjar: &Path,
+    deps: &[PathBuf],
+    work_dir: &Path,
+) -> Result<ThinJar, PackError> {
+    let mut provided = HashSet::new();
+    for dep in deps {
+        let mut archive = ZipArchive::new(std::fs::File::open(dep)?)?;
+        for i in 0..archive.len() {
+            let entry = archive.by_index_raw(i)?;
+            provided.insert((entry.name().to_string(), entry.crc32(), entry.size()));
+        }
+    }
+
+    let mut archive = ZipArchive::new(std::fs::File::open(uberjar)?)?;
+    let main_class = read_main_class(&mut archive)?.ok_or_else(|| {
+        PackError::ThinFailed(format!(
+            "{} has no Main-Class in its manifest",
+            uberjar.display()
+        ))
+    })?;
+
+    let app_jar = work_dir.join("app-thin.jar");
+    let mut writer = ZipWriter::new(std::fs::File::create(&app_jar)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut buf = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let name = entry.name().to_string();
+        if name != "META-INF/MANIFEST.MF"
+            && provided.contains(&(name.clone(), entry.crc32(), entry.size()))
+        {
+            continue;
+        }
+        if entry.is_dir() {
+            writer.add_directory(&name, options)?;
+        } else {
+            buf.clear();
+            entry.read_to_end(&mut buf)?;
+            writer.start_file(&name, options)?;
+            writer.write_all(&buf)?;
+        }
+    }
+    writer.finish()?;
+
+    Ok(ThinJar {
+        app_jar,
+        main_class,
+        deps: deps.to_vec(),
+    })
+}
+
+/// `Main-Class` from the JAR's manifest.
+pub fn jar_main_class(jar: &Path) -> Result<Option<String>, PackError> {
+    read_main_class(&mut ZipArchive::new(std::fs::File::open(jar)?)?)
+}
+
+fn read_main_class(archive: &mut ZipArchive<std::fs::File>) -> Result<Option<String>, PackError> {
+    let mut manifest = match archive.by_name("META-INF/MANIFEST.MF") {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut content = String::new();
+    manifest.read_to_string(&mut content)?;
+    Ok(content
+        .lines()
+        .find_map(|l| l.strip_prefix("Main-Class:"))
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn create_jar(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut zip = ZipWriter::new(std::fs::File::create(path).unwrap());
+        for (name, content) in entries {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    fn entry_names(path: &Path) -> Vec<String> {
+        let archive = ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+        archive.file_names().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn split_keeps_only_project_entries() {
+        let dir = tempdir().unwrap();
+        let dep = dir.path().join("lib.jar");
+        create_jar(
+            &dep,
+            &[
+                ("META-INF/MANIFEST.MF", b"Manifest-Version: 1.0\n"),
+                ("org/lib/Util.class", b"lib"),
+            ],
+        );
+        let uber = dir.path().join("app.jar");
+        create_jar(
+            &uber,
+            &[
+                (
+                    "META-INF/MANIFEST.MF",
+                    b"Manifest-Version: 1.0\nMain-Class: com.example.Main\n",
+                ),
+                ("com/example/Main.class", b"main"),
+                ("org/lib/Util.class", b"lib"),
+            ],
+        );
+
+        let thin = split_uberjar(&uber, std::slice::from_ref(&dep), dir.path()).unwrap();
+        assert_eq!(thin.main_class, "com.example.Main");
+        assert_eq!(thin.deps, vec![dep]);
+
+        let names = entry_names(&thin.app_jar);
+        assert!(names.contains(&"META-INF/MANIFEST.MF".to_string()));
+        assert!(names.contains(&"com/example/Main.class".to_string()));
+        assert!(!names.contains(&"org/lib/Util.class".to_string()));
+    }
+
+    #[test]
+    fn split_keeps_project_files_that_differ_from_deps() {
+        let dir = tempdir().unwrap();
+        let dep = dir.path().join("logging.jar");
+        create_jar(
+            &dep,
+            &[
+                ("logback.xml", b"<configuration debug=\"true\"/>"),
+                ("org/log/Logger.class", b"logger"),
+            ],
+        );
+        let uber = dir.path().join("app.jar");
+        create_jar(
+            &uber,
+            &[
+                (
+                    "META-INF/MANIFEST.MF",
+                    b"Manifest-Version: 1.0\nMain-Class: com.example.Main\n",
+                ),
+                (
+                    "logback.xml",
+                    b"<configuration><root level=\"warn\"/></configuration>",
+                ),
+                ("org/log/Logger.class", b"logger"),
+            ],
+        );
+
+        let thin = split_uberjar(&uber, &[dep], dir.path()).unwrap();
+        let names = entry_names(&thin.app_jar);
+        assert!(!names.contains(&"org/log/Logger.class".to_string()));
+
+        let mut archive = ZipArchive::new(std::fs::File::open(&thin.app_jar).unwrap()).unwrap();
+        let mut content = String::new();
+        archive
+            .by_name("logback.xml")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert!(content.contains("level=\"warn\""));
+    }
+
+    #[test]
+    fn split_requires_main_class() {
+        let dir = tempdir().unwrap();
+        let uber = dir.path().join("app.jar");
+        create_jar(
+            &uber,
+            &[("META-INF/MANIFEST.MF", b"Manifest-Version: 1.0\n")],
+        );
+
+        let result = split_uberjar(&uber, &[], dir.path());
+        assert!(matches!(result, Err(PackError::ThinFailed(_))));
+    }
+
+    #[test]
+    fn deploy_jar_builds_have_no_classpath() {
+        assert!(resolves_classpath(BuildSystem::Gradle));
+        assert!(!resolves_classpath(BuildSystem::Bazel));
+        assert!(!resolves_classpath(BuildSystem::Buck2));
+
+        let dir = tempdir().unwrap();
+        let result = resolve_classpath(dir.path(), BuildSystem::Bazel, None);
+        assert!(matches!(result, Err(PackError::ThinFailed(_))));
+    }
+
+    #[test]
+    fn parse_gradle_classpath_ignores_other_output() {
+        let stdout = "Configuration on demand is an incubating feature.\n\
+                      JBUNDLE_CP=/home/u/.gradle/caches/guava.jar\n\
+                      JBUNDLE_CP=/proj/build/classes/java/main\n";
+        assert_eq!(
+            parse_gradle_classpath(stdout),
+            vec![
+                PathBuf::from("/home/u/.gradle/caches/guava.jar"),
+                PathBuf::from("/proj/build/classes/java/main"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_mill_classpath_strips_refs() {
+        let stdout = r#"[
+  "qref:v1:1a2b3c4d:/home/u/.cache/coursier/cats-core_3-2.10.0.jar",
+  "ref:v0:5e6f7a8b:/proj/out/app/compile.dest/classes",
+  "/proj/app/resources"
+]"#;
+        assert_eq!(
+            parse_mill_classpath(stdout),
+            vec![
+                PathBuf::from("/home/u/.cache/coursier/cats-core_3-2.10.0.jar"),
+                PathBuf::from("/proj/out/app/compile.dest/classes"),
+                PathBuf::from("/proj/app/resources"),
+            ]
+        );
+        assert!(parse_mill_classpath("not json").is_empty());
+    }
+}
@@ -1,25 +1,74 @@
-use std::io::Read;
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read, Seek};
 use std::path::Path;
 
 use indicatif::MultiProgress;
 
 use crate::error::PackError;
 
+/// Origins listed when reporting what forces the detected version
+const MAX_ORIGINS_SHOWN: usize = 5;
+
 struct ClassVersionInfo {
     major_version: u16,
     java_version: u8,
     class_file: String,
 }
 
+/// Class versions found in a JAR.
+#[derive(Default)]
+struct VersionScan {
+    /// Class with the highest required version
+    max: Option<ClassVersionInfo>,
+    /// `Main-Class` (or Spring Boot `Start-Class`) and its class version
+    main_class: Option<(String, u16)>,
+    /// Highest class version per origin: a nested JAR, or the top package
+    origins: BTreeMap<String, u16>,
+}
+
+impl VersionScan {
+    fn record(&mut self, origin: String, class_file: String, major: u16) {
+        let highest = self.origins.entry(origin).or_insert(major);
+        *highest = (*highest).max(major);
+        if self.max.as_ref().is_none_or(|m| major > m.major_version) {
+            self.max = Some(ClassVersionInfo {
+                major_version: major,
+                java_version: java_version_of(major),
+                class_file,
+            });
+        }
+    }
+
+    /// Origins whose classes need the highest version, e.g. "org.eclipse.jetty, guava-33.jar".
+    fn required_by(&self, major: u16) -> String {
+        let origins: Vec<&str> = self
+            .origins
+            .iter()
+            .filter(|(_, v)| **v == major)
+            .map(|(o, _)| o.as_str())
+            .collect();
+        let mut list = origins
+            .iter()
+            .take(MAX_ORIGINS_SHOWN)
+            .copied()
+            .collect::<Vec<_>>()
+            .join(", ");
+        if origins.len() > MAX_ORIGINS_SHOWN {
+            list.push_str(&format!(" and {} more", origins.len() - MAX_ORIGINS_SHOWN));
+        }
+        list
+    }
+}
+
 pub fn resolve_java_version(
     jar_path: &Path,
     configured: u8,
     explicit: bool,
     mp: &MultiProgress,
 ) -> Result<u8, PackError> {
-    let info = detect_max_class_version(jar_path)?;
+    let scan = detect_class_versions(jar_path)?;
 
-    let Some(info) = info else {
+    let Some(ref info) = scan.max else {
         return Ok(configured);
     };
 
@@ -27,12 +76,14 @@ pub fn resolve_java_version(
         return Ok(configured);
     }
 
+    let required_by = scan.required_by(info.major_version);
+
     if explicit {
         return Err(PackError::JavaVersionMismatch {
             required: info.java_version,
             configured,
             class_version: info.major_version,
-            class_file: info.class_file,
+            class_file: format!("{} (required by {})", info.class_file, required_by),
         });
     }
 
@@ -41,57 +92,178 @@ pub fn resolve_java_version(
         "  \x1b[33mℹ\x1b[0m Auto-detected Java {} (class version {} in {})",
         info.java_version, info.major_version, info.class_file
     );
+    eprintln!("    Required by: {required_by}");
+    if let Some((main_class, major)) = &scan.main_class {
+        if *major < info.major_version {
+            eprintln!(
+                "    Main-Class {} itself targets Java {} (Java {} comes from the classes above)",
+                main_class,
+                java_version_of(*major),
+                info.java_version
+            );
+        }
+    }
 
     Ok(info.java_version)
 }
 
-fn detect_max_class_version(jar_path: &Path) -> Result<Option<ClassVersionInfo>, PackError> {
+fn java_version_of(major: u16) -> u8 {
+    major.saturating_sub(44) as u8
+}
+
+/// Scans every class of the JAR, and of the JARs nested in a Spring Boot JAR.
+fn detect_class_versions(jar_path: &Path) -> Result<VersionScan, PackError> {
     let file = std::fs::File::open(jar_path)?;
     let mut archive = zip::ZipArchive::new(file)?;
+    let mut scan = VersionScan::default();
+
+    let is_boot = crate::boot::is_boot_archive(&archive);
+    let manifest = read_manifest(&mut archive)?;
+    let main_class = manifest_attribute(&manifest, "Start-Class")
+        .filter(|_| is_boot)
+        .or_else(|| manifest_attribute(&manifest, "Main-Class"));
+    let main_class_entry = main_class.as_ref().map(|c| {
+        let path = format!("{}.class", c.replace('.', "/"));
+        if is_boot {
+            format!("{}{}", crate::boot::CLASSES_PREFIX, path)
+        } else {
+            path
+        }
+    });
 
-    let mut max: Option<ClassVersionInfo> = None;
-    let mut checked = 0u32;
-    let limit = 200;
+    let main_version = scan_archive(&mut archive, &manifest, None, &mut scan, |name| {
+        main_class_entry.as_deref() == Some(name)
+    })?;
+    if let (Some(class), Some(major)) = (main_class, main_version) {
+        scan.main_class = Some((class, major));
+    }
 
-    for i in 0..archive.len() {
-        if checked >= limit {
-            break;
+    // Spring Boot: dependencies are nested JARs under BOOT-INF/lib/
+    if is_boot {
+        let nested: Vec<String> = archive
+            .file_names()
+            .filter(|n| crate::boot::is_nested_jar(n))
+            .map(str::to_string)
+            .collect();
+        for name in nested {
+            let mut buf = Vec::new();
+            archive.by_name(&name)?.read_to_end(&mut buf)?;
+            let Ok(mut inner) = zip::ZipArchive::new(Cursor::new(buf)) else {
+                continue;
+            };
+            let inner_manifest = read_manifest(&mut inner)?;
+            scan_archive(&mut inner, &inner_manifest, Some(&name), &mut scan, |_| {
+                false
+            })?;
         }
+    }
+
+    Ok(scan)
+}
+
+/// Records the class versions of an archive. `nested` is the path of a nested
+/// JAR, used as the origin of its classes. Returns the version of the class
+/// `is_main` picks out.
+fn scan_archive<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    manifest: &str,
+    nested: Option<&str>,
+    scan: &mut VersionScan,
+    is_main: impl Fn(&str) -> bool,
+) -> Result<Option<u16>, PackError> {
+    let multi_release = manifest_attribute(manifest, "Multi-Release")
+        .is_some_and(|v| v.eq_ignore_ascii_case("true"));
+    let mut main_version = None;
 
+    for i in 0..archive.len() {
         let mut entry = archive.by_index(i)?;
         let name = entry.name().to_string();
 
         if !name.ends_with(".class") {
             continue;
         }
-        if name.starts_with("META-INF/versions/") {
+        // Only read by the module system, never loaded from the classpath
+        if name.ends_with("module-info.class") {
             continue;
         }
 
-        checked += 1;
+        // META-INF/versions/<N>/ entries are only loaded on Java N+, and only in
+        // Multi-Release JARs; elsewhere the JVM ignores them
+        let (class_path, release) = match versioned_entry(&name) {
+            Some(_) if !multi_release => continue,
+            Some((release, path)) => (path, Some(release)),
+            None => (name.as_str(), None),
+        };
 
         let mut buf = [0u8; 8];
         if entry.read_exact(&mut buf).is_err() {
             continue;
         }
-
         let Some(major) = read_class_major_version(&buf) else {
             continue;
         };
 
-        let java_ver = major.saturating_sub(44) as u8;
+        // A versioned class only raises the requirement when it was compiled
+        // for a newer release than its directory says
+        if release.is_some_and(|r| java_version_of(major) <= r) {
+            continue;
+        }
 
-        let dominated = max.as_ref().is_some_and(|m| m.major_version >= major);
-        if !dominated {
-            max = Some(ClassVersionInfo {
-                major_version: major,
-                java_version: java_ver,
-                class_file: name,
-            });
+        if is_main(&name) {
+            main_version = Some(major);
         }
+
+        let origin = match nested {
+            Some(jar) => jar.rsplit('/').next().unwrap_or(jar).to_string(),
+            None => top_package(class_path),
+        };
+        let class_file = match nested {
+            Some(jar) => format!("{jar}!/{name}"),
+            None => name.clone(),
+        };
+        scan.record(origin, class_file, major);
     }
 
-    Ok(max)
+    Ok(main_version)
+}
+
+/// `META-INF/versions/17/com/Foo.class` -> `(17, "com/Foo.class")`
+fn versioned_entry(name: &str) -> Option<(u8, &str)> {
+    let rest = name.strip_prefix("META-INF/versions/")?;
+    let (release, path) = rest.split_once('/')?;
+    Some((release.parse().ok()?, path))
+}
+
+/// First three package segments, e.g. `org.eclipse.jetty`.
+fn top_package(class_path: &str) -> String {
+    let path = class_path
+        .strip_prefix(crate::boot::CLASSES_PREFIX)
+        .unwrap_or(class_path);
+    let parts: Vec<&str> = path.split('/').collect();
+    let depth = parts.len().saturating_sub(1).min(3);
+    if depth == 0 {
+        return "(default package)".to_string();
+    }
+    parts[..depth].join(".")
+}
+
+fn read_manifest<R: Read + Seek>(archive: &mut zip::ZipArchive<R>) -> Result<String, PackError> {
+    let mut manifest = match archive.by_name("META-INF/MANIFEST.MF") {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(String::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut content = String::new();
+    manifest.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+fn manifest_attribute(manifest: &str, key: &str) -> Option<String> {
+    manifest
+        .lines()
+        .find_map(|l| l.strip_prefix(key)?.strip_prefix(':'))
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
 }
 
 fn read_class_major_version(data: &[u8]) -> Option<u16> {
@@ -215,6 +387,118 @@ mod tests {
         assert_eq!(result, 11);
     }
 
+    #[test]
+    fn scans_spring_boot_nested_jars() {
+        let mut nested = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        nested
+            .start_file("org/lib/Util.class", SimpleFileOptions::default())
+            .unwrap();
+        nested.write_all(&make_class_bytes(65)).unwrap();
+        let nested = nested.finish().unwrap().into_inner();
+
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(tmp.path()).unwrap());
+        zip.start_file(
+            "BOOT-INF/classes/com/example/App.class",
+            SimpleFileOptions::default(),
+        )
+        .unwrap();
+        zip.write_all(&make_class_bytes(61)).unwrap();
+        let stored =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        zip.start_file("BOOT-INF/lib/lib-1.0.jar", stored).unwrap();
+        zip.write_all(&nested).unwrap();
+        zip.finish().unwrap();
+
+        let info = detect_class_versions(tmp.path()).unwrap().max.unwrap();
+        assert_eq!(info.java_version, 21);
+        assert_eq!(
+            info.class_file,
+            "BOOT-INF/lib/lib-1.0.jar!/org/lib/Util.class"
+        );
+    }
+
+    fn create_jar_with_manifest(
+        manifest: &str,
+        classes: &[(&str, u16)],
+    ) -> tempfile::NamedTempFile {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(tmp.path()).unwrap());
+        let options = SimpleFileOptions::default();
+        zip.start_file("META-INF/MANIFEST.MF", options).unwrap();
+        zip.write_all(manifest.as_bytes()).unwrap();
+        for (name, major) in classes {
+            zip.start_file(name.to_string(), options).unwrap();
+            zip.write_all(&make_class_bytes(*major)).unwrap();
+        }
+        zip.finish().unwrap();
+        tmp
+    }
+
+    #[test]
+    fn multi_release_entries_only_count_when_newer_than_their_release() {
+        let manifest = "Manifest-Version: 1.0\nMulti-Release: true\n";
+        let jar = create_jar_with_manifest(
+            manifest,
+            &[
+                ("com/example/Main.class", 55),             // Java 11
+                ("META-INF/versions/21/com/Foo.class", 65), // loaded on 21+ only
+            ],
+        );
+        let mp = MultiProgress::new();
+        assert_eq!(
+            resolve_java_version(jar.path(), 11, false, &mp).unwrap(),
+            11
+        );
+
+        // Compiled for 21 but placed where Java 17 would load it
+        let jar = create_jar_with_manifest(
+            manifest,
+            &[
+                ("com/example/Main.class", 55),
+                ("META-INF/versions/17/com/Foo.class", 65),
+            ],
+        );
+        let scan = detect_class_versions(jar.path()).unwrap();
+        let max = scan.max.unwrap();
+        assert_eq!(max.java_version, 21);
+        assert_eq!(max.class_file, "META-INF/versions/17/com/Foo.class");
+    }
+
+    #[test]
+    fn scans_every_class() {
+        // The newest class sits after hundreds of older library classes
+        let mut classes: Vec<(String, u16)> = (0..500)
+            .map(|i| (format!("org/lib/C{i}.class"), 52))
+            .collect();
+        classes.push(("com/example/app/Main.class".to_string(), 65));
+        let refs: Vec<(&str, u16)> = classes.iter().map(|(n, v)| (n.as_str(), *v)).collect();
+        let jar = create_test_jar(&refs);
+        let scan = detect_class_versions(jar.path()).unwrap();
+        assert_eq!(scan.max.unwrap().java_version, 21);
+    }
+
+    #[test]
+    fn reports_origin_and_main_class() {
+        let jar = create_jar_with_manifest(
+            "Main-Class: com.example.app.Main\n",
+            &[
+                ("com/example/app/Main.class", 61),     // Java 17
+                ("org/eclipse/jetty/Server.class", 65), // Java 21
+                ("org/eclipse/jetty/util/Uri.class", 65),
+                ("module-info.class", 66),
+            ],
+        );
+        let scan = detect_class_versions(jar.path()).unwrap();
+        let max = scan.max.as_ref().unwrap();
+        assert_eq!(max.major_version, 65);
+        assert_eq!(scan.required_by(65), "org.eclipse.jetty");
+        assert_eq!(
+            scan.main_class,
+            Some(("com.example.app.Main".to_string(), 61))
+        );
+    }
+
     #[test]
     fn skips_invalid_magic() {
         let tmp = tempfile::NamedTempFile::new().unwrap();